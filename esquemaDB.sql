@@ -64,36 +64,7 @@ CREATE TABLE productos_vendidos (
 CREATE INDEX IF NOT EXISTS idx_prodvend_venta ON productos_vendidos(id_venta);
 CREATE INDEX IF NOT EXISTS idx_prodvend_producto ON productos_vendidos(id_producto);
 
--- Triggers para mantener el stock automáticamente
--- Reducir stock al insertar un item de venta
-CREATE TRIGGER IF NOT EXISTS trg_prodvend_after_insert
-AFTER INSERT ON productos_vendidos
-BEGIN
-  UPDATE productos
-  SET stock = stock - NEW.cantidad,
-      actualizado_at = datetime('now','localtime')
-  WHERE id_producto = NEW.id_producto;
-END;
-
--- Restaurar stock al eliminar un item de venta
-CREATE TRIGGER IF NOT EXISTS trg_prodvend_after_delete
-AFTER DELETE ON productos_vendidos
-BEGIN
-  UPDATE productos
-  SET stock = stock + OLD.cantidad,
-      actualizado_at = datetime('now','localtime')
-  WHERE id_producto = OLD.id_producto;
-END;
-
--- Ajustar stock si se actualiza la cantidad (restar diferencia)
-CREATE TRIGGER IF NOT EXISTS trg_prodvend_after_update
-AFTER UPDATE OF cantidad ON productos_vendidos
-BEGIN
-  -- Si cantidad cambia, restar la diferencia: NEW.cantidad - OLD.cantidad
-  UPDATE productos
-  SET stock = stock - (NEW.cantidad - OLD.cantidad),
-      actualizado_at = datetime('now','localtime')
-  WHERE id_producto = NEW.id_producto;
-END;
+-- Los triggers que mantienen el stock, los lotes y el kardex los crea database.rs
+-- (sql_triggers_inventario) al final de las migraciones
 
 ALTER TABLE productos ADD COLUMN miniatura_base64 TEXT;
\ No newline at end of file
@@ -0,0 +1,104 @@
+use std::path::PathBuf;
+use tauri::State;
+use crate::database;
+use crate::models::{Campana, CampanaInput, PrecioCampana, PrecioCampanaInput, PrecioVigente};
+use crate::services::campana_service::CampanaService;
+
+// ============== Campañas ==============
+#[tauri::command]
+pub fn list_campanas(db_path: State<'_, PathBuf>) -> Result<Vec<Campana>, String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = CampanaService::new(&conn);
+    service.listar_campanas().map_err(|e| e.to_string())
+}
+
+// Campaña vigente en la fecha indicada (hoy si no se indica)
+#[tauri::command]
+pub fn get_campana_vigente(fecha: Option<String>, db_path: State<'_, PathBuf>) -> Result<Option<Campana>, String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = CampanaService::new(&conn);
+    service.get_campana_vigente(fecha.as_deref()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn create_campana(input: CampanaInput, db_path: State<'_, PathBuf>) -> Result<i64, String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let mut conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let service = CampanaService::new(&tx);
+    let id_campana = service.create_campana(&input).map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| format!("Error al confirmar la transacción: {}", e))?;
+    Ok(id_campana)
+}
+
+#[tauri::command]
+pub fn update_campana(id_campana: i64, input: CampanaInput, db_path: State<'_, PathBuf>) -> Result<(), String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let mut conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let service = CampanaService::new(&tx);
+    service.update_campana(id_campana, &input).map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| format!("Error al confirmar la transacción: {}", e))
+}
+
+#[tauri::command]
+pub fn delete_campana(id_campana: i64, db_path: State<'_, PathBuf>) -> Result<(), String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = CampanaService::new(&conn);
+    service.delete_campana(id_campana).map_err(|e| e.to_string())
+}
+
+// ============== Precios de campaña ==============
+#[tauri::command]
+pub fn list_precios_campana(id_campana: i64, db_path: State<'_, PathBuf>) -> Result<Vec<PrecioCampana>, String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = CampanaService::new(&conn);
+    service.listar_precios(id_campana).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn set_precio_campana(id_campana: i64, input: PrecioCampanaInput, db_path: State<'_, PathBuf>) -> Result<(), String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = CampanaService::new(&conn);
+    service.set_precio(id_campana, &input).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn quitar_precio_campana(id_campana: i64, id_producto: i64, db_path: State<'_, PathBuf>) -> Result<(), String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = CampanaService::new(&conn);
+    service.quitar_precio(id_campana, id_producto).map_err(|e| e.to_string())
+}
+
+// Precio que se propone al vender: el de la campaña (indicada o vigente en la fecha) o el del producto
+#[tauri::command]
+pub fn get_precio_vigente(
+    id_producto: i64,
+    id_campana: Option<i64>,
+    fecha: Option<String>,
+    db_path: State<'_, PathBuf>,
+) -> Result<PrecioVigente, String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = CampanaService::new(&conn);
+    service
+        .precio_vigente(id_producto, id_campana, fecha.as_deref())
+        .map_err(|e| e.to_string())
+}
@@ -1,7 +1,7 @@
 use std::path::PathBuf;
 use tauri::State;
 use crate::database;
-use crate::models::Categoria;
+use crate::models::{Categoria, CategoriaNodo, ImpactoCategoria};
 use crate::services::categoria_service::CategoriaService;
 
 #[tauri::command]
@@ -23,12 +23,40 @@ pub fn get_categoria(id: i64, db_path: State<'_, PathBuf>) -> Result<Categoria,
 }
 
 #[tauri::command]
-pub fn create_categoria(nombre: String, db_path: State<'_, PathBuf>) -> Result<i64, String> 
+pub fn create_categoria(nombre: String, id_categoria_padre: Option<i64>, db_path: State<'_, PathBuf>) -> Result<i64, String> 
+{
+    let db_path = db_path.inner();
+    let mut conn = database::open_connection(db_path).map_err(|e| e.to_string())?;
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let service = CategoriaService::new(&tx);
+    let id = match id_categoria_padre {
+        Some(id_padre) => service.create_subcategoria(&nombre, id_padre),
+        None => service.create_categoria(&nombre),
+    }
+    .map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| format!("Error al confirmar la transacción: {}", e))?;
+    Ok(id)
+}
+
+// Árbol de categorías con la ruta completa de cada una
+#[tauri::command]
+pub fn get_arbol_categorias(db_path: State<'_, PathBuf>) -> Result<Vec<CategoriaNodo>, String>
+{
+    let db_path = db_path.inner();
+    let conn = database::open_connection(db_path).map_err(|e| e.to_string())?;
+    let service = CategoriaService::new(&conn);
+    service.arbol_categorias().map_err(|e| e.to_string())
+}
+
+// Cambia la categoría padre; None la deja como categoría raíz
+#[tauri::command]
+pub fn mover_categoria(id: i64, id_categoria_padre: Option<i64>, db_path: State<'_, PathBuf>) -> Result<(), String>
 {
     let db_path = db_path.inner();
     let conn = database::open_connection(db_path).map_err(|e| e.to_string())?;
     let service = CategoriaService::new(&conn);
-    service.create_categoria(&nombre).map_err(|e| e.to_string())
+    service.mover_categoria(id, id_categoria_padre).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -41,11 +69,57 @@ pub fn update_categoria(categoria: Categoria, db_path: State<'_, PathBuf>) -> Re
 }
 
 #[tauri::command]
-pub fn delete_categoria(id: i64, db_path: State<'_, PathBuf>) -> Result<(), String> 
+pub fn delete_categoria(
+    id: i64,
+    id_categoria_destino: Option<i64>,
+    db_path: State<'_, PathBuf>,
+) -> Result<ImpactoCategoria, String> 
+{
+    let db_path = db_path.inner();
+    let mut conn = database::open_connection(db_path).map_err(|e| e.to_string())?;
+
+    // Productos, promociones y subcategorías se reacomodan en la misma transacción
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let service = CategoriaService::new(&tx);
+    let impacto = service
+        .delete_categoria(id, id_categoria_destino)
+        .map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| format!("Error al confirmar la transacción: {}", e))?;
+    Ok(impacto)
+}
+
+// Productos activos, descontinuados, subcategorías y promociones que afectaría eliminar la categoría
+#[tauri::command]
+pub fn get_impacto_eliminar_categoria(id: i64, db_path: State<'_, PathBuf>) -> Result<ImpactoCategoria, String>
 {
     let db_path = db_path.inner();
     let conn = database::open_connection(db_path).map_err(|e| e.to_string())?;
     let service = CategoriaService::new(&conn);
-    service.delete_categoria(id).map_err(|e| e.to_string())
+    service.impacto_eliminacion(id).map_err(|e| e.to_string())
 }
 
+// Fusiona `id_origen` en `id_destino` y elimina el origen
+#[tauri::command]
+pub fn merge_categorias(id_origen: i64, id_destino: i64, db_path: State<'_, PathBuf>) -> Result<ImpactoCategoria, String>
+{
+    let db_path = db_path.inner();
+    let mut conn = database::open_connection(db_path).map_err(|e| e.to_string())?;
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let service = CategoriaService::new(&tx);
+    let impacto = service
+        .merge_categorias(id_origen, id_destino)
+        .map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| format!("Error al confirmar la transacción: {}", e))?;
+    Ok(impacto)
+}
+
+// Stock mínimo por defecto de la categoría; None lo desactiva
+#[tauri::command]
+pub fn set_stock_minimo_categoria(id: i64, stock_minimo: Option<i64>, db_path: State<'_, PathBuf>) -> Result<(), String>
+{
+    let db_path = db_path.inner();
+    let conn = database::open_connection(db_path).map_err(|e| e.to_string())?;
+    let service = CategoriaService::new(&conn);
+    service.set_stock_minimo_default(id, stock_minimo).map_err(|e| e.to_string())
+}
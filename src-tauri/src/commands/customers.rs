@@ -0,0 +1,74 @@
+use std::path::PathBuf;
+use tauri::State;
+use crate::database;
+use crate::models::{Clienta, ClientaInput, EstadoCuentaClienta};
+use crate::services::clienta_service::ClientaService;
+use crate::services::estado_cuenta_service::EstadoCuentaService;
+
+#[tauri::command]
+pub fn list_clientas(db_path: State<'_, PathBuf>) -> Result<Vec<Clienta>, String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = ClientaService::new(&conn);
+    service.list_clientas().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn buscar_clientas(texto: String, db_path: State<'_, PathBuf>) -> Result<Vec<Clienta>, String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = ClientaService::new(&conn);
+    service.buscar_clientas(&texto).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_clienta(id: i64, db_path: State<'_, PathBuf>) -> Result<Clienta, String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = ClientaService::new(&conn);
+    service.get_clienta(id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn create_clienta(input: ClientaInput, db_path: State<'_, PathBuf>) -> Result<i64, String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = ClientaService::new(&conn);
+    service.create_clienta(&input).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn update_clienta(clienta: Clienta, db_path: State<'_, PathBuf>) -> Result<(), String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let mut conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+
+    // El cambio de nombre también se refleja en las ventas de la clienta
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let service = ClientaService::new(&tx);
+    service.update_clienta(&clienta).map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn delete_clienta(id: i64, db_path: State<'_, PathBuf>) -> Result<(), String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = ClientaService::new(&conn);
+    service.delete_clienta(id).map_err(|e| e.to_string())
+}
+
+// Estado de cuenta: ventas, abonos y saldo acumulado de una clienta
+#[tauri::command]
+pub fn get_estado_cuenta_clienta(id_clienta: i64, db_path: State<'_, PathBuf>) -> Result<EstadoCuentaClienta, String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = EstadoCuentaService::new(&conn);
+    service.get_estado_cuenta(id_clienta).map_err(|e| e.to_string())
+}
@@ -5,6 +5,11 @@ use rusqlite::params;
 use rust_xlsxwriter::*;
 
 use crate::database;
+use crate::models::{EtiquetaInput, FormatoEtiquetas};
+use crate::repos::categoria_repo::RUTAS_CATEGORIA_CTE;
+use crate::repos::producto_vendido_repo::ProductoVendidoRepo;
+use crate::services::estado_cuenta_service::EstadoCuentaService;
+use crate::services::etiqueta_service::EtiquetaService;
 
 /// ─── Exportar TODOS los datos a un archivo XLSX ───
 ///
@@ -12,41 +17,54 @@ use crate::database;
 /// y centrados, separadas por una columna vacía entre cada tabla.
 ///
 /// Layout de columnas:
-///   CATEGORIAS (2 cols) | gap | PRODUCTOS (9 cols) | gap | VENTAS (6 cols) | gap | PRODUCTOS_VENDIDOS (7 cols)
+///   CATEGORIAS (3 cols) | gap | PRODUCTOS (10 cols) | gap | VENTAS (7 cols) | gap | PRODUCTOS_VENDIDOS (8 cols)
+///
+/// Las categorías se muestran con su ruta completa ("Maquillaje > Labios > Labiales").
+///
+/// Una segunda hoja resume unidades e ingreso por producto; con
+/// `agrupar_por_padre` las variantes se suman en su producto padre.
 #[tauri::command]
 pub fn export_all_xlsx(
     db_path: State<'_, PathBuf>,
     ruta_destino: String,
+    agrupar_por_padre: Option<bool>,
 ) -> Result<String, String> {
     let conn = database::open_connection(db_path.as_ref() as &Path)
         .map_err(|e| format!("No se pudo abrir la base de datos: {e}"))?;
 
     // Número de columnas por tabla
-    const CAT_COLS: u16 = 2;
-    const PROD_COLS: u16 = 9;
-    const VENT_COLS: u16 = 6;
-    const PV_COLS: u16 = 7;
+    const CAT_COLS: u16 = 3;
+    const PROD_COLS: u16 = 10;
+    const VENT_COLS: u16 = 7;
+    const PV_COLS: u16 = 8;
 
     // Columnas de inicio de cada tabla (con 1 columna gap entre cada una)
     const CAT_START: u16 = 0;                                          // A
-    const PROD_START: u16 = CAT_START + CAT_COLS + 1;                  // D  (0+2+1=3)
-    const VENT_START: u16 = PROD_START + PROD_COLS + 1;                // M  (3+8+1=12)
-    const PV_START: u16 = VENT_START + VENT_COLS + 1;                  // S  (12+5+1=18)
+    const PROD_START: u16 = CAT_START + CAT_COLS + 1;                  // E  (0+3+1=4)
+    const VENT_START: u16 = PROD_START + PROD_COLS + 1;                // P  (4+10+1=15)
+    const PV_START: u16 = VENT_START + VENT_COLS + 1;                  // X  (15+7+1=23)
 
     // ── Recopilar datos ──
 
     // Categorías
-    let cat_headers = ["id_categoria", "nombre"];
+    let cat_headers = ["id_categoria", "nombre", "ruta"];
     let mut cat_rows: Vec<Vec<String>> = Vec::new();
     {
         let mut stmt = conn
-            .prepare("SELECT id_categoria, nombre FROM categorias ORDER BY id_categoria")
+            .prepare(&format!(
+                "{} SELECT c.id_categoria, c.nombre, COALESCE(r.ruta, c.nombre) \
+                 FROM categorias c \
+                 LEFT JOIN rutas_categoria r ON r.id_categoria = c.id_categoria \
+                 ORDER BY r.ruta COLLATE NOCASE",
+                RUTAS_CATEGORIA_CTE
+            ))
             .map_err(|e| e.to_string())?;
         let rows = stmt
             .query_map([], |row| {
                 Ok(vec![
                     row.get::<_, i64>(0)?.to_string(),
                     row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
                 ])
             })
             .map_err(|e| e.to_string())?;
@@ -55,21 +73,25 @@ pub fn export_all_xlsx(
         }
     }
 
-    // Productos (JOIN con categorias para mostrar nombre en vez de id)
+    // Productos (JOIN con las rutas de categoría para mostrar la ruta completa en vez de id)
     let prod_headers = [
         "id_producto", "nombre_producto", "categoria",
         "stock", "precio consultora", "precio publico", "creado_at", "actualizado_at", "estado",
+        "producto padre",
     ];
     let mut prod_rows: Vec<Vec<String>> = Vec::new();
     {
         let mut stmt = conn
-            .prepare(
-                "SELECT p.id_producto, p.nombre_producto, COALESCE(c.nombre, '') as categoria, \
-                        p.stock, p.precio_consultora, p.precio_publico, p.creado_at, p.actualizado_at, p.activo \
+            .prepare(&format!(
+                "{} SELECT p.id_producto, p.nombre_producto, COALESCE(c.ruta, '') as categoria, \
+                        p.stock, p.precio_consultora, p.precio_publico, p.creado_at, p.actualizado_at, p.activo, \
+                        COALESCE(padre.nombre_producto, '') \
                  FROM productos p \
-                 LEFT JOIN categorias c ON p.id_categoria = c.id_categoria \
+                 LEFT JOIN rutas_categoria c ON p.id_categoria = c.id_categoria \
+                 LEFT JOIN productos padre ON padre.id_producto = p.id_producto_padre \
                  ORDER BY p.id_producto",
-            )
+                RUTAS_CATEGORIA_CTE
+            ))
             .map_err(|e| e.to_string())?;
         let rows = stmt
             .query_map([], |row| {
@@ -86,6 +108,7 @@ pub fn export_all_xlsx(
                     row.get::<_, Option<String>>(6)?.unwrap_or_default(),
                     row.get::<_, Option<String>>(7)?.unwrap_or_default(),
                     if activo == 1 { "Activo".to_string() } else { "Descontinuado".to_string() },
+                    row.get::<_, String>(9)?,
                 ])
             })
             .map_err(|e| e.to_string())?;
@@ -97,18 +120,21 @@ pub fn export_all_xlsx(
     // Ventas
     let vent_headers = [
         "id_venta", "fecha", "nombre_clienta", "apellido_clienta", "total_venta", "tipo_pago",
+        "descuento",
     ];
     let mut vent_rows: Vec<Vec<String>> = Vec::new();
     {
         let mut stmt = conn
             .prepare(
-                "SELECT id_venta, fecha, nombre_clienta, apellido_clienta, total_venta, tipo_pago \
-                 FROM ventas ORDER BY id_venta",
+                "SELECT v.id_venta, v.fecha, v.nombre_clienta, v.apellido_clienta, v.total_venta, v.tipo_pago, \
+                        v.descuento + COALESCE((SELECT SUM(pv.descuento) FROM productos_vendidos pv WHERE pv.id_venta = v.id_venta), 0) \
+                 FROM ventas v ORDER BY v.id_venta",
             )
             .map_err(|e| e.to_string())?;
         let rows = stmt
             .query_map([], |row| {
                 let total: f64 = row.get(4)?;
+                let descuento: f64 = row.get(6)?;
                 let fecha_raw: String = row.get(1)?;
                 Ok(vec![
                     row.get::<_, i64>(0)?.to_string(),
@@ -117,6 +143,7 @@ pub fn export_all_xlsx(
                     row.get::<_, String>(3)?,
                     if total.fract() == 0.0 { format!("{}", total as i64) } else { format!("{}", total) },
                     row.get::<_, String>(5)?,
+                    if descuento.fract() == 0.0 { format!("{}", descuento as i64) } else { format!("{:.2}", descuento) },
                 ])
             })
             .map_err(|e| e.to_string())?;
@@ -128,16 +155,15 @@ pub fn export_all_xlsx(
     // Productos vendidos (nombres legibles en vez de IDs)
     let pv_headers = [
         "nro", "nro_venta", "producto",
-        "cantidad", "precio consultora ref", "precio publico aplicado", "subtotal",
+        "cantidad", "costo unitario", "precio publico aplicado", "descuento", "subtotal",
     ];
     let mut pv_rows: Vec<Vec<String>> = Vec::new();
     {
         let mut stmt = conn
             .prepare(
                 "SELECT pv.id_producto_vendido, pv.id_venta, pv.nombre_producto_snapshot, \
-                        pv.cantidad, COALESCE(p.precio_consultora, 0), pv.precio_unitario, pv.subtotal \
+                        pv.cantidad, pv.costo_unitario, pv.precio_unitario, pv.descuento, pv.subtotal \
                  FROM productos_vendidos pv \
-                 LEFT JOIN productos p ON p.id_producto = pv.id_producto \
                  ORDER BY pv.id_venta, pv.id_producto_vendido",
             )
             .map_err(|e| e.to_string())?;
@@ -145,7 +171,8 @@ pub fn export_all_xlsx(
             .query_map([], |row| {
                 let pc: f64 = row.get(4)?;
                 let pu: f64 = row.get(5)?;
-                let sub: f64 = row.get(6)?;
+                let desc: f64 = row.get(6)?;
+                let sub: f64 = row.get(7)?;
                 Ok(vec![
                     row.get::<_, i64>(0)?.to_string(),
                     row.get::<_, i64>(1)?.to_string(),
@@ -153,6 +180,7 @@ pub fn export_all_xlsx(
                     row.get::<_, i64>(3)?.to_string(),
                     if pc.fract() == 0.0 { format!("{}", pc as i64) } else { format!("{:.2}", pc) },
                     if pu.fract() == 0.0 { format!("{}", pu as i64) } else { format!("{:.2}", pu) },
+                    if desc.fract() == 0.0 { format!("{}", desc as i64) } else { format!("{:.2}", desc) },
                     if sub.fract() == 0.0 { format!("{}", sub as i64) } else { format!("{:.2}", sub) },
                 ])
             })
@@ -285,12 +313,164 @@ pub fn export_all_xlsx(
         sheet.set_column_width(gap_col, 2.0).map_err(|e| e.to_string())?;
     }
 
+    // ── Hoja 2: ventas por producto ──
+    let resumen = ProductoVendidoRepo { conn: &conn }
+        .resumen_por_producto(agrupar_por_padre.unwrap_or(false), None)
+        .map_err(|e| e.to_string())?;
+
+    let resumen_sheet = workbook.add_worksheet();
+    resumen_sheet.set_name("Ventas por producto").map_err(|e| e.to_string())?;
+    resumen_sheet.merge_range(
+        0, 0, 0, 2,
+        if agrupar_por_padre.unwrap_or(false) { "VENTAS POR PRODUCTO (VARIANTES AGRUPADAS)" } else { "VENTAS POR PRODUCTO" },
+        &title_format,
+    ).map_err(|e| e.to_string())?;
+
+    for (j, header) in ["producto", "unidades", "ingreso"].iter().enumerate() {
+        resumen_sheet.write_string_with_format(1, j as u16, *header, &header_format)
+            .map_err(|e| e.to_string())?;
+    }
+    for (i, producto) in resumen.iter().enumerate() {
+        let row = (i + 2) as u32;
+        resumen_sheet.write_string_with_format(row, 0, &producto.nombre, &data_format)
+            .map_err(|e| e.to_string())?;
+        resumen_sheet.write_number_with_format(row, 1, producto.unidades as f64, &data_format)
+            .map_err(|e| e.to_string())?;
+        resumen_sheet.write_number_with_format(row, 2, producto.ingreso, &data_format)
+            .map_err(|e| e.to_string())?;
+    }
+    resumen_sheet.set_column_width(0, 35.0).map_err(|e| e.to_string())?;
+    resumen_sheet.set_column_width(1, 12.0).map_err(|e| e.to_string())?;
+    resumen_sheet.set_column_width(2, 14.0).map_err(|e| e.to_string())?;
+
     // ── Guardar archivo ──
     workbook.save(&ruta_destino).map_err(|e| format!("No se pudo escribir el archivo: {e}"))?;
 
     Ok(ruta_destino)
 }
 
+/// ─── Hoja de etiquetas de precio en PDF ───
+///
+/// Cada etiqueta lleva nombre, precio público y un Code 128 con el id del
+/// producto; `formato` elige la hoja (por defecto carta 3x10).
+#[tauri::command]
+pub fn export_etiquetas_pdf(
+    db_path: State<'_, PathBuf>,
+    etiquetas: Vec<EtiquetaInput>,
+    formato: Option<FormatoEtiquetas>,
+    ruta_destino: String,
+) -> Result<String, String> {
+    let conn = database::open_connection(db_path.as_ref() as &Path)
+        .map_err(|e| format!("No se pudo abrir la base de datos: {e}"))?;
+
+    let pdf = EtiquetaService::new(&conn)
+        .generar_pdf(&etiquetas, formato.unwrap_or_default())
+        .map_err(|e| e.to_string())?;
+
+    fs::write(&ruta_destino, pdf).map_err(|e| format!("No se pudo escribir el archivo: {e}"))?;
+
+    Ok(ruta_destino)
+}
+
+/// ─── Exportar el estado de cuenta de una clienta a XLSX ───
+///
+/// Encabezado con los datos de la clienta, tabla de movimientos con saldo
+/// acumulado y un resumen con el total adeudado en ventas a abonos abiertas.
+#[tauri::command]
+pub fn export_estado_cuenta_xlsx(
+    db_path: State<'_, PathBuf>,
+    id_clienta: i64,
+    ruta_destino: String,
+) -> Result<String, String> {
+    let conn = database::open_connection(db_path.as_ref() as &Path)
+        .map_err(|e| format!("No se pudo abrir la base de datos: {e}"))?;
+
+    let estado = EstadoCuentaService::new(&conn)
+        .get_estado_cuenta(id_clienta)
+        .map_err(|e| e.to_string())?;
+
+    let mut workbook = Workbook::new();
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("Estado de cuenta").map_err(|e| e.to_string())?;
+
+    let title_format = Format::new()
+        .set_bold()
+        .set_align(FormatAlign::Center)
+        .set_align(FormatAlign::VerticalCenter)
+        .set_font_size(11.0)
+        .set_background_color(Color::RGB(0xD9E1F2))
+        .set_border(FormatBorder::Thin);
+
+    let header_format = Format::new()
+        .set_bold()
+        .set_align(FormatAlign::Center)
+        .set_background_color(Color::RGB(0xE2EFDA))
+        .set_border(FormatBorder::Thin);
+
+    let data_format = Format::new()
+        .set_border(FormatBorder::Thin);
+
+    let money_format = Format::new()
+        .set_border(FormatBorder::Thin)
+        .set_num_format("0.00");
+
+    let bold_format = Format::new().set_bold();
+
+    let headers = ["fecha", "concepto", "nro_venta", "cargo", "abono", "saldo"];
+    let last_col = headers.len() as u16 - 1;
+
+    let nombre_completo = format!("{} {}", estado.clienta.nombre, estado.clienta.apellido);
+    sheet.merge_range(
+        0, 0, 0, last_col,
+        &format!("ESTADO DE CUENTA - {}", nombre_completo.trim()), &title_format,
+    ).map_err(|e| e.to_string())?;
+
+    sheet.write_string_with_format(1, 0, "telefono", &bold_format).map_err(|e| e.to_string())?;
+    sheet.write_string(1, 1, &estado.clienta.telefono).map_err(|e| e.to_string())?;
+    sheet.write_string_with_format(2, 0, "direccion", &bold_format).map_err(|e| e.to_string())?;
+    sheet.write_string(2, 1, &estado.clienta.direccion).map_err(|e| e.to_string())?;
+
+    // ── Movimientos ──
+    let header_row: u32 = 4;
+    for (j, header) in headers.iter().enumerate() {
+        sheet.write_string_with_format(header_row, j as u16, *header, &header_format)
+            .map_err(|e| e.to_string())?;
+    }
+
+    for (i, mov) in estado.movimientos.iter().enumerate() {
+        let row = header_row + 1 + i as u32;
+        sheet.write_string_with_format(row, 0, normalize_fecha(&mov.fecha), &data_format).map_err(|e| e.to_string())?;
+        sheet.write_string_with_format(row, 1, &mov.concepto, &data_format).map_err(|e| e.to_string())?;
+        sheet.write_number_with_format(row, 2, mov.id_venta as f64, &data_format).map_err(|e| e.to_string())?;
+        sheet.write_number_with_format(row, 3, mov.cargo, &money_format).map_err(|e| e.to_string())?;
+        sheet.write_number_with_format(row, 4, mov.abono, &money_format).map_err(|e| e.to_string())?;
+        sheet.write_number_with_format(row, 5, mov.saldo, &money_format).map_err(|e| e.to_string())?;
+    }
+
+    // ── Resumen ──
+    let resumen_row = header_row + estado.movimientos.len() as u32 + 2;
+    let resumen: [(&str, f64); 4] = [
+        ("total comprado", estado.total_comprado),
+        ("total abonado", estado.total_abonado),
+        ("ventas abiertas", estado.ventas_abiertas as f64),
+        ("saldo pendiente", estado.saldo_total),
+    ];
+    for (i, (etiqueta, valor)) in resumen.iter().enumerate() {
+        let row = resumen_row + i as u32;
+        sheet.write_string_with_format(row, 4, *etiqueta, &bold_format).map_err(|e| e.to_string())?;
+        sheet.write_number(row, 5, *valor).map_err(|e| e.to_string())?;
+    }
+
+    let widths = [20.0, 18.0, 10.0, 12.0, 16.0, 12.0];
+    for (j, width) in widths.iter().enumerate() {
+        sheet.set_column_width(j as u16, *width).map_err(|e| e.to_string())?;
+    }
+
+    workbook.save(&ruta_destino).map_err(|e| format!("No se pudo escribir el archivo: {e}"))?;
+
+    Ok(ruta_destino)
+}
+
 fn normalize_fecha(value: &str) -> String {
     // Expected output: "YYYY-MM-DD HH:MM:SS"
     if let Some((date_part, time_part)) = value.split_once('T') {
@@ -428,7 +608,8 @@ pub fn import_database(
     let _ = fs::remove_file(&wal_path);
     let _ = fs::remove_file(&shm_path);
 
-    // verifica que la nueva DB se pueda abrir e inicializar correctamente
+    // verifica que la nueva DB se pueda abrir e inicializar correctamente;
+    // las migraciones también dejan relativas las rutas de imagen de otra computadora
     database::init_db(current_db)
         .map_err(|e| format!("La DB importada no se pudo inicializar: {e}"))?;
 
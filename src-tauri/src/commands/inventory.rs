@@ -0,0 +1,157 @@
+use std::path::PathBuf;
+use tauri::State;
+use crate::database;
+use crate::models::{AjusteStockOutput, ConteoInventario, Lote, MotivoAjuste, MovimientoInventario, ReporteConteo};
+use crate::services::conteo_service::ConteoService;
+use crate::services::lote_service::LoteService;
+use crate::services::movimiento_inventario_service::MovimientoInventarioService;
+use crate::services::producto_service::ProductoService;
+
+// Kardex de un producto (más reciente primero)
+#[tauri::command]
+pub fn list_movimientos_por_producto(id_producto: i64, db_path: State<'_, PathBuf>) -> Result<Vec<MovimientoInventario>, String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = MovimientoInventarioService::new(&conn);
+    service.listar_por_producto(id_producto).map_err(|e| e.to_string())
+}
+
+// Movimientos de todos los productos entre dos fechas (inclusive)
+#[tauri::command]
+pub fn list_movimientos_por_fecha(desde: String, hasta: String, db_path: State<'_, PathBuf>) -> Result<Vec<MovimientoInventario>, String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = MovimientoInventarioService::new(&conn);
+    service.listar_por_fechas(&desde, &hasta).map_err(|e| e.to_string())
+}
+
+// Ajuste manual de stock (delta positivo o negativo) con su motivo
+#[tauri::command]
+pub fn ajustar_stock(
+    id_producto: i64,
+    delta: i64,
+    motivo: MotivoAjuste,
+    nota: Option<String>,
+    db_path: State<'_, PathBuf>,
+) -> Result<AjusteStockOutput, String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let mut conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let service = ProductoService::new(&tx);
+    let output = service
+        .ajustar_stock(id_producto, delta, motivo, nota.as_deref().unwrap_or(""))
+        .map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| e.to_string())?;
+
+    Ok(output)
+}
+
+// Lotes del producto en orden de consumo (primero el que caduca antes)
+#[tauri::command]
+pub fn list_lotes_por_producto(id_producto: i64, db_path: State<'_, PathBuf>) -> Result<Vec<Lote>, String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = LoteService::new(&conn);
+    service.listar_por_producto(id_producto).map_err(|e| e.to_string())
+}
+
+// Lotes con existencia que vencen dentro de `dias` (30 por defecto), incluidos los ya vencidos
+#[tauri::command]
+pub fn list_lotes_por_vencer(dias: Option<i64>, db_path: State<'_, PathBuf>) -> Result<Vec<Lote>, String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = LoteService::new(&conn);
+    service.listar_por_vencer(dias.unwrap_or(30)).map_err(|e| e.to_string())
+}
+
+// Retira del stock todos los lotes vencidos y devuelve los lotes dados de baja
+#[tauri::command]
+pub fn dar_de_baja_lotes_vencidos(db_path: State<'_, PathBuf>) -> Result<Vec<Lote>, String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let mut conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let service = LoteService::new(&tx);
+    let lotes = service.dar_de_baja_vencidos().map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| e.to_string())?;
+
+    Ok(lotes)
+}
+
+// ============== Conteo físico ==============
+#[tauri::command]
+pub fn list_conteos(db_path: State<'_, PathBuf>) -> Result<Vec<ConteoInventario>, String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = ConteoService::new(&conn);
+    service.listar_conteos().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn abrir_conteo(descripcion: Option<String>, db_path: State<'_, PathBuf>) -> Result<i64, String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = ConteoService::new(&conn);
+    service
+        .abrir_conteo(descripcion.as_deref().unwrap_or(""))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn registrar_cantidad_contada(
+    id_conteo: i64,
+    id_producto: i64,
+    cantidad_contada: i64,
+    db_path: State<'_, PathBuf>,
+) -> Result<(), String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = ConteoService::new(&conn);
+    service
+        .registrar_cantidad(id_conteo, id_producto, cantidad_contada)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn quitar_producto_conteo(id_conteo: i64, id_producto: i64, db_path: State<'_, PathBuf>) -> Result<(), String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = ConteoService::new(&conn);
+    service.quitar_producto(id_conteo, id_producto).map_err(|e| e.to_string())
+}
+
+// Diferencias contra el stock del sistema valorizadas a precio consultora
+#[tauri::command]
+pub fn get_reporte_conteo(id_conteo: i64, db_path: State<'_, PathBuf>) -> Result<ReporteConteo, String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = ConteoService::new(&conn);
+    service.get_reporte(id_conteo).map_err(|e| e.to_string())
+}
+
+// Aplica todos los ajustes del conteo en una sola transacción y lo cierra
+#[tauri::command]
+pub fn aplicar_conteo(id_conteo: i64, db_path: State<'_, PathBuf>) -> Result<ReporteConteo, String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let mut conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let service = ConteoService::new(&tx);
+    let reporte = service.aplicar_conteo(id_conteo).map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| e.to_string())?;
+
+    Ok(reporte)
+}
@@ -3,4 +3,10 @@ pub mod categories;
 pub mod sales;
 pub mod sold_products;
 pub mod export;
-pub mod profile;
\ No newline at end of file
+pub mod profile;
+pub mod customers;
+pub mod inventory;
+pub mod purchases;
+pub mod campaigns;
+pub mod promotions;
+pub mod product_import;
@@ -0,0 +1,53 @@
+use std::path::{Path, PathBuf};
+use tauri::State;
+use crate::database;
+use crate::importacion;
+use crate::models::{OpcionesImportacion, ResultadoImportacion};
+use crate::services::importacion_service::ImportacionService;
+
+// Vista previa de la importación: valida y aplica todo en una transacción que se descarta,
+// para mostrar qué se crearía, actualizaría u omitiría y los errores de cada fila
+#[tauri::command]
+pub fn previsualizar_importacion_productos(
+    ruta_archivo: String,
+    opciones: Option<OpcionesImportacion>,
+    db_path: State<'_, PathBuf>,
+) -> Result<ResultadoImportacion, String>
+{
+    let opciones = opciones.unwrap_or_default();
+    let tabla = importacion::leer_archivo(Path::new(&ruta_archivo), opciones.hoja.as_deref())?;
+
+    let db_path: &PathBuf = db_path.inner();
+    let mut conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let service = ImportacionService::new(&tx);
+    let resultado = service.importar_productos(&tabla, &opciones).map_err(|e| e.to_string())?;
+    tx.rollback().map_err(|e| e.to_string())?;
+    Ok(resultado)
+}
+
+// Importa el archivo en una sola transacción; si alguna fila tiene errores no se guarda nada
+// y se regresa el resultado con `aplicado = false`
+#[tauri::command]
+pub fn importar_productos(
+    ruta_archivo: String,
+    opciones: Option<OpcionesImportacion>,
+    db_path: State<'_, PathBuf>,
+) -> Result<ResultadoImportacion, String>
+{
+    let opciones = opciones.unwrap_or_default();
+    let tabla = importacion::leer_archivo(Path::new(&ruta_archivo), opciones.hoja.as_deref())?;
+
+    let db_path: &PathBuf = db_path.inner();
+    let mut conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let service = ImportacionService::new(&tx);
+    let mut resultado = service.importar_productos(&tabla, &opciones).map_err(|e| e.to_string())?;
+    if resultado.con_errores > 0 {
+        tx.rollback().map_err(|e| e.to_string())?;
+        return Ok(resultado);
+    }
+    tx.commit().map_err(|e| format!("Error al confirmar la transacción: {}", e))?;
+    resultado.aplicado = true;
+    Ok(resultado)
+}
@@ -1,9 +1,15 @@
 use std::path::PathBuf;
+use rusqlite::Connection;
 use tauri::{State};
 use std::fs;
 use crate::database;
-use crate::models::Producto;
+use crate::imagen;
+use crate::models::{ConsultaLista, FiltrosBusqueda, HistorialPrecio, ImpactoEliminacionProducto, KitComponente, KitComponenteInput, LimpiezaImagenes, Pagina, Producto, ProductoAgrupado, ProductoBajoStock, RegeneracionMiniaturas, ReporteImagenes, VarianteInput};
+use crate::services::historial_precio_service::HistorialPrecioService;
+use crate::services::imagen_service::ImagenService;
+use crate::services::kit_service::KitService;
 use crate::services::producto_service::ProductoService;
+use crate::services::variante_service::VarianteService;
 
 #[tauri::command]
 pub fn list_productos(db_path: State<'_, PathBuf>) -> Result<Vec<Producto>, String> 
@@ -14,6 +20,47 @@ pub fn list_productos(db_path: State<'_, PathBuf>) -> Result<Vec<Producto>, Stri
     service.list_productos().map_err(|e| e.to_string())
 }
 
+// Página de productos con filtros, orden y total; sin consulta usa la primera página por nombre
+#[tauri::command]
+pub fn list_productos_paginados(
+    consulta: Option<ConsultaLista>,
+    db_path: State<'_, PathBuf>,
+) -> Result<Pagina<Producto>, String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = ProductoService::new(&conn);
+    service
+        .list_productos_paginados(&consulta.unwrap_or_default())
+        .map_err(|e| e.to_string())
+}
+
+// Productos de una categoría; por defecto incluye los de todas sus subcategorías
+#[tauri::command]
+pub fn list_productos_por_categoria(
+    id_categoria: i64,
+    incluir_subcategorias: Option<bool>,
+    db_path: State<'_, PathBuf>,
+) -> Result<Vec<Producto>, String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = ProductoService::new(&conn);
+    service
+        .list_productos_por_categoria(id_categoria, incluir_subcategorias.unwrap_or(true))
+        .map_err(|e| e.to_string())
+}
+
+// Catálogo agrupado: cada producto con sus variantes (tono, tamaño, fragancia)
+#[tauri::command]
+pub fn list_productos_agrupados(db_path: State<'_, PathBuf>) -> Result<Vec<ProductoAgrupado>, String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = VarianteService::new(&conn);
+    service.list_productos_agrupados().map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub fn get_producto(id: i64, db_path: State<'_, PathBuf>) -> Result<Producto, String> 
 {
@@ -29,98 +76,173 @@ pub fn create_producto(
     id_categoria: Option<i64>,
     image_bytes: Option<Vec<u8>>,
     image_ext: Option<String>,
-    miniatura_base64: Option<String>,
     stock: i64,
     precio: f64,
     precio_consultora: Option<f64>,
     precio_publico: Option<f64>,
+    sku: Option<String>,
+    codigo_barras: Option<String>,
     db_path: State<'_, PathBuf>,
 ) -> Result<i64, String> 
 {
     let db_path: &PathBuf = db_path.inner();
-    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
-    let service = ProductoService::new(&conn);
+    let mut conn = database::init_db(db_path).map_err(|e| e.to_string())?;
 
     let mut ruta_imagen: Option<String> = None;
-    
-    if let (Some(bytes), Some(ext)) = (image_bytes, image_ext) {
-        let image_dir = db_path
-            .parent()
-            .ok_or("No se pudo resolver la carpeta de imagenes")?
-            .join("images");
-
-        fs::create_dir_all(&image_dir).map_err(|e| e.to_string())?;
+    let mut miniatura_base64: Option<String> = None;
 
-        let filename = format!("producto_{}_{}.{}", chrono::Utc::now().timestamp_millis(),
-            uuid::Uuid::new_v4(),
-            ext);
+    // La imagen se valida por su contenido, se reduce y se vuelve a codificar; la miniatura se genera aquí
+    if let Some(bytes) = image_bytes {
+        let image_dir = imagen::carpeta_imagenes(db_path);
 
-        let full_path = image_dir.join(filename);
-        fs::write(&full_path, bytes).map_err(|e| e.to_string())?;
+        let procesada = imagen::procesar(&bytes, image_ext.as_deref())?;
+        let full_path = imagen::guardar(&image_dir, "producto", &procesada)?;
         ruta_imagen = Some(full_path.to_string_lossy().to_string());
+        miniatura_base64 = Some(procesada.miniatura_base64);
     }
 
     let consultora = precio_consultora.unwrap_or(precio);
     let publico = precio_publico.unwrap_or(precio);
 
+    // Un código duplicado o inválido deshace el alta completa
+    guardar_con_imagen(&mut conn, ruta_imagen.as_deref(), |service| {
+        let id = service
+            .create_producto_with_prices(
+                &nombre_producto,
+                id_categoria,
+                ruta_imagen.as_deref(),
+                miniatura_base64.as_deref(),
+                stock,
+                consultora,
+                publico,
+            )
+            .map_err(|e| e.to_string())?;
+        if sku.is_some() || codigo_barras.is_some() {
+            service
+                .set_codigos(id, sku.as_deref(), codigo_barras.as_deref())
+                .map_err(|e| e.to_string())?;
+        }
+        Ok(id)
+    })
+}
+
+// Ejecuta `guardar` en una transacción; si algo falla borra la imagen recién escrita para no dejarla huérfana
+fn guardar_con_imagen<T>(
+    conn: &mut Connection,
+    imagen_nueva: Option<&str>,
+    guardar: impl FnOnce(&ProductoService) -> Result<T, String>,
+) -> Result<T, String>
+{
+    let resultado = conn.transaction().map_err(|e| e.to_string()).and_then(|tx| {
+        let valor = guardar(&ProductoService::new(&tx))?;
+        tx.commit().map_err(|e| format!("Error al confirmar la transacción: {}", e))?;
+        Ok(valor)
+    });
+    if resultado.is_err() {
+        if let Some(ruta) = imagen_nueva {
+            let _ = fs::remove_file(ruta);
+        }
+    }
+    resultado
+}
+
+// Regenera en el servidor las miniaturas de los productos y del perfil a partir de las imágenes guardadas
+#[tauri::command]
+pub fn regenerar_miniaturas(db_path: State<'_, PathBuf>) -> Result<RegeneracionMiniaturas, String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = ImagenService::new(&conn);
+    service.regenerar_miniaturas().map_err(|e| e.to_string())
+}
+
+// Mantenimiento de `images/`: archivos sin usar, su tamaño y productos con la imagen faltante
+#[tauri::command]
+pub fn reporte_imagenes(db_path: State<'_, PathBuf>) -> Result<ReporteImagenes, String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = ImagenService::new(&conn);
     service
-        .create_producto_with_prices(
-            &nombre_producto,
-            id_categoria,
-            ruta_imagen.as_deref(),
-            miniatura_base64.as_deref(),
-            stock,
-            consultora,
-            publico,
-        )
+        .reporte_almacenamiento(&imagen::carpeta_imagenes(db_path))
         .map_err(|e| e.to_string())
 }
 
+// Borra los archivos huérfanos que el usuario confirmó del reporte
+#[tauri::command]
+pub fn limpiar_imagenes_huerfanas(archivos: Vec<String>, db_path: State<'_, PathBuf>) -> Result<LimpiezaImagenes, String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = ImagenService::new(&conn);
+    service
+        .limpiar_huerfanas(&imagen::carpeta_imagenes(db_path), &archivos)
+        .map_err(|e| e.to_string())
+}
+
+// Búsqueda para el lector de códigos en la pantalla de ventas (código de barras o SKU)
+#[tauri::command]
+pub fn buscar_producto_por_codigo(codigo: String, db_path: State<'_, PathBuf>) -> Result<Producto, String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = ProductoService::new(&conn);
+    service.buscar_por_codigo(&codigo).map_err(|e| e.to_string())
+}
+
+// Búsqueda de texto completo con ranking; solo regresa los `limite` productos más relevantes
+#[tauri::command]
+pub fn buscar_productos(
+    query: String,
+    filtros: Option<FiltrosBusqueda>,
+    limite: Option<i64>,
+    db_path: State<'_, PathBuf>,
+) -> Result<Vec<Producto>, String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = ProductoService::new(&conn);
+    service
+        .buscar_productos(&query, &filtros.unwrap_or_default(), limite)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn set_notas_producto(id: i64, notas: Option<String>, db_path: State<'_, PathBuf>) -> Result<(), String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = ProductoService::new(&conn);
+    service.set_notas(id, notas.as_deref()).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub fn update_producto(
     producto: Producto, 
     image_bytes: Option<Vec<u8>>,
     image_ext: Option<String>,
-    miniatura_base64: Option<String>,
     precio_consultora: Option<f64>,
     precio_publico: Option<f64>,
     db_path: State<'_, PathBuf>) 
     -> Result<(), String> 
 {
     let db_path: &PathBuf = db_path.inner();
-    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
-    let service = ProductoService::new(&conn);
+    let mut conn = database::init_db(db_path).map_err(|e| e.to_string())?;
 
     let mut updated = producto;
+    let image_dir = imagen::carpeta_imagenes(db_path);
+    let mut imagen_anterior: Option<String> = None;
+    let mut imagen_nueva: Option<String> = None;
 
-    if let (Some(bytes), Some(ext)) = (image_bytes, image_ext)
+    // La imagen anterior se conserva hasta que el cambio quede guardado
+    if let Some(bytes) = image_bytes
     {
-        let image_dir = db_path
-            .parent()
-            .ok_or("No se pudo resolver la carpeta de imagenes")?
-            .join("images");
-
-        fs::create_dir_all(&image_dir).map_err(|e| e.to_string())?;
-
-        if let Some(existing_path) = updated.ruta_imagen.as_deref() {
-            let _ = fs::remove_file(existing_path);
-        }
-
-        let filename = format!(
-            "producto_{}_{}.{}",
-            chrono::Utc::now().timestamp_millis(),
-            uuid::Uuid::new_v4(),
-            ext
-        );
-
-        let full_path = image_dir.join(filename);
-        fs::write(&full_path, bytes).map_err(|e| e.to_string())?;
-        updated.ruta_imagen = Some(full_path.to_string_lossy().to_string());
-    }
-
-    if let Some(mini) = miniatura_base64
-    {
-        updated.miniatura_base64 = Some(mini);
+        let procesada = imagen::procesar(&bytes, image_ext.as_deref())?;
+        let full_path = imagen::guardar(&image_dir, "producto", &procesada)?;
+        imagen_nueva = Some(full_path.to_string_lossy().to_string());
+        imagen_anterior = updated.ruta_imagen.take();
+        updated.ruta_imagen = imagen_nueva.clone();
+        updated.miniatura_base64 = Some(procesada.miniatura_base64);
     }
 
     if let Some(consultora) = precio_consultora {
@@ -133,16 +255,98 @@ pub fn update_producto(
         updated.precio = publico;
     }
 
-    service.update_producto(&updated).map_err(|e| e.to_string())
+    // Producto e historial de precios se guardan juntos
+    guardar_con_imagen(&mut conn, imagen_nueva.as_deref(), |service| {
+        service.update_producto(&updated).map_err(|e| e.to_string())
+    })?;
+
+    // Solo se borra dentro de `images/`, venga como venga la ruta desde la interfaz
+    if let Some(existing_path) = imagen_anterior.as_deref() {
+        let _ = fs::remove_file(imagen::ruta_completa(&image_dir, existing_path));
+    }
+    Ok(())
+}
+
+// Stock mínimo propio del producto; None vuelve a usar el de su categoría
+#[tauri::command]
+pub fn set_stock_minimo_producto(id_producto: i64, stock_minimo: Option<i64>, db_path: State<'_, PathBuf>) -> Result<(), String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = ProductoService::new(&conn);
+    service.set_stock_minimo(id_producto, stock_minimo).map_err(|e| e.to_string())
+}
+
+// Reporte de reposición: productos en o bajo su stock mínimo (por defecto ventas de los últimos 30 días)
+#[tauri::command]
+pub fn list_productos_bajo_stock(
+    dias: Option<i64>,
+    id_categoria: Option<i64>,
+    db_path: State<'_, PathBuf>,
+) -> Result<Vec<ProductoBajoStock>, String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = ProductoService::new(&conn);
+    service
+        .list_productos_bajo_stock(dias.unwrap_or(30), id_categoria)
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
 pub fn delete_producto(id: i64, db_path: State<'_, PathBuf>) -> Result<(), String> 
+{
+    let db_path: &PathBuf = db_path.inner();
+    let mut conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+
+    // El producto y sus variantes se descontinúan juntos
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let service = ProductoService::new(&tx);
+    service.delete_producto(id).map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| format!("Error al confirmar la transacción: {}", e))
+}
+
+#[tauri::command]
+pub fn list_productos_descontinuados(db_path: State<'_, PathBuf>) -> Result<Vec<Producto>, String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = ProductoService::new(&conn);
+    service.list_productos_descontinuados().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn reactivar_producto(id: i64, db_path: State<'_, PathBuf>) -> Result<(), String>
 {
     let db_path: &PathBuf = db_path.inner();
     let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
     let service = ProductoService::new(&conn);
-    service.delete_producto(id).map_err(|e| e.to_string())
+    service.reactivar_producto(id).map_err(|e| e.to_string())
+}
+
+// Movimientos, historial de precios, lotes y precios de campaña que se perderían al eliminar el producto
+#[tauri::command]
+pub fn get_impacto_eliminar_producto(id: i64, db_path: State<'_, PathBuf>) -> Result<ImpactoEliminacionProducto, String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = ProductoService::new(&conn);
+    service.impacto_eliminacion_definitiva(id).map_err(|e| e.to_string())
+}
+
+// Solo para productos descontinuados sin ventas, compras, conteos ni promociones; también borra su imagen
+#[tauri::command]
+pub fn eliminar_producto_definitivo(id: i64, db_path: State<'_, PathBuf>) -> Result<(), String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = ProductoService::new(&conn);
+    let eliminado = service.eliminar_producto_definitivo(id).map_err(|e| e.to_string())?;
+
+    if let Some(ruta) = eliminado.ruta_imagen.as_deref() {
+        let _ = fs::remove_file(imagen::ruta_completa(&imagen::carpeta_imagenes(db_path), ruta));
+    }
+    Ok(())
 }
 
 #[tauri::command]
@@ -152,4 +356,98 @@ pub fn get_total_inventory_value(db_path: State<'_, PathBuf>) -> Result<f64, Str
     let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
     let service = ProductoService::new(&conn);
     service.get_total_inventory_value().map_err(|e| e.to_string())
-}
\ No newline at end of file
+}
+
+// Línea de tiempo de precio_consultora y precio_publico del producto
+#[tauri::command]
+pub fn get_historial_precios(id_producto: i64, db_path: State<'_, PathBuf>) -> Result<Vec<HistorialPrecio>, String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = HistorialPrecioService::new(&conn);
+    service.listar_por_producto(id_producto).map_err(|e| e.to_string())
+}
+
+// ============== Variantes ==============
+#[tauri::command]
+pub fn list_variantes(id_producto: i64, db_path: State<'_, PathBuf>) -> Result<Vec<Producto>, String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = VarianteService::new(&conn);
+    service.list_variantes(id_producto).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn create_variante(
+    id_producto_padre: i64,
+    variante: VarianteInput,
+    stock: Option<i64>,
+    db_path: State<'_, PathBuf>,
+) -> Result<i64, String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let mut conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let service = VarianteService::new(&tx);
+    let id_variante = service
+        .create_variante(id_producto_padre, &variante, stock.unwrap_or(0))
+        .map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| format!("Error al confirmar la transacción: {}", e))?;
+
+    Ok(id_variante)
+}
+
+#[tauri::command]
+pub fn update_variante(id_producto: i64, variante: VarianteInput, db_path: State<'_, PathBuf>) -> Result<(), String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let mut conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let service = VarianteService::new(&tx);
+    service.update_variante(id_producto, &variante).map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| format!("Error al confirmar la transacción: {}", e))
+}
+
+#[tauri::command]
+pub fn list_componentes_kit(id_kit: i64, db_path: State<'_, PathBuf>) -> Result<Vec<KitComponente>, String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = KitService::new(&conn);
+    service.list_componentes(id_kit).map_err(|e| e.to_string())
+}
+
+// Convierte el producto en kit (o cambia su receta); su stock pasa a derivarse de los componentes
+#[tauri::command]
+pub fn set_componentes_kit(
+    id_kit: i64,
+    componentes: Vec<KitComponenteInput>,
+    db_path: State<'_, PathBuf>,
+) -> Result<Vec<KitComponente>, String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let mut conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let service = KitService::new(&tx);
+    service.set_componentes(id_kit, &componentes).map_err(|e| e.to_string())?;
+    let resultado = service.list_componentes(id_kit).map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| format!("Error al confirmar la transacción: {}", e))?;
+    Ok(resultado)
+}
+
+#[tauri::command]
+pub fn deshacer_kit(id_kit: i64, db_path: State<'_, PathBuf>) -> Result<(), String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let mut conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let service = KitService::new(&tx);
+    service.deshacer_kit(id_kit).map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| format!("Error al confirmar la transacción: {}", e))
+}
+
@@ -2,6 +2,7 @@ use std::path::PathBuf;
 use std::fs;
 use tauri::State;
 use crate::database;
+use crate::imagen;
 use crate::models::Perfil;
 use crate::services::perfil_service::PerfilService;
 
@@ -19,7 +20,6 @@ pub fn save_perfil(
     cargo: String,
     image_bytes: Option<Vec<u8>>,
     image_ext: Option<String>,
-    miniatura_base64: Option<String>,
     db_path: State<'_, PathBuf>,
 ) -> Result<(), String> {
     let db_path_ref: &PathBuf = db_path.inner();
@@ -27,31 +27,23 @@ pub fn save_perfil(
     let service = PerfilService::new(&conn);
 
     let mut ruta_foto: Option<String> = None;
+    let mut miniatura_base64: Option<String> = None;
 
-    if let (Some(bytes), Some(ext)) = (image_bytes, image_ext) {
-        let image_dir = db_path_ref
-            .parent()
-            .ok_or("No se pudo resolver la carpeta de imágenes")?
-            .join("images");
+    if let Some(bytes) = image_bytes {
+        let image_dir = imagen::carpeta_imagenes(db_path_ref);
 
-        fs::create_dir_all(&image_dir).map_err(|e| e.to_string())?;
+        let procesada = imagen::procesar(&bytes, image_ext.as_deref())?;
 
         // Eliminar foto anterior si existe
         if let Ok(Some(existing)) = service.get_perfil() {
             if let Some(existing_path) = existing.ruta_foto.as_deref() {
-                let _ = fs::remove_file(existing_path);
+                let _ = fs::remove_file(imagen::ruta_completa(&image_dir, existing_path));
             }
         }
 
-        let filename = format!(
-            "perfil_{}.{}",
-            chrono::Utc::now().timestamp_millis(),
-            ext
-        );
-
-        let full_path = image_dir.join(filename);
-        fs::write(&full_path, bytes).map_err(|e| e.to_string())?;
+        let full_path = imagen::guardar(&image_dir, "perfil", &procesada)?;
         ruta_foto = Some(full_path.to_string_lossy().to_string());
+        miniatura_base64 = Some(procesada.miniatura_base64);
     }
 
     service
@@ -0,0 +1,42 @@
+use std::path::PathBuf;
+use tauri::State;
+use crate::database;
+use crate::models::{Promocion, PromocionInput};
+use crate::services::promocion_service::PromocionService;
+
+// ============== Promociones ==============
+#[tauri::command]
+pub fn list_promociones(db_path: State<'_, PathBuf>) -> Result<Vec<Promocion>, String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = PromocionService::new(&conn);
+    service.listar_promociones().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn create_promocion(input: PromocionInput, db_path: State<'_, PathBuf>) -> Result<i64, String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = PromocionService::new(&conn);
+    service.create_promocion(&input).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn update_promocion(id_promocion: i64, input: PromocionInput, db_path: State<'_, PathBuf>) -> Result<(), String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = PromocionService::new(&conn);
+    service.update_promocion(id_promocion, &input).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn delete_promocion(id_promocion: i64, db_path: State<'_, PathBuf>) -> Result<(), String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = PromocionService::new(&conn);
+    service.delete_promocion(id_promocion).map_err(|e| e.to_string())
+}
@@ -0,0 +1,124 @@
+use std::path::PathBuf;
+use tauri::State;
+use crate::database;
+use crate::models::{
+    Compra,
+    CompraCompleta,
+    CompraInput,
+    Proveedor,
+    ProveedorInput,
+    RecepcionLineaInput,
+    RegistrarPagoCompraInput,
+};
+use crate::services::compra_service::CompraService;
+use crate::services::proveedor_service::ProveedorService;
+
+// ============== Proveedores ==============
+#[tauri::command]
+pub fn list_proveedores(db_path: State<'_, PathBuf>) -> Result<Vec<Proveedor>, String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = ProveedorService::new(&conn);
+    service.list_proveedores().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn create_proveedor(input: ProveedorInput, db_path: State<'_, PathBuf>) -> Result<i64, String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = ProveedorService::new(&conn);
+    service.create_proveedor(&input).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn update_proveedor(proveedor: Proveedor, db_path: State<'_, PathBuf>) -> Result<(), String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = ProveedorService::new(&conn);
+    service.update_proveedor(&proveedor).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn delete_proveedor(id: i64, db_path: State<'_, PathBuf>) -> Result<(), String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = ProveedorService::new(&conn);
+    service.delete_proveedor(id).map_err(|e| e.to_string())
+}
+
+// ============== Compras ==============
+#[tauri::command]
+pub fn list_compras(db_path: State<'_, PathBuf>) -> Result<Vec<Compra>, String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = CompraService::new(&conn);
+    service.list_compras().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_compra(id_compra: i64, db_path: State<'_, PathBuf>) -> Result<CompraCompleta, String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = CompraService::new(&conn);
+    service.get_compra(id_compra).map_err(|e| e.to_string())
+}
+
+// Crea la orden de compra con todas sus líneas en una sola transacción
+#[tauri::command]
+pub fn create_compra(input: CompraInput, db_path: State<'_, PathBuf>) -> Result<i64, String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let mut conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let service = CompraService::new(&tx);
+    let id_compra = service.create_compra(&input).map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| format!("Error al confirmar la transacción: {}", e))?;
+
+    Ok(id_compra)
+}
+
+// Registra la mercancía recibida; si alguna línea falla no se aplica ningún cambio de stock
+#[tauri::command]
+pub fn recibir_compra(
+    id_compra: i64,
+    recepciones: Vec<RecepcionLineaInput>,
+    db_path: State<'_, PathBuf>,
+) -> Result<Compra, String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let mut conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let service = CompraService::new(&tx);
+    let compra = service
+        .recibir_compra(id_compra, &recepciones)
+        .map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| format!("Error al confirmar la transacción: {}", e))?;
+
+    Ok(compra)
+}
+
+#[tauri::command]
+pub fn registrar_pago_compra(input: RegistrarPagoCompraInput, db_path: State<'_, PathBuf>) -> Result<i64, String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = CompraService::new(&conn);
+    service.registrar_pago(&input).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn delete_compra(id_compra: i64, db_path: State<'_, PathBuf>) -> Result<(), String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = CompraService::new(&conn);
+    service.delete_compra(id_compra).map_err(|e| e.to_string())
+}
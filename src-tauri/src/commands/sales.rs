@@ -3,6 +3,10 @@ use tauri::State;
 use crate::database;
 use crate::models::{
     AbonoVenta,
+    ConsultaLista,
+    Pagina,
+    ReporteGanancias,
+    ReporteKits,
     RegistrarAbonoInput,
     TipoPago,
     TopProducto,
@@ -11,10 +15,16 @@ use crate::models::{
     VentaCompletaInput,
     VentaCompletaOutput,
 };
+use crate::repos::producto_vendido_repo::ProductoVendidoRepo;
 use crate::repos::venta_repo::VentaRepo;
 use crate::services::abono_venta_service::AbonoVentaService;
+use crate::services::campana_service::CampanaService;
+use crate::services::clienta_service::ClientaService;
+use crate::services::ganancia_service::GananciaService;
 use crate::services::venta_service::VentaService;
 use crate::services::producto_vendido_service::ProductoVendidoService;
+use crate::services::kit_service::KitService;
+use crate::services::promocion_service::PromocionService;
 use crate::services::producto_service::ProductoService;
 
 pub fn get_sales_total_between_dates(
@@ -58,6 +68,20 @@ pub fn list_ventas(db_path: State<'_, PathBuf>) -> Result<Vec<Venta>, String>
     service.list_ventas().map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub fn list_ventas_paginadas(
+    consulta: Option<ConsultaLista>,
+    db_path: State<'_, PathBuf>,
+) -> Result<Pagina<Venta>, String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = VentaService::new(&conn);
+    service
+        .list_ventas_paginadas(&consulta.unwrap_or_default())
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub fn get_venta(id: i64, db_path: State<'_, PathBuf>) -> Result<Venta, String> 
 {
@@ -110,63 +134,76 @@ pub fn create_venta_completa(
         return Err("Debe agregar al menos un producto a la venta".to_string());
     }
 
-    let nombre = input.nombre_clienta.trim();
-    if nombre.is_empty() {
-        return Err("Debe ingresar el nombre de la clienta".to_string());
-    }
-
-    let apellido = input.apellido_clienta.trim();
-    if apellido.is_empty() {
-        return Err("Debe ingresar el apellido de la clienta".to_string());
-    }
+    // No se venden productos descontinuados
+    let ids: Vec<i64> = input.productos.iter().map(|item| item.id_producto).collect();
+    ProductoService::new(&conn)
+        .validar_activos_para_venta(&ids)
+        .map_err(|e| e.to_string())?;
 
-    // Calcula el total de la venta
-    let mut total_venta = 0.0;
-    for item in &input.productos {
-        total_venta += item.cantidad as f64 * item.precio_unitario;
-    }
+    // Campaña de la venta y precio de cada línea (el de la campaña salvo que se capture otro)
+    let (id_campana, precios) = CampanaService::new(&conn)
+        .precios_venta(&input)
+        .map_err(|e| e.to_string())?;
 
-    // Validar stock ANTES de iniciar la transacción
-    let producto_service = ProductoService::new(&conn);
-    for item in &input.productos {
-        let producto = producto_service
-            .get_producto(item.id_producto)
-            .map_err(|e| format!("Producto con ID {} no encontrado: {}", item.id_producto, e))?;
-
-        if producto.stock < item.cantidad {
-            return Err(format!(
-                "Stock insuficiente para '{}'. Disponible: {}, Solicitado: {}",
-                producto.nombre_producto, producto.stock, item.cantidad
-            ));
-        }
-    }
+    // Promociones vigentes, descuentos por línea y descuento de la venta
+    let calculo = PromocionService::new(&conn)
+        .calcular_venta(&input.fecha, &input.productos, &precios, input.descuento.as_ref())
+        .map_err(|e| e.to_string())?;
+    let total_venta = calculo.total;
+
+    // Validar stock ANTES de iniciar la transacción (los kits se validan contra sus componentes)
+    let items: Vec<(i64, i64)> = input
+        .productos
+        .iter()
+        .map(|item| (item.id_producto, item.cantidad))
+        .collect();
+    KitService::new(&conn)
+        .validar_stock_venta(&items)
+        .map_err(|e| e.to_string())?;
 
     // INICIAR TRANSACCIÓN
     let tx = conn.transaction().map_err(|e| e.to_string())?;
 
+    // Resuelve la clienta (existente, nueva o por nombre) dentro de la misma transacción
+    let clienta_service = ClientaService::new(&tx);
+    let clienta = clienta_service
+        .resolver_clienta_venta(&input)
+        .map_err(|e| e.to_string())?;
+
     // Inserta la venta en la tabla de ventas
     let venta_service = VentaService::new(&tx);
     let id_venta = venta_service
-        .create_venta_with_initial_abono(
+        .create_venta_para_clienta(
             &input.fecha,
-            nombre,
-            apellido,
+            &clienta,
             total_venta,
             &input.tipo_pago,
             abono_inicial,
         )
         .map_err(|e| format!("Error al crear venta: {}", e))?;
 
-    // Inserta cada producto(item) vendido
+    let venta_repo = VentaRepo { conn: &tx };
+    if let Some(id_campana) = id_campana {
+        venta_repo
+            .set_campana(id_venta, id_campana)
+            .map_err(|e| e.to_string())?;
+    }
+    venta_repo
+        .set_descuento(id_venta, &calculo)
+        .map_err(|e| e.to_string())?;
+
+    // Inserta cada producto(item) vendido con su subtotal neto; los triggers descuentan el stock y los lotes (FEFO)
     let producto_vendido_service = ProductoVendidoService::new(&tx);
+    let producto_vendido_repo = ProductoVendidoRepo { conn: &tx };
     let mut items_insertados = 0;
 
-    for item in &input.productos {
-        let subtotal = item.cantidad as f64 * item.precio_unitario;
-
-        producto_vendido_service
-            .create_producto_vendido(id_venta, item.id_producto, &item.nombre_producto, item.cantidad, item.precio_unitario, subtotal)
+    for (item, linea) in input.productos.iter().zip(&calculo.lineas) {
+        let id_producto_vendido = producto_vendido_service
+            .create_producto_vendido(id_venta, item.id_producto, &item.nombre_producto, item.cantidad, linea.precio_unitario, linea.subtotal)
             .map_err(|e| format!("Error al insertar producto con ID {}: {}", item.id_producto, e))?;
+        producto_vendido_repo
+            .set_descuento(id_producto_vendido, linea)
+            .map_err(|e| e.to_string())?;
 
         items_insertados += 1;
     }
@@ -178,40 +215,23 @@ pub fn create_venta_completa(
     // Retorna el resultado
     Ok(VentaCompletaOutput {
         id_venta,
+        subtotal_bruto: calculo.subtotal_bruto,
+        descuento: AbonoVentaService::normalize_money(calculo.descuento_lineas + calculo.descuento_venta),
         total_venta,
         items_insertados,
+        promociones: calculo.promociones,
     })
 }
 
-// Top 5 productos más vendidos (unidades + ingreso)
+// Top 5 productos más vendidos (unidades + ingreso); opcionalmente sumando variantes en su producto padre
 #[tauri::command]
-pub fn get_top_productos(db_path: State<'_, PathBuf>) -> Result<Vec<TopProducto>, String> {
+pub fn get_top_productos(agrupar_por_padre: Option<bool>, db_path: State<'_, PathBuf>) -> Result<Vec<TopProducto>, String> {
     let db_path: &PathBuf = db_path.inner();
     let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
 
-    let mut stmt = conn.prepare(
-        "SELECT nombre_producto_snapshot, \
-                SUM(cantidad) AS total_unidades, \
-                SUM(subtotal) AS total_ingreso \
-         FROM productos_vendidos \
-         GROUP BY nombre_producto_snapshot \
-         ORDER BY total_ingreso DESC \
-         LIMIT 5"
-    ).map_err(|e| e.to_string())?;
-
-    let rows = stmt.query_map([], |row| {
-        Ok(TopProducto {
-            nombre: row.get(0)?,
-            unidades: row.get(1)?,
-            ingreso: row.get(2)?,
-        })
-    }).map_err(|e| e.to_string())?;
-
-    let mut result = Vec::new();
-    for row in rows {
-        result.push(row.map_err(|e| e.to_string())?);
-    }
-    Ok(result)
+    let repo = ProductoVendidoRepo { conn: &conn };
+    repo.resumen_por_producto(agrupar_por_padre.unwrap_or(false), Some(5))
+        .map_err(|e| e.to_string())
 }
 
 //Comando tauri para obtener el total de ventas en los últimos 7 días
@@ -246,6 +266,38 @@ pub fn get_sales_month(db_path: State<'_, PathBuf>) -> Result<f64, String> {
     get_sales_total_between_dates(&conn, &start_date, &today).map_err(|e| e.to_string())
 }
 
+// Ganancia real del periodo (por venta y por producto) con el costo congelado al vender;
+// con `id_campana` solo cuenta las ventas de esa campaña
+#[tauri::command]
+pub fn get_reporte_ganancias(
+    fecha_inicio: String,
+    fecha_fin: String,
+    id_campana: Option<i64>,
+    db_path: State<'_, PathBuf>,
+) -> Result<ReporteGanancias, String> {
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = GananciaService::new(&conn);
+    service
+        .get_reporte(&fecha_inicio, &fecha_fin, id_campana)
+        .map_err(|e| e.to_string())
+}
+
+// Kits vendidos en el periodo y unidades que tomaron de cada componente
+#[tauri::command]
+pub fn get_reporte_kits(
+    fecha_inicio: String,
+    fecha_fin: String,
+    db_path: State<'_, PathBuf>,
+) -> Result<ReporteKits, String> {
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = KitService::new(&conn);
+    service
+        .get_reporte(&fecha_inicio, &fecha_fin)
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub fn registrar_abono_venta(
     input: RegistrarAbonoInput,
@@ -285,10 +337,28 @@ pub fn get_cobranza_summary(
 
 #[tauri::command]
 pub fn list_ventas_con_cobranza(
+    id_campana: Option<i64>,
     db_path: State<'_, PathBuf>,
 ) -> Result<Vec<VentaCobranzaView>, String> {
     let db_path: &PathBuf = db_path.inner();
     let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
     let repo = VentaRepo { conn: &conn };
-    repo.list_with_cobranza().map_err(|e| e.to_string())
+    match id_campana {
+        Some(id_campana) => repo.list_with_cobranza_by_campana(id_campana),
+        None => repo.list_with_cobranza(),
+    }
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn list_ventas_con_cobranza_paginadas(
+    consulta: Option<ConsultaLista>,
+    db_path: State<'_, PathBuf>,
+) -> Result<Pagina<VentaCobranzaView>, String> {
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = VentaService::new(&conn);
+    service
+        .list_ventas_con_cobranza_paginadas(&consulta.unwrap_or_default())
+        .map_err(|e| e.to_string())
 }
\ No newline at end of file
@@ -1,7 +1,7 @@
 use std::path::PathBuf;
 use tauri::State;
 use crate::database;
-use crate::models::ProductoVendido;
+use crate::models::{ConsultaLista, Pagina, ProductoVendido};
 use crate::services::producto_vendido_service::ProductoVendidoService;
 
 #[tauri::command]
@@ -13,6 +13,20 @@ pub fn list_productos_vendidos(db_path: State<'_, PathBuf>) -> Result<Vec<Produc
     service.list_productos_vendidos().map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub fn list_productos_vendidos_paginados(
+    consulta: Option<ConsultaLista>,
+    db_path: State<'_, PathBuf>,
+) -> Result<Pagina<ProductoVendido>, String>
+{
+    let db_path: &PathBuf = db_path.inner();
+    let conn = database::init_db(db_path).map_err(|e| e.to_string())?;
+    let service = ProductoVendidoService::new(&conn);
+    service
+        .list_productos_vendidos_paginados(&consulta.unwrap_or_default())
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub fn get_producto_vendido(id: i64, db_path: State<'_, PathBuf>) -> Result<ProductoVendido, String> 
 {
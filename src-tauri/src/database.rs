@@ -27,6 +27,23 @@ pub fn init_db<P: AsRef<Path>> (db_path: P) -> Result<Connection> {
     migrate_add_apellido_clienta(&conn)?;
     migrate_create_perfil(&conn)?;
     migrate_create_abonos_venta(&conn)?;
+    migrate_create_clientas(&conn)?;
+    migrate_create_movimientos_inventario(&conn)?;
+    migrate_create_compras(&conn)?;
+    migrate_add_costos(&conn)?;
+    migrate_create_historial_precios(&conn)?;
+    migrate_add_stock_minimo(&conn)?;
+    migrate_create_lotes(&conn)?;
+    migrate_create_conteos(&conn)?;
+    migrate_add_variantes(&conn)?;
+    migrate_add_codigos(&conn)?;
+    migrate_create_kits(&conn)?;
+    migrate_create_campanas(&conn)?;
+    migrate_add_descuentos(&conn)?;
+    migrate_relative_image_paths(&conn)?;
+    migrate_add_categoria_padre(&conn)?;
+    migrate_create_busqueda_productos(&conn)?;
+    migrate_recrear_triggers_inventario(&conn)?;
 
     Ok(conn)
 
@@ -194,3 +211,895 @@ fn migrate_create_abonos_venta(conn: &rusqlite::Connection) -> rusqlite::Result<
     Ok(())
 }
 
+fn migrate_create_clientas(conn: &rusqlite::Connection) -> rusqlite::Result<()>
+{
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS clientas (
+            id_clienta INTEGER PRIMARY KEY AUTOINCREMENT,
+            nombre TEXT NOT NULL,
+            apellido TEXT NOT NULL DEFAULT '',
+            telefono TEXT NOT NULL DEFAULT '',
+            direccion TEXT NOT NULL DEFAULT '',
+            notas TEXT NOT NULL DEFAULT '',
+            creado_at TEXT NOT NULL DEFAULT (datetime('now','localtime'))
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_clientas_nombre ON clientas(nombre, apellido);"
+    )?;
+
+    if !ensure_column_exists(conn, "ventas", "id_clienta")?
+    {
+        conn.execute(
+            "ALTER TABLE ventas ADD COLUMN id_clienta INTEGER REFERENCES clientas(id_clienta) ON DELETE SET NULL",
+            [],
+        )?;
+
+        // Unifica los pares nombre/apellido escritos de distintas formas en una sola clienta
+        conn.execute(
+            "INSERT INTO clientas (nombre, apellido)
+             SELECT TRIM(nombre_clienta), TRIM(apellido_clienta)
+             FROM ventas
+             WHERE TRIM(nombre_clienta) <> ''
+             GROUP BY LOWER(TRIM(nombre_clienta)), LOWER(TRIM(apellido_clienta))",
+            [],
+        )?;
+
+        conn.execute(
+            "UPDATE ventas
+             SET id_clienta = (
+                SELECT c.id_clienta FROM clientas c
+                WHERE LOWER(c.nombre) = LOWER(TRIM(ventas.nombre_clienta))
+                  AND LOWER(c.apellido) = LOWER(TRIM(ventas.apellido_clienta))
+                ORDER BY c.id_clienta
+                LIMIT 1
+             )
+             WHERE id_clienta IS NULL",
+            [],
+        )?;
+    }
+
+    conn.execute("CREATE INDEX IF NOT EXISTS idx_ventas_clienta ON ventas(id_clienta)", [])?;
+    Ok(())
+}
+
+fn migrate_create_movimientos_inventario(conn: &rusqlite::Connection) -> rusqlite::Result<()>
+{
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS movimientos_inventario (
+            id_movimiento INTEGER PRIMARY KEY AUTOINCREMENT,
+            id_producto INTEGER NOT NULL REFERENCES productos(id_producto) ON DELETE CASCADE,
+            delta INTEGER NOT NULL,
+            stock_resultante INTEGER NOT NULL,
+            motivo TEXT NOT NULL CHECK (motivo IN ('venta', 'devolucion', 'ajuste', 'compra', 'merma', 'inicial')),
+            id_referencia INTEGER,
+            nota TEXT NOT NULL DEFAULT '',
+            fecha TEXT NOT NULL DEFAULT (datetime('now','localtime'))
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_movimientos_producto ON movimientos_inventario(id_producto, fecha);
+        CREATE INDEX IF NOT EXISTS idx_movimientos_fecha ON movimientos_inventario(fecha);"
+    )?;
+
+    if !ensure_column_exists(conn, "movimientos_inventario", "motivo_ajuste")?
+    {
+        conn.execute("ALTER TABLE movimientos_inventario ADD COLUMN motivo_ajuste TEXT", [])?;
+    }
+
+    Ok(())
+}
+
+fn migrate_create_compras(conn: &rusqlite::Connection) -> rusqlite::Result<()>
+{
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS proveedores (
+            id_proveedor INTEGER PRIMARY KEY AUTOINCREMENT,
+            nombre TEXT NOT NULL UNIQUE,
+            contacto TEXT NOT NULL DEFAULT '',
+            telefono TEXT NOT NULL DEFAULT '',
+            notas TEXT NOT NULL DEFAULT '',
+            creado_at TEXT NOT NULL DEFAULT (datetime('now','localtime'))
+        );
+
+        CREATE TABLE IF NOT EXISTS compras (
+            id_compra INTEGER PRIMARY KEY AUTOINCREMENT,
+            id_proveedor INTEGER REFERENCES proveedores(id_proveedor) ON DELETE SET NULL,
+            fecha TEXT NOT NULL DEFAULT (datetime('now','localtime')),
+            estado TEXT NOT NULL DEFAULT 'Pendiente' CHECK (estado IN ('Pendiente', 'Parcial', 'Recibida')),
+            total_compra REAL NOT NULL DEFAULT 0 CHECK (total_compra >= 0),
+            notas TEXT NOT NULL DEFAULT '',
+            creado_at TEXT NOT NULL DEFAULT (datetime('now','localtime'))
+        );
+
+        CREATE TABLE IF NOT EXISTS compras_detalle (
+            id_detalle INTEGER PRIMARY KEY AUTOINCREMENT,
+            id_compra INTEGER NOT NULL REFERENCES compras(id_compra) ON DELETE CASCADE,
+            id_producto INTEGER NOT NULL REFERENCES productos(id_producto),
+            cantidad_pedida INTEGER NOT NULL CHECK (cantidad_pedida > 0),
+            cantidad_recibida INTEGER NOT NULL DEFAULT 0 CHECK (cantidad_recibida >= 0 AND cantidad_recibida <= cantidad_pedida),
+            costo_unitario REAL NOT NULL CHECK (costo_unitario >= 0),
+            subtotal REAL NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS pagos_compra (
+            id_pago INTEGER PRIMARY KEY AUTOINCREMENT,
+            id_compra INTEGER NOT NULL REFERENCES compras(id_compra) ON DELETE CASCADE,
+            monto_pago REAL NOT NULL CHECK (monto_pago > 0),
+            fecha_pago TEXT NOT NULL DEFAULT (datetime('now','localtime')),
+            observacion TEXT NOT NULL DEFAULT '',
+            creado_at TEXT NOT NULL DEFAULT (datetime('now','localtime'))
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_compras_proveedor ON compras(id_proveedor);
+        CREATE INDEX IF NOT EXISTS idx_compras_fecha ON compras(fecha);
+        CREATE INDEX IF NOT EXISTS idx_compras_detalle_compra ON compras_detalle(id_compra);
+        CREATE INDEX IF NOT EXISTS idx_compras_detalle_producto ON compras_detalle(id_producto);
+        CREATE INDEX IF NOT EXISTS idx_pagos_compra_compra ON pagos_compra(id_compra);"
+    )?;
+    Ok(())
+}
+
+// Costo promedio ponderado por producto y costo congelado en cada línea vendida
+fn migrate_add_costos(conn: &rusqlite::Connection) -> rusqlite::Result<()>
+{
+    if !ensure_column_exists(conn, "productos", "costo_promedio")?
+    {
+        conn.execute(
+            "ALTER TABLE productos ADD COLUMN costo_promedio REAL NOT NULL DEFAULT 0.0",
+            [],
+        )?;
+        // El mejor dato disponible para el costo inicial es el precio consultora vigente
+        conn.execute("UPDATE productos SET costo_promedio = precio_consultora", [])?;
+    }
+
+    if !ensure_column_exists(conn, "productos_vendidos", "costo_unitario")?
+    {
+        conn.execute(
+            "ALTER TABLE productos_vendidos ADD COLUMN costo_unitario REAL NOT NULL DEFAULT 0.0",
+            [],
+        )?;
+        // Ventas anteriores: no hay historial, se toma el costo actual del producto
+        conn.execute(
+            "UPDATE productos_vendidos
+             SET costo_unitario = COALESCE(
+                (SELECT p.costo_promedio FROM productos p WHERE p.id_producto = productos_vendidos.id_producto),
+                0.0
+             )",
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+fn migrate_create_historial_precios(conn: &rusqlite::Connection) -> rusqlite::Result<()>
+{
+    let existia: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'historial_precios')",
+        [],
+        |row| row.get(0),
+    )?;
+
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS historial_precios (
+            id_historial INTEGER PRIMARY KEY AUTOINCREMENT,
+            id_producto INTEGER NOT NULL REFERENCES productos(id_producto) ON DELETE CASCADE,
+            campo TEXT NOT NULL CHECK (campo IN ('precio_consultora', 'precio_publico')),
+            precio_anterior REAL,
+            precio_nuevo REAL NOT NULL,
+            fecha TEXT NOT NULL DEFAULT (datetime('now','localtime')),
+            origen TEXT NOT NULL DEFAULT 'edicion'
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_historial_precios_producto ON historial_precios(id_producto, fecha);"
+    )?;
+
+    // Punto de partida de la línea de tiempo para productos existentes
+    if !existia
+    {
+        conn.execute_batch(
+            "INSERT INTO historial_precios (id_producto, campo, precio_anterior, precio_nuevo, origen)
+             SELECT id_producto, 'precio_consultora', NULL, precio_consultora, 'alta' FROM productos;
+             INSERT INTO historial_precios (id_producto, campo, precio_anterior, precio_nuevo, origen)
+             SELECT id_producto, 'precio_publico', NULL, precio_publico, 'alta' FROM productos;"
+        )?;
+    }
+    Ok(())
+}
+
+fn migrate_add_stock_minimo(conn: &rusqlite::Connection) -> rusqlite::Result<()>
+{
+    if !ensure_column_exists(conn, "productos", "stock_minimo")?
+    {
+        conn.execute(
+            "ALTER TABLE productos ADD COLUMN stock_minimo INTEGER CHECK (stock_minimo IS NULL OR stock_minimo >= 0)",
+            [],
+        )?;
+    }
+
+    if !ensure_column_exists(conn, "categorias", "stock_minimo_default")?
+    {
+        conn.execute(
+            "ALTER TABLE categorias ADD COLUMN stock_minimo_default INTEGER CHECK (stock_minimo_default IS NULL OR stock_minimo_default >= 0)",
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+// Reparte `{fila}.cantidad` entre los lotes con existencia, primero el que caduca antes (FEFO);
+// los lotes sin fecha de caducidad se consumen al final. Si los lotes no alcanzan se aborta, igual
+// que LoteService::consumir_fefo, para que el stock siga siendo la suma de sus lotes
+fn sql_consumir_lotes(fila: &str) -> String
+{
+    format!(
+        "SELECT RAISE(ABORT, 'Los lotes del producto no alcanzan para la venta')
+          WHERE (SELECT COALESCE(SUM(cantidad), 0) FROM lotes WHERE id_producto = {fila}.id_producto) < {fila}.cantidad;
+
+          INSERT INTO lotes_vendidos (id_producto_vendido, id_lote, cantidad)
+          SELECT {fila}.id_producto_vendido, id_lote, MIN(cantidad, {fila}.cantidad - previo)
+          FROM (
+            SELECT id_lote, cantidad,
+                   COALESCE(SUM(cantidad) OVER (
+                     ORDER BY fecha_caducidad IS NULL, fecha_caducidad, id_lote
+                     ROWS BETWEEN UNBOUNDED PRECEDING AND 1 PRECEDING
+                   ), 0) AS previo
+            FROM lotes
+            WHERE id_producto = {fila}.id_producto AND cantidad > 0
+          )
+          WHERE previo < {fila}.cantidad;
+
+          UPDATE lotes
+          SET cantidad = cantidad - (
+            SELECT lv.cantidad FROM lotes_vendidos lv
+            WHERE lv.id_producto_vendido = {fila}.id_producto_vendido AND lv.id_lote = lotes.id_lote
+          )
+          WHERE id_producto = {fila}.id_producto
+            AND id_lote IN (SELECT id_lote FROM lotes_vendidos WHERE id_producto_vendido = {fila}.id_producto_vendido);"
+    )
+}
+
+// Devuelve a cada lote lo que se tomó de él; las ventas anteriores a los lotes regresan al lote sin código.
+// Solo toca los lotes de `{fila}.id_producto`: una línea de kit comparte id_producto_vendido entre componentes
+fn sql_devolver_lotes(fila: &str) -> String
+{
+    let sin_lotes = format!(
+        "NOT EXISTS (
+              SELECT 1 FROM lotes_vendidos lv INNER JOIN lotes l ON l.id_lote = lv.id_lote
+              WHERE lv.id_producto_vendido = {fila}.id_producto_vendido AND l.id_producto = {fila}.id_producto
+            )"
+    );
+    format!(
+        "INSERT INTO lotes (id_producto, codigo_lote, fecha_caducidad, cantidad)
+          SELECT {fila}.id_producto, '', NULL, 0
+          WHERE {sin_lotes}
+            AND NOT EXISTS (
+              SELECT 1 FROM lotes
+              WHERE id_producto = {fila}.id_producto AND codigo_lote = '' AND fecha_caducidad IS NULL
+            );
+
+          UPDATE lotes
+          SET cantidad = cantidad + {fila}.cantidad
+          WHERE id_producto = {fila}.id_producto AND codigo_lote = '' AND fecha_caducidad IS NULL
+            AND {sin_lotes};
+
+          UPDATE lotes
+          SET cantidad = cantidad + (
+            SELECT lv.cantidad FROM lotes_vendidos lv
+            WHERE lv.id_producto_vendido = {fila}.id_producto_vendido AND lv.id_lote = lotes.id_lote
+          )
+          WHERE id_producto = {fila}.id_producto
+            AND id_lote IN (SELECT id_lote FROM lotes_vendidos WHERE id_producto_vendido = {fila}.id_producto_vendido);
+
+          DELETE FROM lotes_vendidos
+          WHERE id_producto_vendido = {fila}.id_producto_vendido
+            AND id_lote IN (SELECT id_lote FROM lotes WHERE id_producto = {fila}.id_producto);"
+    )
+}
+
+// Lotes con fecha de caducidad; productos.stock siempre es la suma de sus lotes
+fn migrate_create_lotes(conn: &rusqlite::Connection) -> rusqlite::Result<()>
+{
+    let existia: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'lotes')",
+        [],
+        |row| row.get(0),
+    )?;
+
+    // lotes_vendidos no tiene FK a productos_vendidos: los triggers la limpian después de devolver las cantidades
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS lotes (
+            id_lote INTEGER PRIMARY KEY AUTOINCREMENT,
+            id_producto INTEGER NOT NULL REFERENCES productos(id_producto) ON DELETE CASCADE,
+            codigo_lote TEXT NOT NULL DEFAULT '',
+            fecha_caducidad TEXT,
+            cantidad INTEGER NOT NULL DEFAULT 0 CHECK (cantidad >= 0),
+            creado_at TEXT NOT NULL DEFAULT (datetime('now','localtime'))
+        );
+
+        CREATE UNIQUE INDEX IF NOT EXISTS idx_lotes_producto_codigo
+            ON lotes(id_producto, codigo_lote, IFNULL(fecha_caducidad, ''));
+        CREATE INDEX IF NOT EXISTS idx_lotes_caducidad ON lotes(fecha_caducidad);
+
+        CREATE TABLE IF NOT EXISTS lotes_vendidos (
+            id_producto_vendido INTEGER NOT NULL,
+            id_lote INTEGER NOT NULL REFERENCES lotes(id_lote) ON DELETE CASCADE,
+            cantidad INTEGER NOT NULL CHECK (cantidad > 0),
+            PRIMARY KEY (id_producto_vendido, id_lote)
+        );"
+    )?;
+
+    // El stock existente queda en un lote sin código ni caducidad
+    if !existia
+    {
+        conn.execute(
+            "INSERT INTO lotes (id_producto, codigo_lote, fecha_caducidad, cantidad)
+             SELECT id_producto, '', NULL, stock FROM productos WHERE stock > 0",
+            [],
+        )?;
+    }
+
+    Ok(())
+}
+
+// Sesiones de conteo físico; stock_sistema y precio_consultora se congelan al aplicar el conteo
+fn migrate_create_conteos(conn: &rusqlite::Connection) -> rusqlite::Result<()>
+{
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS conteos_inventario (
+            id_conteo INTEGER PRIMARY KEY AUTOINCREMENT,
+            descripcion TEXT NOT NULL DEFAULT '',
+            estado TEXT NOT NULL DEFAULT 'Abierto' CHECK (estado IN ('Abierto', 'Cerrado')),
+            creado_at TEXT NOT NULL DEFAULT (datetime('now','localtime')),
+            cerrado_at TEXT
+        );
+
+        CREATE TABLE IF NOT EXISTS conteos_detalle (
+            id_conteo INTEGER NOT NULL REFERENCES conteos_inventario(id_conteo) ON DELETE CASCADE,
+            id_producto INTEGER NOT NULL REFERENCES productos(id_producto) ON DELETE CASCADE,
+            cantidad_contada INTEGER NOT NULL CHECK (cantidad_contada >= 0),
+            stock_sistema INTEGER,
+            precio_consultora REAL,
+            contado_at TEXT NOT NULL DEFAULT (datetime('now','localtime')),
+            PRIMARY KEY (id_conteo, id_producto)
+        );"
+    )?;
+    Ok(())
+}
+
+// Variantes: filas de productos que apuntan a su producto padre
+fn migrate_add_variantes(conn: &rusqlite::Connection) -> rusqlite::Result<()>
+{
+    if !ensure_column_exists(conn, "productos", "id_producto_padre")?
+    {
+        conn.execute(
+            "ALTER TABLE productos ADD COLUMN id_producto_padre INTEGER REFERENCES productos(id_producto)",
+            [],
+        )?;
+    }
+
+    for column in ["tono", "tamano", "fragancia"]
+    {
+        if !ensure_column_exists(conn, "productos", column)?
+        {
+            conn.execute(&format!("ALTER TABLE productos ADD COLUMN {} TEXT", column), [])?;
+        }
+    }
+
+    if !ensure_column_exists(conn, "productos", "precio_propio")?
+    {
+        conn.execute(
+            "ALTER TABLE productos ADD COLUMN precio_propio INTEGER NOT NULL DEFAULT 0",
+            [],
+        )?;
+    }
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_productos_padre ON productos(id_producto_padre)",
+        [],
+    )?;
+    Ok(())
+}
+
+fn migrate_add_codigos(conn: &rusqlite::Connection) -> rusqlite::Result<()>
+{
+    for column in ["sku", "codigo_barras"]
+    {
+        if !ensure_column_exists(conn, "productos", column)?
+        {
+            conn.execute(&format!("ALTER TABLE productos ADD COLUMN {} TEXT", column), [])?;
+        }
+    }
+
+    // Únicos entre todos los productos (también descontinuados); NULL = sin código
+    conn.execute(
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_productos_sku ON productos(sku COLLATE NOCASE) WHERE sku IS NOT NULL",
+        [],
+    )?;
+    conn.execute(
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_productos_codigo_barras ON productos(codigo_barras) WHERE codigo_barras IS NOT NULL",
+        [],
+    )?;
+    Ok(())
+}
+
+fn sql_es_kit(fila: &str) -> String
+{
+    format!("EXISTS (SELECT 1 FROM productos WHERE id_producto = {fila}.id_producto AND es_kit = 1)")
+}
+
+// Kits: un producto que al venderse descuenta el stock de sus componentes.
+// Las líneas de kit no mueven stock ni lotes propios; kit_consumos guarda lo que tomó de cada
+// componente (los triggers de sql_triggers_inventario hacen el descuento), y el stock del kit se
+// deriva de sus componentes
+fn migrate_create_kits(conn: &rusqlite::Connection) -> rusqlite::Result<()>
+{
+    if !ensure_column_exists(conn, "productos", "es_kit")?
+    {
+        conn.execute("ALTER TABLE productos ADD COLUMN es_kit INTEGER NOT NULL DEFAULT 0", [])?;
+    }
+
+    // kit_consumos no tiene FK a productos_vendidos, igual que lotes_vendidos
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS kit_componentes (
+            id_kit INTEGER NOT NULL REFERENCES productos(id_producto) ON DELETE CASCADE,
+            id_componente INTEGER NOT NULL REFERENCES productos(id_producto),
+            cantidad INTEGER NOT NULL CHECK (cantidad > 0),
+            PRIMARY KEY (id_kit, id_componente)
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_kit_componentes_componente ON kit_componentes(id_componente);
+
+        CREATE TABLE IF NOT EXISTS kit_consumos (
+            id_producto_vendido INTEGER NOT NULL,
+            id_venta INTEGER NOT NULL,
+            id_producto INTEGER NOT NULL REFERENCES productos(id_producto),
+            cantidad INTEGER NOT NULL CHECK (cantidad > 0),
+            PRIMARY KEY (id_producto_vendido, id_producto)
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_kit_consumos_venta ON kit_consumos(id_venta);"
+    )?;
+
+    Ok(())
+}
+
+// Recalcula el stock derivado de los kits que usan el componente `{fila}.id_producto`
+fn sql_recalcular_stock_kits(fila: &str) -> String
+{
+    format!(
+        "UPDATE productos
+          SET stock = COALESCE((
+                SELECT MIN(c.stock / kc.cantidad)
+                FROM kit_componentes kc
+                INNER JOIN productos c ON c.id_producto = kc.id_componente
+                WHERE kc.id_kit = productos.id_producto
+              ), 0)
+          WHERE es_kit = 1
+            AND id_producto IN (SELECT id_kit FROM kit_componentes WHERE id_componente = {fila}.id_producto);"
+    )
+}
+
+// Campañas (periodos de catálogo) con precios propios por producto; cada venta guarda su campaña
+fn migrate_create_campanas(conn: &rusqlite::Connection) -> rusqlite::Result<()>
+{
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS campanas (
+            id_campana INTEGER PRIMARY KEY AUTOINCREMENT,
+            nombre TEXT NOT NULL,
+            fecha_inicio TEXT NOT NULL,
+            fecha_fin TEXT NOT NULL,
+            creado_at TEXT NOT NULL DEFAULT (datetime('now','localtime')),
+            CHECK (fecha_fin >= fecha_inicio)
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_campanas_fechas ON campanas(fecha_inicio, fecha_fin);
+
+        CREATE TABLE IF NOT EXISTS precios_campana (
+            id_campana INTEGER NOT NULL REFERENCES campanas(id_campana) ON DELETE CASCADE,
+            id_producto INTEGER NOT NULL REFERENCES productos(id_producto) ON DELETE CASCADE,
+            precio_consultora REAL NOT NULL CHECK (precio_consultora >= 0),
+            precio_publico REAL NOT NULL CHECK (precio_publico >= precio_consultora),
+            PRIMARY KEY (id_campana, id_producto)
+        );"
+    )?;
+
+    if !ensure_column_exists(conn, "ventas", "id_campana")?
+    {
+        conn.execute(
+            "ALTER TABLE ventas ADD COLUMN id_campana INTEGER REFERENCES campanas(id_campana)",
+            [],
+        )?;
+    }
+
+    // Sin campaña explícita, la venta queda en la campaña vigente en su fecha
+    conn.execute_batch(
+        "CREATE INDEX IF NOT EXISTS idx_ventas_campana ON ventas(id_campana);
+
+        CREATE TRIGGER IF NOT EXISTS trg_ventas_campana_after_insert
+        AFTER INSERT ON ventas
+        WHEN NEW.id_campana IS NULL
+        BEGIN
+          UPDATE ventas
+          SET id_campana = (
+            SELECT id_campana FROM campanas
+            WHERE DATE(NEW.fecha) BETWEEN fecha_inicio AND fecha_fin
+            ORDER BY fecha_inicio DESC
+            LIMIT 1
+          )
+          WHERE id_venta = NEW.id_venta;
+        END;"
+    )?;
+    Ok(())
+}
+
+fn migrate_add_descuentos(conn: &rusqlite::Connection) -> rusqlite::Result<()>
+{
+    // Reglas de promoción; cada una aplica a un producto o a todos los de una categoría
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS promociones (
+            id_promocion INTEGER PRIMARY KEY AUTOINCREMENT,
+            nombre TEXT NOT NULL,
+            tipo TEXT NOT NULL CHECK (tipo IN ('NxM', 'PrecioPaquete', 'Porcentaje')),
+            id_producto INTEGER REFERENCES productos(id_producto) ON DELETE CASCADE,
+            id_categoria INTEGER REFERENCES categorias(id_categoria) ON DELETE CASCADE,
+            cantidad_lleva INTEGER,
+            cantidad_paga INTEGER,
+            precio_paquete REAL,
+            porcentaje REAL,
+            fecha_inicio TEXT,
+            fecha_fin TEXT,
+            activa INTEGER NOT NULL DEFAULT 1,
+            creado_at TEXT NOT NULL DEFAULT (datetime('now','localtime')),
+            CHECK ((id_producto IS NULL) <> (id_categoria IS NULL))
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_promociones_producto ON promociones(id_producto);
+        CREATE INDEX IF NOT EXISTS idx_promociones_categoria ON promociones(id_categoria);"
+    )?;
+
+    // Descuento de línea: `descuento` es el total (promoción + manual) y `subtotal` ya viene neto
+    let columnas_linea = [
+        ("descuento_tipo", "TEXT"),
+        ("descuento_valor", "REAL NOT NULL DEFAULT 0"),
+        ("descuento", "REAL NOT NULL DEFAULT 0"),
+        ("descuento_promocion", "REAL NOT NULL DEFAULT 0"),
+        ("id_promocion", "INTEGER REFERENCES promociones(id_promocion)"),
+    ];
+    for (columna, definicion) in columnas_linea
+    {
+        if !ensure_column_exists(conn, "productos_vendidos", columna)?
+        {
+            conn.execute(&format!("ALTER TABLE productos_vendidos ADD COLUMN {} {}", columna, definicion), [])?;
+        }
+    }
+
+    // Descuento sobre el total de la venta; `total_venta` ya viene neto
+    let columnas_venta = [
+        ("descuento_tipo", "TEXT"),
+        ("descuento_valor", "REAL NOT NULL DEFAULT 0"),
+        ("descuento", "REAL NOT NULL DEFAULT 0"),
+    ];
+    for (columna, definicion) in columnas_venta
+    {
+        if !ensure_column_exists(conn, "ventas", columna)?
+        {
+            conn.execute(&format!("ALTER TABLE ventas ADD COLUMN {} {}", columna, definicion), [])?;
+        }
+    }
+    Ok(())
+}
+
+// Las rutas de imagen se guardaban absolutas y se rompían al restaurar un respaldo en otra
+// computadora; se dejan relativas a `images/`. También repara las de un respaldo importado
+fn migrate_relative_image_paths(conn: &rusqlite::Connection) -> rusqlite::Result<()>
+{
+    let tablas = [("productos", "id_producto", "ruta_imagen"), ("perfil", "id", "ruta_foto")];
+    for (tabla, id, columna) in tablas
+    {
+        let rutas: Vec<(i64, String)> = {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {id}, {columna} FROM {tabla} WHERE {columna} LIKE '%/%' OR {columna} LIKE '%\\%'"
+            ))?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            rows.collect::<Result<_, _>>()?
+        };
+        for (id_fila, ruta) in rutas
+        {
+            let relativa = crate::imagen::ruta_relativa(&ruta);
+            if relativa != ruta
+            {
+                conn.execute(
+                    &format!("UPDATE {tabla} SET {columna} = ?1 WHERE {id} = ?2"),
+                    rusqlite::params![relativa, id_fila],
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
+
+// Subcategorías: cada categoría puede colgar de otra (Maquillaje > Labios > Labiales);
+// los ciclos los impide CategoriaService
+fn migrate_add_categoria_padre(conn: &rusqlite::Connection) -> rusqlite::Result<()>
+{
+    if !ensure_column_exists(conn, "categorias", "id_categoria_padre")?
+    {
+        conn.execute(
+            "ALTER TABLE categorias ADD COLUMN id_categoria_padre INTEGER REFERENCES categorias(id_categoria)",
+            [],
+        )?;
+    }
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_categorias_padre ON categorias(id_categoria_padre)",
+        [],
+    )?;
+    Ok(())
+}
+
+// Búsqueda de texto completo: `productos_fts` (FTS5) indexa nombre, atributos de variante, categoría,
+// códigos y notas de cada producto (rowid = id_producto). `remove_diacritics 2` hace que "rimel"
+// encuentre "rímel". Los triggers la mantienen al día; los cambios de stock no la tocan
+fn migrate_create_busqueda_productos(conn: &rusqlite::Connection) -> rusqlite::Result<()>
+{
+    if !ensure_column_exists(conn, "productos", "notas")?
+    {
+        conn.execute("ALTER TABLE productos ADD COLUMN notas TEXT", [])?;
+    }
+
+    let existe: bool = conn.query_row(
+        "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = 'productos_fts'",
+        [],
+        |row| row.get(0),
+    )?;
+    if existe
+    {
+        return Ok(());
+    }
+
+    conn.execute_batch(&format!(
+        "CREATE VIRTUAL TABLE productos_fts USING fts5(
+            nombre, atributos, categoria, codigos, notas,
+            tokenize = 'unicode61 remove_diacritics 2'
+        );
+
+        INSERT INTO productos_fts (rowid, nombre, atributos, categoria, codigos, notas)
+          SELECT {valores_p} FROM productos p;
+
+        CREATE TRIGGER IF NOT EXISTS trg_productos_fts_after_insert
+        AFTER INSERT ON productos
+        BEGIN
+          INSERT INTO productos_fts (rowid, nombre, atributos, categoria, codigos, notas)
+            SELECT {valores_new};
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS trg_productos_fts_after_update
+        AFTER UPDATE OF nombre_producto, id_categoria, tono, tamano, fragancia, sku, codigo_barras, notas ON productos
+        BEGIN
+          DELETE FROM productos_fts WHERE rowid = OLD.id_producto;
+          INSERT INTO productos_fts (rowid, nombre, atributos, categoria, codigos, notas)
+            SELECT {valores_new};
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS trg_productos_fts_after_delete
+        AFTER DELETE ON productos
+        BEGIN
+          DELETE FROM productos_fts WHERE rowid = OLD.id_producto;
+        END;
+
+        -- Renombrar una categoría reindexa sus productos
+        CREATE TRIGGER IF NOT EXISTS trg_categorias_fts_after_update
+        AFTER UPDATE OF nombre ON categorias
+        BEGIN
+          DELETE FROM productos_fts WHERE rowid IN (SELECT id_producto FROM productos WHERE id_categoria = NEW.id_categoria);
+          INSERT INTO productos_fts (rowid, nombre, atributos, categoria, codigos, notas)
+            SELECT {valores_p} FROM productos p WHERE p.id_categoria = NEW.id_categoria;
+        END;",
+        valores_p = sql_valores_fts("p"),
+        valores_new = sql_valores_fts("NEW"),
+    ))?;
+    Ok(())
+}
+
+// Columnas de `productos_fts` para la fila `{fila}` de productos
+fn sql_valores_fts(fila: &str) -> String
+{
+    format!(
+        "{fila}.id_producto,
+         {fila}.nombre_producto,
+         TRIM(COALESCE({fila}.tono, '') || ' ' || COALESCE({fila}.tamano, '') || ' ' || COALESCE({fila}.fragancia, '')),
+         COALESCE((SELECT c.nombre FROM categorias c WHERE c.id_categoria = {fila}.id_categoria), ''),
+         TRIM(COALESCE({fila}.sku, '') || ' ' || COALESCE({fila}.codigo_barras, '')),
+         COALESCE({fila}.notas, '')"
+    )
+}
+
+// Triggers que mueven stock, lotes, kits y kardex con cada venta, cada uno con su única definición.
+// Las líneas de kit no mueven stock ni lotes propios: kit_consumos guarda lo que tomó de cada componente
+fn sql_triggers_inventario() -> Vec<(&'static str, String)>
+{
+    let kit_new = sql_es_kit("NEW");
+    let kit_old = sql_es_kit("OLD");
+    let consumir_new = sql_consumir_lotes("NEW");
+    let devolver_old = sql_devolver_lotes("OLD");
+
+    vec![
+        ("trg_prodvend_after_insert", format!(
+            "CREATE TRIGGER trg_prodvend_after_insert
+        AFTER INSERT ON productos_vendidos
+        WHEN NOT {kit_new}
+        BEGIN
+          UPDATE productos
+          SET stock = stock - NEW.cantidad,
+              actualizado_at = datetime('now','localtime')
+          WHERE id_producto = NEW.id_producto;
+
+          INSERT INTO movimientos_inventario (id_producto, delta, stock_resultante, motivo, id_referencia)
+          SELECT NEW.id_producto, -NEW.cantidad, stock, 'venta', NEW.id_venta
+          FROM productos WHERE id_producto = NEW.id_producto;
+        END;"
+        )),
+        ("trg_prodvend_after_delete", format!(
+            "CREATE TRIGGER trg_prodvend_after_delete
+        AFTER DELETE ON productos_vendidos
+        WHEN NOT {kit_old}
+        BEGIN
+          UPDATE productos
+          SET stock = stock + OLD.cantidad,
+              actualizado_at = datetime('now','localtime')
+          WHERE id_producto = OLD.id_producto;
+
+          INSERT INTO movimientos_inventario (id_producto, delta, stock_resultante, motivo, id_referencia)
+          SELECT OLD.id_producto, OLD.cantidad, stock, 'devolucion', OLD.id_venta
+          FROM productos WHERE id_producto = OLD.id_producto;
+        END;"
+        )),
+        // Devuelve lo anterior y descuenta lo nuevo (cubre también el cambio de producto)
+        ("trg_prodvend_after_update", format!(
+            "CREATE TRIGGER trg_prodvend_after_update
+        AFTER UPDATE OF cantidad, id_producto ON productos_vendidos
+        WHEN (NEW.cantidad <> OLD.cantidad OR NEW.id_producto <> OLD.id_producto)
+          AND NOT {kit_old} AND NOT {kit_new}
+        BEGIN
+          UPDATE productos
+          SET stock = stock + OLD.cantidad,
+              actualizado_at = datetime('now','localtime')
+          WHERE id_producto = OLD.id_producto;
+
+          UPDATE productos
+          SET stock = stock - NEW.cantidad,
+              actualizado_at = datetime('now','localtime')
+          WHERE id_producto = NEW.id_producto;
+
+          INSERT INTO movimientos_inventario (id_producto, delta, stock_resultante, motivo, id_referencia, nota)
+          SELECT NEW.id_producto, OLD.cantidad - NEW.cantidad, stock,
+                 CASE WHEN OLD.cantidad > NEW.cantidad THEN 'devolucion' ELSE 'venta' END,
+                 NEW.id_venta, 'Edición de venta'
+          FROM productos
+          WHERE id_producto = NEW.id_producto AND NEW.id_producto = OLD.id_producto;
+
+          INSERT INTO movimientos_inventario (id_producto, delta, stock_resultante, motivo, id_referencia, nota)
+          SELECT OLD.id_producto, OLD.cantidad, stock, 'devolucion', OLD.id_venta, 'Cambio de producto en venta'
+          FROM productos
+          WHERE id_producto = OLD.id_producto AND NEW.id_producto <> OLD.id_producto;
+
+          INSERT INTO movimientos_inventario (id_producto, delta, stock_resultante, motivo, id_referencia, nota)
+          SELECT NEW.id_producto, -NEW.cantidad, stock, 'venta', NEW.id_venta, 'Cambio de producto en venta'
+          FROM productos
+          WHERE id_producto = NEW.id_producto AND NEW.id_producto <> OLD.id_producto;
+        END;"
+        )),
+        ("trg_prodvend_lotes_after_insert", format!(
+            "CREATE TRIGGER trg_prodvend_lotes_after_insert
+        AFTER INSERT ON productos_vendidos
+        WHEN NOT {kit_new}
+        BEGIN
+          {consumir_new}
+        END;"
+        )),
+        ("trg_prodvend_lotes_after_delete", format!(
+            "CREATE TRIGGER trg_prodvend_lotes_after_delete
+        AFTER DELETE ON productos_vendidos
+        WHEN NOT {kit_old}
+        BEGIN
+          {devolver_old}
+        END;"
+        )),
+        ("trg_prodvend_lotes_after_update", format!(
+            "CREATE TRIGGER trg_prodvend_lotes_after_update
+        AFTER UPDATE OF cantidad, id_producto ON productos_vendidos
+        WHEN (NEW.cantidad <> OLD.cantidad OR NEW.id_producto <> OLD.id_producto)
+          AND NOT {kit_old} AND NOT {kit_new}
+        BEGIN
+          {devolver_old}
+          {consumir_new}
+        END;"
+        )),
+        // Líneas de kit: reparten la cantidad vendida entre los componentes
+        ("trg_prodvend_kit_after_insert", format!(
+            "CREATE TRIGGER trg_prodvend_kit_after_insert
+        AFTER INSERT ON productos_vendidos
+        WHEN {kit_new}
+        BEGIN
+          INSERT INTO kit_consumos (id_producto_vendido, id_venta, id_producto, cantidad)
+          SELECT NEW.id_producto_vendido, NEW.id_venta, id_componente, cantidad * NEW.cantidad
+          FROM kit_componentes WHERE id_kit = NEW.id_producto;
+        END;"
+        )),
+        ("trg_prodvend_kit_after_delete", format!(
+            "CREATE TRIGGER trg_prodvend_kit_after_delete
+        AFTER DELETE ON productos_vendidos
+        WHEN {kit_old}
+        BEGIN
+          DELETE FROM kit_consumos WHERE id_producto_vendido = OLD.id_producto_vendido;
+        END;"
+        )),
+        ("trg_prodvend_kit_after_update", format!(
+            "CREATE TRIGGER trg_prodvend_kit_after_update
+        AFTER UPDATE OF cantidad, id_producto ON productos_vendidos
+        WHEN (NEW.cantidad <> OLD.cantidad OR NEW.id_producto <> OLD.id_producto)
+          AND {kit_old} AND {kit_new}
+        BEGIN
+          DELETE FROM kit_consumos WHERE id_producto_vendido = OLD.id_producto_vendido;
+
+          INSERT INTO kit_consumos (id_producto_vendido, id_venta, id_producto, cantidad)
+          SELECT NEW.id_producto_vendido, NEW.id_venta, id_componente, cantidad * NEW.cantidad
+          FROM kit_componentes WHERE id_kit = NEW.id_producto;
+        END;"
+        )),
+        ("trg_kit_consumos_after_insert", format!(
+            "CREATE TRIGGER trg_kit_consumos_after_insert
+        AFTER INSERT ON kit_consumos
+        BEGIN
+          UPDATE productos
+          SET stock = stock - NEW.cantidad,
+              actualizado_at = datetime('now','localtime')
+          WHERE id_producto = NEW.id_producto;
+
+          INSERT INTO movimientos_inventario (id_producto, delta, stock_resultante, motivo, id_referencia, nota)
+          SELECT NEW.id_producto, -NEW.cantidad, stock, 'venta', NEW.id_venta, 'Componente de kit'
+          FROM productos WHERE id_producto = NEW.id_producto;
+
+          {consumir_new}
+        END;"
+        )),
+        ("trg_kit_consumos_after_delete", format!(
+            "CREATE TRIGGER trg_kit_consumos_after_delete
+        AFTER DELETE ON kit_consumos
+        BEGIN
+          UPDATE productos
+          SET stock = stock + OLD.cantidad,
+              actualizado_at = datetime('now','localtime')
+          WHERE id_producto = OLD.id_producto;
+
+          INSERT INTO movimientos_inventario (id_producto, delta, stock_resultante, motivo, id_referencia, nota)
+          SELECT OLD.id_producto, OLD.cantidad, stock, 'devolucion', OLD.id_venta, 'Componente de kit'
+          FROM productos WHERE id_producto = OLD.id_producto;
+
+          {devolver_old}
+        END;"
+        )),
+        // Stock del kit = kits completos que alcanzan a armarse con sus componentes; es derivado y no
+        // deja movimientos: el kardex de los componentes ya registra cada cambio
+        ("trg_productos_stock_kits", format!(
+            "CREATE TRIGGER trg_productos_stock_kits
+        AFTER UPDATE OF stock ON productos
+        WHEN NEW.stock <> OLD.stock
+          AND EXISTS (SELECT 1 FROM kit_componentes WHERE id_componente = NEW.id_producto)
+        BEGIN
+          {recalcular_kits}
+        END;",
+            recalcular_kits = sql_recalcular_stock_kits("NEW"),
+        )),
+    ]
+}
+
+// Va al final de las migraciones: recrea siempre los triggers de inventario con su definición actual,
+// así una base vieja nunca se queda con una versión anterior. La transacción evita que otra conexión
+// vea la tabla sin triggers entre el DROP y el CREATE
+fn migrate_recrear_triggers_inventario(conn: &rusqlite::Connection) -> rusqlite::Result<()>
+{
+    let tx = conn.unchecked_transaction()?;
+    for (nombre, sql) in sql_triggers_inventario() {
+        tx.execute_batch(&format!("DROP TRIGGER IF EXISTS {};\n{}", nombre, sql))?;
+    }
+    tx.commit()
+}
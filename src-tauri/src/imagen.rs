@@ -0,0 +1,220 @@
+// Validación y normalización de las imágenes que sube la interfaz (productos y perfil).
+// El formato se detecta por los bytes del archivo, nunca por la extensión que manda la interfaz.
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use base64::Engine;
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::webp::WebPEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ExtendedColorType, ImageDecoder, ImageFormat, ImageReader, Limits, RgbImage};
+use rusqlite::Connection;
+
+// Tamaño máximo del archivo recibido
+pub const TAMANO_MAXIMO_BYTES: usize = 20 * 1024 * 1024;
+// Lado mayor de la imagen guardada y de la miniatura, en pixeles
+pub const LADO_MAXIMO: u32 = 1600;
+pub const LADO_MINIATURA: u32 = 160;
+
+const CALIDAD_JPEG: u8 = 85;
+const CALIDAD_MINIATURA: u8 = 75;
+const DIMENSION_MAXIMA_ORIGEN: u32 = 12_000;
+
+// Extensiones que se aceptan como nombre de origen; cualquier otra (svg, html, exe...) se rechaza
+const EXTENSIONES_PERMITIDAS: [&str; 6] = ["jpg", "jpeg", "png", "webp", "gif", "bmp"];
+
+pub struct ImagenProcesada
+{
+    pub bytes: Vec<u8>,
+    // "jpg" para fotos opacas, "webp" (sin pérdida) si la imagen tiene transparencia
+    pub extension: &'static str,
+    pub ancho: u32,
+    pub alto: u32,
+    // JPEG en base64 sin prefijo `data:`, como lo muestra la interfaz
+    pub miniatura_base64: String,
+}
+
+// Valida, reduce y vuelve a codificar la imagen recibida y genera su miniatura
+pub fn procesar(bytes: &[u8], extension: Option<&str>) -> Result<ImagenProcesada, String>
+{
+    if let Some(extension) = extension {
+        let extension = extension.trim().trim_start_matches('.').to_lowercase();
+        if !EXTENSIONES_PERMITIDAS.contains(&extension.as_str()) {
+            return Err(format!(
+                "La extensión '.{}' no está permitida; usa JPG, PNG, WebP, GIF o BMP",
+                extension
+            ));
+        }
+    }
+
+    let imagen = decodificar(bytes)?;
+    let imagen = if imagen.width() > LADO_MAXIMO || imagen.height() > LADO_MAXIMO {
+        imagen.resize(LADO_MAXIMO, LADO_MAXIMO, FilterType::Lanczos3)
+    } else {
+        imagen
+    };
+
+    let (bytes, extension) = if imagen.color().has_alpha() {
+        (codificar_webp(&imagen)?, "webp")
+    } else {
+        (codificar_jpeg(&imagen.to_rgb8(), CALIDAD_JPEG)?, "jpg")
+    };
+
+    Ok(ImagenProcesada {
+        bytes,
+        extension,
+        ancho: imagen.width(),
+        alto: imagen.height(),
+        miniatura_base64: miniatura_de(&imagen)?,
+    })
+}
+
+// Miniatura de una imagen ya guardada en disco
+pub fn miniatura_de_archivo(ruta: &Path) -> Result<String, String>
+{
+    let bytes = fs::read(ruta).map_err(|e| format!("No se pudo leer '{}': {}", ruta.display(), e))?;
+    miniatura_de(&decodificar(&bytes)?)
+}
+
+// Guarda la imagen procesada en `dir` con un nombre único y regresa la ruta completa
+pub fn guardar(dir: &Path, prefijo: &str, imagen: &ImagenProcesada) -> Result<PathBuf, String>
+{
+    fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    let filename = format!(
+        "{}_{}_{}.{}",
+        prefijo,
+        chrono::Utc::now().timestamp_millis(),
+        uuid::Uuid::new_v4(),
+        imagen.extension
+    );
+    let ruta = dir.join(filename);
+    fs::write(&ruta, &imagen.bytes).map_err(|e| e.to_string())?;
+    Ok(ruta)
+}
+
+// Carpeta `images/` junto al archivo de la base de datos
+pub fn carpeta_imagenes(db_path: &Path) -> PathBuf
+{
+    db_path.parent().unwrap_or(Path::new(".")).join("images")
+}
+
+// Ruta que se guarda en la base: relativa a `images/`, con `/` como separador, para que un respaldo
+// funcione en otra computadora. Acepta rutas absolutas de Windows o Unix (de cualquier equipo)
+// y descarta `.` y `..` para que nunca apunte fuera de la carpeta
+pub fn ruta_relativa(ruta: &str) -> String
+{
+    let ruta = ruta.trim();
+    let partes: Vec<&str> = ruta
+        .split(['/', '\\'])
+        .filter(|parte| !parte.is_empty() && *parte != "." && *parte != "..")
+        .collect();
+
+    match partes.iter().rposition(|parte| parte.eq_ignore_ascii_case("images")) {
+        Some(posicion) => partes[posicion + 1..].join("/"),
+        None if es_absoluta(ruta) => partes.last().map(|nombre| nombre.to_string()).unwrap_or_default(),
+        None => partes.join("/"),
+    }
+}
+
+// Ruta completa dentro de `carpeta` para una ruta guardada (relativa o absoluta heredada)
+pub fn ruta_completa(carpeta: &Path, ruta: &str) -> PathBuf
+{
+    carpeta.join(ruta_relativa(ruta))
+}
+
+// Resuelve al leer la ruta guardada contra la carpeta de imágenes de la conexión;
+// sin archivo de base (en memoria) la deja como está
+pub fn resolver_ruta(conn: &Connection, ruta: Option<String>) -> Option<String>
+{
+    let ruta = ruta.filter(|ruta| !ruta.trim().is_empty())?;
+    match conn.path().filter(|path| !path.is_empty()) {
+        Some(db_path) => Some(
+            ruta_completa(&carpeta_imagenes(Path::new(db_path)), &ruta)
+                .to_string_lossy()
+                .to_string(),
+        ),
+        None => Some(ruta),
+    }
+}
+
+fn es_absoluta(ruta: &str) -> bool
+{
+    let bytes = ruta.as_bytes();
+    ruta.starts_with('/') || ruta.starts_with('\\') || (bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':')
+}
+
+// Formatos reconocidos por su firma (magic bytes); respeta la orientación EXIF de las fotos de celular
+fn decodificar(bytes: &[u8]) -> Result<DynamicImage, String>
+{
+    if bytes.is_empty() {
+        return Err("El archivo de imagen está vacío".to_string());
+    }
+    if bytes.len() > TAMANO_MAXIMO_BYTES {
+        return Err(format!(
+            "La imagen pesa {:.1} MB; el máximo es {} MB",
+            bytes.len() as f64 / (1024.0 * 1024.0),
+            TAMANO_MAXIMO_BYTES / (1024 * 1024)
+        ));
+    }
+
+    let formato = image::guess_format(bytes)
+        .ok()
+        .filter(|formato| {
+            matches!(
+                formato,
+                ImageFormat::Jpeg | ImageFormat::Png | ImageFormat::WebP | ImageFormat::Gif | ImageFormat::Bmp
+            )
+        })
+        .ok_or("El archivo no es una imagen válida (JPG, PNG, WebP, GIF o BMP)")?;
+
+    let mut limites = Limits::default();
+    limites.max_image_width = Some(DIMENSION_MAXIMA_ORIGEN);
+    limites.max_image_height = Some(DIMENSION_MAXIMA_ORIGEN);
+
+    let mut reader = ImageReader::with_format(Cursor::new(bytes), formato);
+    reader.limits(limites);
+    let mut decoder = reader
+        .into_decoder()
+        .map_err(|e| format!("No se pudo leer la imagen: {}", e))?;
+    let orientacion = decoder
+        .orientation()
+        .map_err(|e| format!("No se pudo leer la imagen: {}", e))?;
+    let mut imagen = DynamicImage::from_decoder(decoder)
+        .map_err(|e| format!("No se pudo leer la imagen: {}", e))?;
+    imagen.apply_orientation(orientacion);
+    Ok(imagen)
+}
+
+fn codificar_jpeg(imagen: &RgbImage, calidad: u8) -> Result<Vec<u8>, String>
+{
+    let mut salida = Vec::new();
+    JpegEncoder::new_with_quality(&mut salida, calidad)
+        .encode_image(imagen)
+        .map_err(|e| format!("No se pudo codificar la imagen: {}", e))?;
+    Ok(salida)
+}
+
+fn codificar_webp(imagen: &DynamicImage) -> Result<Vec<u8>, String>
+{
+    let rgba = imagen.to_rgba8();
+    let mut salida = Vec::new();
+    WebPEncoder::new_lossless(&mut salida)
+        .encode(rgba.as_raw(), rgba.width(), rgba.height(), ExtendedColorType::Rgba8)
+        .map_err(|e| format!("No se pudo codificar la imagen: {}", e))?;
+    Ok(salida)
+}
+
+// Miniatura JPEG; la transparencia se asienta sobre fondo blanco
+fn miniatura_de(imagen: &DynamicImage) -> Result<String, String>
+{
+    let reducida = imagen.thumbnail(LADO_MINIATURA, LADO_MINIATURA).to_rgba8();
+    let mut opaca = RgbImage::new(reducida.width(), reducida.height());
+    for (origen, destino) in reducida.pixels().zip(opaca.pixels_mut()) {
+        let [r, g, b, a] = origen.0;
+        let mezclar = |canal: u8| ((canal as u32 * a as u32 + 255 * (255 - a as u32)) / 255) as u8;
+        destino.0 = [mezclar(r), mezclar(g), mezclar(b)];
+    }
+    let jpeg = codificar_jpeg(&opaca, CALIDAD_MINIATURA)?;
+    Ok(base64::engine::general_purpose::STANDARD.encode(jpeg))
+}
@@ -0,0 +1,130 @@
+// Lectura de archivos CSV y XLSX para la importación de productos.
+// Solo convierte el archivo en una tabla de texto; la validación es de ImportacionService.
+use std::fs;
+use std::path::Path;
+
+use calamine::{open_workbook_auto, Data, Reader};
+
+// Primera fila con encabezados y el resto de las filas con su número en el archivo (empezando en 1)
+#[derive(Debug, Clone, Default)]
+pub struct TablaImportacion
+{
+    pub encabezados: Vec<String>,
+    pub filas: Vec<(usize, Vec<String>)>,
+    // Nombre del archivo de origen, para anotarlo en los ajustes de stock
+    pub archivo: Option<String>,
+}
+
+// Lee el archivo según su extensión; en libros de Excel usa `hoja` o la primera hoja
+pub fn leer_archivo(ruta: &Path, hoja: Option<&str>) -> Result<TablaImportacion, String>
+{
+    let extension = ruta
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_lowercase)
+        .unwrap_or_default();
+
+    let mut tabla = match extension.as_str() {
+        "csv" | "txt" => {
+            let bytes = fs::read(ruta).map_err(|e| format!("No se pudo leer '{}': {}", ruta.display(), e))?;
+            leer_csv(&bytes)?
+        }
+        "xlsx" | "xlsm" | "xls" | "ods" => leer_libro(ruta, hoja)?,
+        _ => return Err("El archivo debe ser CSV o XLSX".to_string()),
+    };
+    tabla.archivo = ruta.file_name().map(|nombre| nombre.to_string_lossy().to_string());
+    Ok(tabla)
+}
+
+// CSV en UTF-8 (con o sin BOM) o en Latin-1 como lo guarda Excel en Windows;
+// el separador puede ser coma o punto y coma
+pub fn leer_csv(bytes: &[u8]) -> Result<TablaImportacion, String>
+{
+    let texto = match std::str::from_utf8(bytes) {
+        Ok(texto) => texto.trim_start_matches('\u{feff}').to_string(),
+        Err(_) => bytes.iter().map(|&b| b as char).collect(),
+    };
+
+    let primera_linea = texto.lines().next().unwrap_or_default();
+    let separador = if primera_linea.matches(';').count() > primera_linea.matches(',').count() {
+        b';'
+    } else {
+        b','
+    };
+
+    let mut lector = csv::ReaderBuilder::new()
+        .delimiter(separador)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(texto.as_bytes());
+
+    let mut filas = Vec::new();
+    for (indice, registro) in lector.records().enumerate() {
+        let registro = registro.map_err(|e| format!("El CSV no es válido: {}", e))?;
+        // El contador de líneas del lector no cuenta las líneas vacías y la posición puede quedar antes
+        // de ellas; se cuentan los saltos hasta el primer carácter del registro
+        let numero = registro
+            .position()
+            .map(|p| {
+                let bytes = texto.as_bytes();
+                let mut inicio = p.byte() as usize;
+                while inicio < bytes.len() && (bytes[inicio] == b'\n' || bytes[inicio] == b'\r') {
+                    inicio += 1;
+                }
+                bytes[..inicio].iter().filter(|&&b| b == b'\n').count() + 1
+            })
+            .unwrap_or(indice + 1);
+        filas.push((numero, registro.iter().map(|celda| celda.trim().to_string()).collect()));
+    }
+    tabla_de_filas(filas)
+}
+
+fn leer_libro(ruta: &Path, hoja: Option<&str>) -> Result<TablaImportacion, String>
+{
+    let mut libro = open_workbook_auto(ruta).map_err(|e| format!("No se pudo abrir '{}': {}", ruta.display(), e))?;
+    let nombre_hoja = match hoja {
+        Some(hoja) => hoja.to_string(),
+        None => libro
+            .sheet_names()
+            .first()
+            .cloned()
+            .ok_or("El libro no tiene hojas")?,
+    };
+    let rango = libro
+        .worksheet_range(&nombre_hoja)
+        .map_err(|e| format!("No se pudo leer la hoja '{}': {}", nombre_hoja, e))?;
+
+    // El rango empieza en la primera celda con datos, no necesariamente en A1
+    let fila_inicial = rango.start().map(|(fila, _)| fila as usize).unwrap_or(0);
+    let filas = rango
+        .rows()
+        .enumerate()
+        .map(|(indice, celdas)| (fila_inicial + indice + 1, celdas.iter().map(texto_celda).collect()))
+        .collect();
+    tabla_de_filas(filas)
+}
+
+// Los números enteros de Excel llegan como flotantes; "12.0" se lee como "12"
+fn texto_celda(celda: &Data) -> String
+{
+    match celda {
+        Data::Empty => String::new(),
+        Data::Float(valor) if valor.fract() == 0.0 && valor.abs() < 1e15 => format!("{}", *valor as i64),
+        Data::String(texto) => texto.trim().to_string(),
+        otro => otro.to_string(),
+    }
+}
+
+// Quita las filas vacías; la primera con datos son los encabezados
+fn tabla_de_filas(filas: Vec<(usize, Vec<String>)>) -> Result<TablaImportacion, String>
+{
+    let mut filas = filas
+        .into_iter()
+        .filter(|(_, celdas)| celdas.iter().any(|celda| !celda.trim().is_empty()));
+    let (_, encabezados) = filas.next().ok_or("El archivo está vacío")?;
+    Ok(TablaImportacion {
+        encabezados: encabezados.iter().map(|encabezado| encabezado.trim().to_string()).collect(),
+        filas: filas.collect(),
+        archivo: None,
+    })
+}
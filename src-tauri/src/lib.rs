@@ -7,11 +7,20 @@ use commands::sales;
 use commands::sold_products;
 use commands::export;
 use commands::profile;
+use commands::customers;
+use commands::inventory;
+use commands::purchases;
+use commands::campaigns;
+use commands::promotions;
+use commands::product_import;
 
 pub mod database; //para manejo de la base de datos
 pub mod models; //definicion de modelo de datos
 pub mod repos; //repositorios para acceso a datos
 pub mod services; //lógica de negocio
+pub mod pdf; //generación de PDF y códigos de barras
+pub mod imagen; //validación, reducción y miniaturas de imágenes
+pub mod importacion; //lectura de CSV y XLSX para importar productos
 pub mod commands; //comandos expuestos a la interfaz
 
 
@@ -46,19 +55,47 @@ pub fn run() {
     })
     .invoke_handler(tauri::generate_handler![
       products::list_productos,
+      products::list_productos_paginados,
+      products::list_productos_por_categoria,
       products::get_producto,
+      products::regenerar_miniaturas,
+      products::reporte_imagenes,
+      products::limpiar_imagenes_huerfanas,
+      products::buscar_producto_por_codigo,
+      products::buscar_productos,
+      products::set_notas_producto,
       products::create_producto,
       products::update_producto,
       products::delete_producto,
+      products::list_productos_descontinuados,
+      products::reactivar_producto,
+      products::get_impacto_eliminar_producto,
+      products::eliminar_producto_definitivo,
       products::get_total_inventory_value,
+      products::get_historial_precios,
+      products::set_stock_minimo_producto,
+      products::list_productos_bajo_stock,
+      products::list_productos_agrupados,
+      products::list_variantes,
+      products::create_variante,
+      products::update_variante,
+      products::list_componentes_kit,
+      products::set_componentes_kit,
+      products::deshacer_kit,
 
       categories::list_categorias,
       categories::get_categoria,
       categories::create_categoria,
       categories::update_categoria,
       categories::delete_categoria,
+      categories::get_impacto_eliminar_categoria,
+      categories::merge_categorias,
+      categories::set_stock_minimo_categoria,
+      categories::get_arbol_categorias,
+      categories::mover_categoria,
 
       sales::list_ventas,
+      sales::list_ventas_paginadas,
       sales::get_venta,
       sales::create_venta,
       sales::update_venta,
@@ -68,11 +105,30 @@ pub fn run() {
       sales::list_abonos_por_venta,
       sales::get_cobranza_summary,
       sales::list_ventas_con_cobranza,
+      sales::list_ventas_con_cobranza_paginadas,
       sales::get_sales_today,
       sales::get_sales_month,
       sales::get_top_productos,
+      sales::get_reporte_ganancias,
+      sales::get_reporte_kits,
+
+      campaigns::list_campanas,
+      campaigns::get_campana_vigente,
+      campaigns::create_campana,
+      campaigns::update_campana,
+      campaigns::delete_campana,
+      campaigns::list_precios_campana,
+      campaigns::set_precio_campana,
+      campaigns::quitar_precio_campana,
+      campaigns::get_precio_vigente,
+
+      promotions::list_promociones,
+      promotions::create_promocion,
+      promotions::update_promocion,
+      promotions::delete_promocion,
 
       sold_products::list_productos_vendidos,
+      sold_products::list_productos_vendidos_paginados,
       sold_products::get_producto_vendido,
       sold_products::get_productos_by_venta,
       sold_products::create_producto_vendido,
@@ -81,11 +137,48 @@ pub fn run() {
       sold_products::delete_productos_by_venta,
 
       export::export_all_xlsx,
+      export::export_estado_cuenta_xlsx,
+      export::export_etiquetas_pdf,
       export::backup_database,
       export::import_database,
 
+      product_import::previsualizar_importacion_productos,
+      product_import::importar_productos,
+
       profile::get_perfil,
       profile::save_perfil,
+
+      customers::list_clientas,
+      customers::buscar_clientas,
+      customers::get_clienta,
+      customers::create_clienta,
+      customers::update_clienta,
+      customers::delete_clienta,
+      customers::get_estado_cuenta_clienta,
+
+      inventory::list_movimientos_por_producto,
+      inventory::list_movimientos_por_fecha,
+      inventory::ajustar_stock,
+      inventory::list_lotes_por_producto,
+      inventory::list_lotes_por_vencer,
+      inventory::dar_de_baja_lotes_vencidos,
+      inventory::list_conteos,
+      inventory::abrir_conteo,
+      inventory::registrar_cantidad_contada,
+      inventory::quitar_producto_conteo,
+      inventory::get_reporte_conteo,
+      inventory::aplicar_conteo,
+
+      purchases::list_proveedores,
+      purchases::create_proveedor,
+      purchases::update_proveedor,
+      purchases::delete_proveedor,
+      purchases::list_compras,
+      purchases::get_compra,
+      purchases::create_compra,
+      purchases::recibir_compra,
+      purchases::registrar_pago_compra,
+      purchases::delete_compra,
     ])
     .run(tauri::generate_context!())
     .expect("error while running tauri application");
@@ -19,6 +19,35 @@ pub struct Producto
     pub actualizado_at: Option<String>,
     #[serde(default = "default_activo")]
     pub activo: i64,
+    // Costo promedio ponderado; solo lo modifica la recepción de compras
+    #[serde(default)]
+    pub costo_promedio: f64,
+    // None: se usa el stock mínimo por defecto de la categoría
+    #[serde(default)]
+    pub stock_minimo: Option<i64>,
+    // Variante (tono, tamaño, fragancia) de otro producto
+    #[serde(default)]
+    pub id_producto_padre: Option<i64>,
+    #[serde(default)]
+    pub tono: Option<String>,
+    #[serde(default)]
+    pub tamano: Option<String>,
+    #[serde(default)]
+    pub fragancia: Option<String>,
+    // false: la variante usa los precios del producto padre
+    #[serde(default)]
+    pub precio_propio: bool,
+    // Códigos únicos para búsqueda por lector; el de barras es EAN-13, UPC-A o EAN-8
+    #[serde(default)]
+    pub sku: Option<String>,
+    #[serde(default)]
+    pub codigo_barras: Option<String>,
+    // Kit: su stock se deriva de los componentes y venderlo los descuenta
+    #[serde(default)]
+    pub es_kit: bool,
+    // Texto libre que también entra en la búsqueda; solo lo modifica `set_notas`
+    #[serde(default)]
+    pub notas: Option<String>,
 }
 
 impl Producto {
@@ -40,6 +69,65 @@ pub struct Categoria
 {
     pub id_categoria: i64,
     pub nombre: String,
+    // Stock mínimo para los productos de la categoría que no tienen uno propio
+    #[serde(default)]
+    pub stock_minimo_default: Option<i64>,
+    // None: categoría raíz
+    #[serde(default)]
+    pub id_categoria_padre: Option<i64>,
+}
+
+// Lo que se mueve (o queda sin categoría) al eliminar o fusionar una categoría
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImpactoCategoria
+{
+    pub id_categoria: i64,
+    pub nombre: String,
+    pub productos_activos: i64,
+    pub productos_descontinuados: i64,
+    pub subcategorias: i64,
+    pub promociones: i64,
+    // None: los productos quedan sin categoría
+    pub id_categoria_destino: Option<i64>,
+}
+
+// Historial que se borra junto con un producto al eliminarlo definitivamente
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImpactoEliminacionProducto
+{
+    pub id_producto: i64,
+    pub nombre_producto: String,
+    pub movimientos: i64,
+    pub historial_precios: i64,
+    pub lotes: i64,
+    pub precios_campana: i64,
+}
+
+// Filtros opcionales de `buscar_productos`
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct FiltrosBusqueda
+{
+    // Categoría y sus subcategorías
+    #[serde(default)]
+    pub id_categoria: Option<i64>,
+    #[serde(default)]
+    pub incluir_descontinuados: bool,
+    #[serde(default)]
+    pub solo_con_stock: bool,
+}
+
+// Categoría dentro del árbol, con su ruta completa ("Maquillaje > Labios > Labiales")
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CategoriaNodo
+{
+    pub id_categoria: i64,
+    pub nombre: String,
+    pub id_categoria_padre: Option<i64>,
+    pub stock_minimo_default: Option<i64>,
+    pub ruta: String,
+    // Productos activos de la categoría, sin contar subcategorías
+    pub productos: i64,
+    pub hijos: Vec<CategoriaNodo>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -52,17 +140,52 @@ pub enum TipoPago
     Contado,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Clienta
+{
+    pub id_clienta: i64,
+    pub nombre: String,
+    #[serde(default)]
+    pub apellido: String,
+    #[serde(default)]
+    pub telefono: String,
+    #[serde(default)]
+    pub direccion: String,
+    #[serde(default)]
+    pub notas: String,
+    pub creado_at: Option<String>,
+}
+
+// Datos para registrar una clienta nueva (desde el directorio o en línea al vender)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ClientaInput
+{
+    pub nombre: String,
+    #[serde(default)]
+    pub apellido: String,
+    pub telefono: Option<String>,
+    pub direccion: Option<String>,
+    pub notas: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Venta
 {
     pub id_venta: i64,
+    #[serde(default)]
+    pub id_clienta: Option<i64>,
     pub fecha: String,
     pub nombre_clienta: String,
     #[serde(default)]
     pub apellido_clienta: String,
     pub total_venta: f64,
     pub tipo_pago: TipoPago,
-    
+    // Campaña (periodo de catálogo) en la que se registró la venta
+    #[serde(default)]
+    pub id_campana: Option<i64>,
+    // Descuento aplicado al total de la venta (ya restado de total_venta)
+    #[serde(default)]
+    pub descuento: f64,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -77,7 +200,7 @@ pub struct AbonoVenta
     pub creado_at: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
 pub enum EstadoPago
 {
     #[serde(rename = "Pendiente")]
@@ -102,6 +225,7 @@ pub struct RegistrarAbonoInput
 pub struct VentaCobranzaView
 {
     pub id_venta: i64,
+    pub id_clienta: Option<i64>,
     pub fecha: String,
     pub nombre_clienta: String,
     pub apellido_clienta: String,
@@ -110,8 +234,34 @@ pub struct VentaCobranzaView
     pub total_abonado: f64,
     pub saldo_pendiente: f64,
     pub estado_pago: EstadoPago,
+    pub id_campana: Option<i64>,
+}
+
+// Renglón del estado de cuenta: una venta (cargo) o un abono, con el saldo acumulado
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MovimientoEstadoCuenta
+{
+    pub fecha: String,
+    pub concepto: String,
+    pub id_venta: i64,
+    pub id_abono: Option<i64>,
+    pub cargo: f64,
+    pub abono: f64,
+    pub saldo: f64,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EstadoCuentaClienta
+{
+    pub clienta: Clienta,
+    pub ventas: Vec<VentaCobranzaView>,
+    pub movimientos: Vec<MovimientoEstadoCuenta>,
+    pub total_comprado: f64,
+    pub total_abonado: f64,
+    // Suma de saldos pendientes de las ventas a abonos abiertas
+    pub saldo_total: f64,
+    pub ventas_abiertas: usize,
+}
  
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ProductoVendido
@@ -123,6 +273,14 @@ pub struct ProductoVendido
     pub cantidad: i64,
     pub precio_unitario: f64,
     pub subtotal: f64,
+    // Costo unitario congelado al momento de la venta
+    #[serde(default)]
+    pub costo_unitario: f64,
+    // Descuento total de la línea (promoción + manual), ya restado del subtotal
+    #[serde(default)]
+    pub descuento: f64,
+    #[serde(default)]
+    pub id_promocion: Option<i64>,
 }
 
 // Estructuras para el comando de venta completa
@@ -132,26 +290,169 @@ pub struct ItemVenta
     pub id_producto: i64,
     pub nombre_producto: String,
     pub cantidad: i64,
-    pub precio_unitario: f64,
+    // None: precio público de la campaña de la venta (o el del producto si no tiene precio de campaña)
+    #[serde(default)]
+    pub precio_unitario: Option<f64>,
+    // Descuento manual de la línea, se aplica después de la promoción
+    #[serde(default)]
+    pub descuento: Option<DescuentoInput>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum TipoDescuento
+{
+    #[serde(rename = "Porcentaje")]
+    Porcentaje,
+    #[serde(rename = "Monto")]
+    Monto,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DescuentoInput
+{
+    pub tipo: TipoDescuento,
+    pub valor: f64,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct VentaCompletaInput
 {
     pub fecha: String,
+    // Clienta existente del directorio; tiene prioridad sobre los demás datos de clienta
+    #[serde(default)]
+    pub id_clienta: Option<i64>,
+    // Clienta nueva capturada en la misma venta
+    #[serde(default)]
+    pub clienta_nueva: Option<ClientaInput>,
+    // Nombre libre (compatibilidad): se busca o se registra en el directorio
+    #[serde(default)]
     pub nombre_clienta: String,
     #[serde(default)]
     pub apellido_clienta: String,
     pub tipo_pago: TipoPago,
     pub productos: Vec<ItemVenta>,
+    // None: la campaña vigente en la fecha de la venta
+    #[serde(default)]
+    pub id_campana: Option<i64>,
+    // Descuento sobre el total, después de los descuentos de cada línea
+    #[serde(default)]
+    pub descuento: Option<DescuentoInput>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct VentaCompletaOutput
 {
     pub id_venta: i64,
+    pub subtotal_bruto: f64,
+    pub descuento: f64,
     pub total_venta: f64,
     pub items_insertados: usize,
+    pub promociones: Vec<PromocionAplicada>,
+}
+
+// ============== Promociones ==============
+// NxM: lleva `cantidad_lleva` y paga `cantidad_paga` (2x1, 3x2);
+// PrecioPaquete: `cantidad_lleva` piezas por `precio_paquete` (3 por $200);
+// Porcentaje: `porcentaje` de descuento en cada pieza
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum TipoPromocion
+{
+    #[serde(rename = "NxM")]
+    NxM,
+    #[serde(rename = "PrecioPaquete")]
+    PrecioPaquete,
+    #[serde(rename = "Porcentaje")]
+    Porcentaje,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Promocion
+{
+    pub id_promocion: i64,
+    pub nombre: String,
+    pub tipo: TipoPromocion,
+    // Aplica a un producto o a todos los productos de una categoría
+    pub id_producto: Option<i64>,
+    pub id_categoria: Option<i64>,
+    pub cantidad_lleva: Option<i64>,
+    pub cantidad_paga: Option<i64>,
+    pub precio_paquete: Option<f64>,
+    pub porcentaje: Option<f64>,
+    // Sin fechas la promoción no caduca
+    pub fecha_inicio: Option<String>,
+    pub fecha_fin: Option<String>,
+    pub activa: bool,
+    pub creado_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PromocionInput
+{
+    pub nombre: String,
+    pub tipo: TipoPromocion,
+    #[serde(default)]
+    pub id_producto: Option<i64>,
+    #[serde(default)]
+    pub id_categoria: Option<i64>,
+    #[serde(default)]
+    pub cantidad_lleva: Option<i64>,
+    #[serde(default)]
+    pub cantidad_paga: Option<i64>,
+    #[serde(default)]
+    pub precio_paquete: Option<f64>,
+    #[serde(default)]
+    pub porcentaje: Option<f64>,
+    #[serde(default)]
+    pub fecha_inicio: Option<String>,
+    #[serde(default)]
+    pub fecha_fin: Option<String>,
+    #[serde(default = "default_activa")]
+    pub activa: bool,
+}
+
+fn default_activa() -> bool {
+    true
+}
+
+// Promoción que se aplicó a una línea de la venta
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PromocionAplicada
+{
+    pub id_promocion: i64,
+    pub nombre: String,
+    pub id_producto: i64,
+    pub descuento: f64,
+}
+
+// Desglose de una línea de venta con sus descuentos
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LineaCalculada
+{
+    pub id_producto: i64,
+    pub cantidad: i64,
+    pub precio_unitario: f64,
+    pub bruto: f64,
+    pub id_promocion: Option<i64>,
+    pub descuento_promocion: f64,
+    pub descuento_tipo: Option<TipoDescuento>,
+    pub descuento_valor: f64,
+    // Promoción + descuento manual
+    pub descuento: f64,
+    pub subtotal: f64,
+}
+
+// Totales de una venta: bruto - descuentos de línea - descuento de la venta = total
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CalculoVenta
+{
+    pub lineas: Vec<LineaCalculada>,
+    pub subtotal_bruto: f64,
+    pub descuento_lineas: f64,
+    pub descuento_tipo: Option<TipoDescuento>,
+    pub descuento_valor: f64,
+    pub descuento_venta: f64,
+    pub total: f64,
+    pub promociones: Vec<PromocionAplicada>,
 }
 
 // Top productos más vendidos (para dashboard)
@@ -163,6 +464,488 @@ pub struct TopProducto
     pub ingreso: f64,
 }
 
+// Atributos de una variante; sin precios hereda los del producto padre
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct VarianteInput
+{
+    pub tono: Option<String>,
+    pub tamano: Option<String>,
+    pub fragancia: Option<String>,
+    pub precio_consultora: Option<f64>,
+    pub precio_publico: Option<f64>,
+    #[serde(default)]
+    pub sku: Option<String>,
+    #[serde(default)]
+    pub codigo_barras: Option<String>,
+}
+
+// Producto con sus variantes activas (vacío si no tiene)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProductoAgrupado
+{
+    pub producto: Producto,
+    pub variantes: Vec<Producto>,
+    pub stock_total: i64,
+}
+
+// Producto en o bajo su stock mínimo, con la velocidad de venta reciente
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProductoBajoStock
+{
+    pub id_producto: i64,
+    pub nombre_producto: String,
+    pub id_categoria: Option<i64>,
+    pub nombre_categoria: Option<String>,
+    pub stock: i64,
+    pub stock_minimo: i64,
+    pub unidades_vendidas: i64,
+    pub dias_periodo: i64,
+    pub venta_promedio_diaria: f64,
+    // None cuando no hubo ventas en el periodo
+    pub dias_cobertura: Option<f64>,
+}
+
+// Ganancia real calculada con el costo congelado de cada línea vendida
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GananciaVenta
+{
+    pub id_venta: i64,
+    pub fecha: String,
+    pub nombre_cliente: String,
+    // Bruto - descuento = ingreso (neto)
+    pub bruto: f64,
+    pub descuento: f64,
+    pub ingreso: f64,
+    pub costo: f64,
+    pub ganancia: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GananciaProducto
+{
+    pub id_producto: i64,
+    pub nombre_producto: String,
+    pub unidades: i64,
+    // El descuento de la venta se reparte entre sus líneas en proporción a su subtotal
+    pub bruto: f64,
+    pub descuento: f64,
+    pub ingreso: f64,
+    pub costo: f64,
+    pub ganancia: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReporteGanancias
+{
+    pub fecha_inicio: String,
+    pub fecha_fin: String,
+    pub id_campana: Option<i64>,
+    pub bruto: f64,
+    pub descuento: f64,
+    pub ingreso: f64,
+    pub costo: f64,
+    pub ganancia: f64,
+    pub margen_porcentaje: f64,
+    pub por_venta: Vec<GananciaVenta>,
+    pub por_producto: Vec<GananciaProducto>,
+}
+
+// Motivo de un movimiento de inventario (se guarda en minúsculas en la base de datos)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum MotivoMovimiento
+{
+    #[serde(rename = "venta")]
+    Venta,
+    #[serde(rename = "devolucion")]
+    Devolucion,
+    #[serde(rename = "ajuste")]
+    Ajuste,
+    #[serde(rename = "compra")]
+    Compra,
+    #[serde(rename = "merma")]
+    Merma,
+    #[serde(rename = "inicial")]
+    Inicial,
+}
+
+impl MotivoMovimiento {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MotivoMovimiento::Venta => "venta",
+            MotivoMovimiento::Devolucion => "devolucion",
+            MotivoMovimiento::Ajuste => "ajuste",
+            MotivoMovimiento::Compra => "compra",
+            MotivoMovimiento::Merma => "merma",
+            MotivoMovimiento::Inicial => "inicial",
+        }
+    }
+
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "venta" => MotivoMovimiento::Venta,
+            "devolucion" => MotivoMovimiento::Devolucion,
+            "compra" => MotivoMovimiento::Compra,
+            "merma" => MotivoMovimiento::Merma,
+            "inicial" => MotivoMovimiento::Inicial,
+            _ => MotivoMovimiento::Ajuste,
+        }
+    }
+}
+
+// Razón de un ajuste manual de stock
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum MotivoAjuste
+{
+    #[serde(rename = "reconteo")]
+    Reconteo,
+    #[serde(rename = "dano")]
+    Dano,
+    #[serde(rename = "regalo")]
+    Regalo,
+    #[serde(rename = "uso_personal")]
+    UsoPersonal,
+    #[serde(rename = "correccion")]
+    Correccion,
+    #[serde(rename = "caducado")]
+    Caducado,
+    #[serde(rename = "importacion")]
+    Importacion,
+}
+
+impl MotivoAjuste {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MotivoAjuste::Reconteo => "reconteo",
+            MotivoAjuste::Dano => "dano",
+            MotivoAjuste::Regalo => "regalo",
+            MotivoAjuste::UsoPersonal => "uso_personal",
+            MotivoAjuste::Correccion => "correccion",
+            MotivoAjuste::Caducado => "caducado",
+            MotivoAjuste::Importacion => "importacion",
+        }
+    }
+
+    pub fn from_db(value: &str) -> Option<Self> {
+        match value {
+            "reconteo" => Some(MotivoAjuste::Reconteo),
+            "dano" => Some(MotivoAjuste::Dano),
+            "regalo" => Some(MotivoAjuste::Regalo),
+            "uso_personal" => Some(MotivoAjuste::UsoPersonal),
+            "correccion" => Some(MotivoAjuste::Correccion),
+            "caducado" => Some(MotivoAjuste::Caducado),
+            "importacion" => Some(MotivoAjuste::Importacion),
+            _ => None,
+        }
+    }
+
+    // Daños, regalos, consumo propio y caducidad son salidas sin venta (merma)
+    pub fn motivo_movimiento(&self) -> MotivoMovimiento {
+        match self {
+            MotivoAjuste::Reconteo | MotivoAjuste::Correccion | MotivoAjuste::Importacion => MotivoMovimiento::Ajuste,
+            MotivoAjuste::Dano | MotivoAjuste::Regalo | MotivoAjuste::UsoPersonal | MotivoAjuste::Caducado => MotivoMovimiento::Merma,
+        }
+    }
+}
+
+// Renglón del kardex: cada cambio de stock con su motivo y stock resultante
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MovimientoInventario
+{
+    pub id_movimiento: i64,
+    pub id_producto: i64,
+    pub nombre_producto: String,
+    pub delta: i64,
+    pub stock_resultante: i64,
+    pub motivo: MotivoMovimiento,
+    pub motivo_ajuste: Option<MotivoAjuste>,
+    pub id_referencia: Option<i64>,
+    pub nota: String,
+    pub fecha: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AjusteStockOutput
+{
+    pub id_producto: i64,
+    pub stock_anterior: i64,
+    pub stock_actual: i64,
+}
+
+// Proveedores y compras (entrada de mercancía)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Proveedor
+{
+    pub id_proveedor: i64,
+    pub nombre: String,
+    #[serde(default)]
+    pub contacto: String,
+    #[serde(default)]
+    pub telefono: String,
+    #[serde(default)]
+    pub notas: String,
+    pub creado_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProveedorInput
+{
+    pub nombre: String,
+    pub contacto: Option<String>,
+    pub telefono: Option<String>,
+    pub notas: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum EstadoCompra
+{
+    #[serde(rename = "Pendiente")]
+    Pendiente,
+    #[serde(rename = "Parcial")]
+    Parcial,
+    #[serde(rename = "Recibida")]
+    Recibida,
+}
+
+impl EstadoCompra {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EstadoCompra::Pendiente => "Pendiente",
+            EstadoCompra::Parcial => "Parcial",
+            EstadoCompra::Recibida => "Recibida",
+        }
+    }
+
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "Parcial" => EstadoCompra::Parcial,
+            "Recibida" => EstadoCompra::Recibida,
+            _ => EstadoCompra::Pendiente,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Compra
+{
+    pub id_compra: i64,
+    pub id_proveedor: Option<i64>,
+    pub nombre_proveedor: String,
+    pub fecha: String,
+    pub estado: EstadoCompra,
+    pub total_compra: f64,
+    pub notas: String,
+    pub total_pagado: f64,
+    pub saldo_pendiente: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CompraDetalle
+{
+    pub id_detalle: i64,
+    pub id_compra: i64,
+    pub id_producto: i64,
+    pub nombre_producto: String,
+    pub cantidad_pedida: i64,
+    pub cantidad_recibida: i64,
+    pub costo_unitario: f64,
+    pub subtotal: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PagoCompra
+{
+    pub id_pago: i64,
+    pub id_compra: i64,
+    pub monto_pago: f64,
+    pub fecha_pago: String,
+    pub observacion: String,
+    pub creado_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CompraCompleta
+{
+    pub compra: Compra,
+    pub lineas: Vec<CompraDetalle>,
+    pub pagos: Vec<PagoCompra>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LineaCompraInput
+{
+    pub id_producto: i64,
+    pub cantidad: i64,
+    pub costo_unitario: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CompraInput
+{
+    pub id_proveedor: Option<i64>,
+    pub fecha: Option<String>,
+    pub notas: Option<String>,
+    pub lineas: Vec<LineaCompraInput>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RecepcionLineaInput
+{
+    pub id_detalle: i64,
+    pub cantidad: i64,
+    // Lote recibido; sin código ni caducidad va al lote general del producto
+    #[serde(default)]
+    pub codigo_lote: Option<String>,
+    #[serde(default)]
+    pub fecha_caducidad: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RegistrarPagoCompraInput
+{
+    pub id_compra: i64,
+    pub monto_pago: f64,
+    pub fecha_pago: Option<String>,
+    pub observacion: Option<String>,
+}
+
+// Precio de producto al que aplica un registro del historial
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum CampoPrecio
+{
+    #[serde(rename = "precio_consultora")]
+    PrecioConsultora,
+    #[serde(rename = "precio_publico")]
+    PrecioPublico,
+}
+
+impl CampoPrecio {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CampoPrecio::PrecioConsultora => "precio_consultora",
+            CampoPrecio::PrecioPublico => "precio_publico",
+        }
+    }
+
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "precio_consultora" => CampoPrecio::PrecioConsultora,
+            _ => CampoPrecio::PrecioPublico,
+        }
+    }
+}
+
+// Origen del cambio de precio
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum OrigenCambioPrecio
+{
+    #[serde(rename = "alta")]
+    Alta,
+    #[serde(rename = "edicion")]
+    Edicion,
+}
+
+impl OrigenCambioPrecio {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrigenCambioPrecio::Alta => "alta",
+            OrigenCambioPrecio::Edicion => "edicion",
+        }
+    }
+
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "alta" => OrigenCambioPrecio::Alta,
+            _ => OrigenCambioPrecio::Edicion,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HistorialPrecio
+{
+    pub id_historial: i64,
+    pub id_producto: i64,
+    pub campo: CampoPrecio,
+    // None en el registro de alta del producto
+    pub precio_anterior: Option<f64>,
+    pub precio_nuevo: f64,
+    pub fecha: String,
+    pub origen: OrigenCambioPrecio,
+}
+
+// Lote de un producto; codigo_lote vacío y sin caducidad es el lote general
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Lote
+{
+    pub id_lote: i64,
+    pub id_producto: i64,
+    pub nombre_producto: String,
+    pub codigo_lote: String,
+    pub fecha_caducidad: Option<String>,
+    pub cantidad: i64,
+    // Negativo si ya venció; None si no tiene caducidad
+    pub dias_para_vencer: Option<i64>,
+    pub creado_at: String,
+}
+
+// Estado de una sesión de conteo físico
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum EstadoConteo
+{
+    Abierto,
+    Cerrado,
+}
+
+impl EstadoConteo {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EstadoConteo::Abierto => "Abierto",
+            EstadoConteo::Cerrado => "Cerrado",
+        }
+    }
+
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "Cerrado" => EstadoConteo::Cerrado,
+            _ => EstadoConteo::Abierto,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConteoInventario
+{
+    pub id_conteo: i64,
+    pub descripcion: String,
+    pub estado: EstadoConteo,
+    pub creado_at: String,
+    pub cerrado_at: Option<String>,
+    pub productos_contados: i64,
+}
+
+// Diferencia de un producto contado; en sesiones cerradas stock y precio son los del cierre
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct VarianzaConteo
+{
+    pub id_producto: i64,
+    pub nombre_producto: String,
+    pub stock_sistema: i64,
+    pub cantidad_contada: i64,
+    pub diferencia: i64,
+    pub precio_consultora: f64,
+    pub valor_diferencia: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReporteConteo
+{
+    pub conteo: ConteoInventario,
+    pub lineas: Vec<VarianzaConteo>,
+    pub valor_faltante: f64,
+    pub valor_sobrante: f64,
+    pub valor_neto: f64,
+}
+
 // Perfil del usuario
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Perfil
@@ -173,3 +956,358 @@ pub struct Perfil
     pub ruta_foto: Option<String>,
     pub miniatura_base64: Option<String>,
 }
+
+// Producto y cantidad de etiquetas a imprimir
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EtiquetaInput
+{
+    pub id_producto: i64,
+    pub cantidad: i64,
+}
+
+// Hojas de etiquetas comunes (columnas x filas)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum FormatoEtiquetas
+{
+    // Carta, 2.625" x 1" (tipo Avery 5160)
+    #[default]
+    #[serde(rename = "3x10")]
+    Carta3x10,
+    // Carta, 4" x 1" (tipo Avery 5161)
+    #[serde(rename = "2x10")]
+    Carta2x10,
+    // A4, 63.5 x 38.1 mm (tipo L7160)
+    #[serde(rename = "3x7")]
+    A4_3x7,
+}
+
+// Componente de un kit con el stock que tiene hoy
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct KitComponente
+{
+    pub id_componente: i64,
+    pub nombre_producto: String,
+    pub cantidad: i64,
+    pub stock: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct KitComponenteInput
+{
+    pub id_producto: i64,
+    pub cantidad: i64,
+}
+
+// Kits vendidos en el periodo
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct KitVendido
+{
+    pub id_kit: i64,
+    pub nombre_kit: String,
+    pub unidades: i64,
+    pub ingreso: f64,
+}
+
+// Unidades de un componente que salieron dentro de kits
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConsumoKit
+{
+    pub id_kit: i64,
+    pub nombre_kit: String,
+    pub id_componente: i64,
+    pub nombre_componente: String,
+    pub unidades: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReporteKits
+{
+    pub fecha_inicio: String,
+    pub fecha_fin: String,
+    pub kits: Vec<KitVendido>,
+    pub consumos: Vec<ConsumoKit>,
+}
+
+// Campaña o periodo de catálogo (fechas YYYY-MM-DD inclusivas)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Campana
+{
+    pub id_campana: i64,
+    pub nombre: String,
+    pub fecha_inicio: String,
+    pub fecha_fin: String,
+    pub creado_at: String,
+    pub productos_con_precio: i64,
+    pub ventas: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CampanaInput
+{
+    pub nombre: String,
+    pub fecha_inicio: String,
+    pub fecha_fin: String,
+}
+
+// Precio de campaña junto al precio normal del producto
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PrecioCampana
+{
+    pub id_campana: i64,
+    pub id_producto: i64,
+    pub nombre_producto: String,
+    pub precio_consultora: f64,
+    pub precio_publico: f64,
+    pub precio_consultora_base: f64,
+    pub precio_publico_base: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PrecioCampanaInput
+{
+    pub id_producto: i64,
+    pub precio_consultora: f64,
+    pub precio_publico: f64,
+}
+
+// Precio que aplica a un producto en una fecha
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PrecioVigente
+{
+    pub id_producto: i64,
+    pub id_campana: Option<i64>,
+    pub precio_consultora: f64,
+    pub precio_publico: f64,
+}
+
+// Imagen referenciada que no se pudo leer (archivo faltante o dañado)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImagenFallida
+{
+    // None: foto del perfil
+    pub id_producto: Option<i64>,
+    pub nombre: String,
+    pub ruta: String,
+    pub error: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RegeneracionMiniaturas
+{
+    pub actualizadas: i64,
+    pub fallidas: Vec<ImagenFallida>,
+}
+
+// Archivo dentro de la carpeta `images/`; `nombre` es la ruta relativa que se guarda en la base
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ArchivoImagen
+{
+    pub nombre: String,
+    pub ruta: String,
+    pub bytes: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReporteImagenes
+{
+    pub total_archivos: i64,
+    pub bytes_totales: i64,
+    // Archivos que ningún producto ni el perfil usan
+    pub huerfanas: Vec<ArchivoImagen>,
+    pub bytes_huerfanas: i64,
+    // Productos (o el perfil) que apuntan a un archivo que ya no existe
+    pub faltantes: Vec<ImagenFallida>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LimpiezaImagenes
+{
+    pub eliminadas: i64,
+    pub bytes_liberados: i64,
+}
+
+// ============== Listados paginados ==============
+// Modelo común de list_productos, list_ventas, list_ventas_con_cobranza y list_productos_vendidos
+// en su versión paginada; cada listado ignora los filtros que no le aplican
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+pub enum DireccionOrden
+{
+    #[serde(rename = "asc")]
+    #[default]
+    Asc,
+    #[serde(rename = "desc")]
+    Desc,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct FiltrosLista
+{
+    // Categoría y sus subcategorías
+    #[serde(default)]
+    pub id_categoria: Option<i64>,
+    #[serde(default)]
+    pub stock_desde: Option<i64>,
+    #[serde(default)]
+    pub stock_hasta: Option<i64>,
+    #[serde(default)]
+    pub incluir_descontinuados: bool,
+    // Fechas de venta inclusivas (YYYY-MM-DD)
+    #[serde(default)]
+    pub fecha_desde: Option<String>,
+    #[serde(default)]
+    pub fecha_hasta: Option<String>,
+    #[serde(default)]
+    pub id_clienta: Option<i64>,
+    #[serde(default)]
+    pub id_campana: Option<i64>,
+    #[serde(default)]
+    pub estado_pago: Option<EstadoPago>,
+    #[serde(default)]
+    pub id_venta: Option<i64>,
+    #[serde(default)]
+    pub id_producto: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ConsultaLista
+{
+    // Empieza en 1
+    #[serde(default)]
+    pub pagina: Option<i64>,
+    #[serde(default)]
+    pub tamano_pagina: Option<i64>,
+    // Campo de orden; los permitidos dependen del listado
+    #[serde(default)]
+    pub orden: Option<String>,
+    #[serde(default)]
+    pub direccion: DireccionOrden,
+    #[serde(default)]
+    pub filtros: FiltrosLista,
+    // Las miniaturas en base64 solo se envían si se piden
+    #[serde(default)]
+    pub incluir_miniaturas: bool,
+}
+
+impl ConsultaLista
+{
+    pub const TAMANO_PAGINA: i64 = 50;
+    pub const TAMANO_PAGINA_MAXIMO: i64 = 500;
+
+    pub fn pagina(&self) -> i64
+    {
+        self.pagina.unwrap_or(1).max(1)
+    }
+
+    pub fn tamano_pagina(&self) -> i64
+    {
+        self.tamano_pagina
+            .unwrap_or(Self::TAMANO_PAGINA)
+            .clamp(1, Self::TAMANO_PAGINA_MAXIMO)
+    }
+
+    pub fn offset(&self) -> i64
+    {
+        // Una página enorme no debe desbordar; SQLite regresa una página vacía
+        (self.pagina() - 1).saturating_mul(self.tamano_pagina())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Pagina<T>
+{
+    pub elementos: Vec<T>,
+    // Total de filas que cumplen los filtros, sin paginar
+    pub total: i64,
+    pub pagina: i64,
+    pub tamano_pagina: i64,
+    pub total_paginas: i64,
+}
+
+impl<T> Pagina<T>
+{
+    pub fn new(elementos: Vec<T>, total: i64, consulta: &ConsultaLista) -> Self
+    {
+        let tamano_pagina = consulta.tamano_pagina();
+        Pagina {
+            elementos,
+            total,
+            pagina: consulta.pagina(),
+            tamano_pagina,
+            total_paginas: (total + tamano_pagina - 1) / tamano_pagina,
+        }
+    }
+}
+
+// Encabezado del archivo que corresponde a cada campo; sin indicar, se busca un encabezado
+// con el nombre del campo (sin importar mayúsculas ni acentos)
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct MapeoColumnas
+{
+    #[serde(default)]
+    pub nombre: Option<String>,
+    #[serde(default)]
+    pub categoria: Option<String>,
+    #[serde(default)]
+    pub stock: Option<String>,
+    #[serde(default)]
+    pub precio_consultora: Option<String>,
+    #[serde(default)]
+    pub precio_publico: Option<String>,
+    #[serde(default)]
+    pub sku: Option<String>,
+    #[serde(default)]
+    pub codigo_barras: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct OpcionesImportacion
+{
+    #[serde(default)]
+    pub columnas: MapeoColumnas,
+    // Si un renglón coincide con un producto existente (por SKU, código de barras o nombre)
+    // lo actualiza; si no, lo omite
+    #[serde(default)]
+    pub actualizar_existentes: bool,
+    // Hoja del libro de Excel; por defecto la primera
+    #[serde(default)]
+    pub hoja: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum AccionImportacion
+{
+    #[serde(rename = "crear")]
+    Crear,
+    #[serde(rename = "actualizar")]
+    Actualizar,
+    #[serde(rename = "omitir")]
+    Omitir,
+    #[serde(rename = "error")]
+    Error,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FilaImportacion
+{
+    // Número de fila en el archivo, como lo muestra Excel
+    pub fila: i64,
+    pub nombre_producto: String,
+    pub accion: AccionImportacion,
+    // Producto existente que coincide (o el creado al aplicar)
+    pub id_producto: Option<i64>,
+    pub errores: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ResultadoImportacion
+{
+    pub filas: Vec<FilaImportacion>,
+    pub creados: i64,
+    pub actualizados: i64,
+    pub omitidos: i64,
+    pub con_errores: i64,
+    pub categorias_creadas: Vec<String>,
+    // false en la vista previa o si hubo errores: no se guardó nada
+    pub aplicado: bool,
+}
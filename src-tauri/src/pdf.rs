@@ -0,0 +1,227 @@
+// Generación mínima de PDF (PDF 1.4 sin compresión) y códigos de barras Code 128,
+// suficiente para hojas de etiquetas sin depender de servicios ni librerías externas.
+
+// Anchos barra/espacio de cada símbolo Code 128 (valores 0..=105) y el símbolo de paro
+const CODE128_PATRONES: [&str; 107] = [
+    "212222", "222122", "222221", "121223", "121322", "131222", "122213", "122312", "132212", "221213",
+    "221312", "231212", "112232", "122132", "122231", "113222", "123122", "123221", "223211", "221132",
+    "221231", "213212", "223112", "312131", "311222", "321122", "321221", "312212", "322112", "322211",
+    "212123", "212321", "232121", "111323", "131123", "131321", "112313", "132113", "132311", "211313",
+    "231113", "231311", "112133", "112331", "132131", "113123", "113321", "133121", "313121", "211331",
+    "231131", "213113", "213311", "213131", "311123", "311321", "331121", "312113", "312311", "332111",
+    "314111", "221411", "431111", "111224", "111422", "121124", "121421", "141122", "141221", "112214",
+    "112412", "122114", "122411", "142112", "142211", "241211", "221114", "413111", "241112", "134111",
+    "111242", "121142", "121241", "114212", "124112", "124211", "411212", "421112", "421211", "212141",
+    "214121", "412121", "111143", "111341", "131141", "114113", "114311", "411113", "411311", "113141",
+    "114131", "311141", "411131", "211412", "211214", "211232", "2331112",
+];
+
+const CODE128_START_B: u8 = 104;
+const CODE128_START_C: u8 = 105;
+const CODE128_STOP: u8 = 106;
+
+// Zona en blanco a cada lado del código, en módulos
+pub const CODE128_ZONA_SILENCIO: usize = 10;
+
+// Símbolos Code 128 (inicio, datos, verificador y paro) para `texto`; None si hay caracteres fuera del ASCII imprimible.
+// Los números de longitud par usan el juego C (dos dígitos por símbolo); el resto, el juego B
+pub fn code128_simbolos(texto: &str) -> Option<Vec<u8>>
+{
+    if texto.is_empty() {
+        return None;
+    }
+
+    let bytes = texto.as_bytes();
+    let mut simbolos = Vec::new();
+    if bytes.len() % 2 == 0 && bytes.iter().all(u8::is_ascii_digit) {
+        simbolos.push(CODE128_START_C);
+        for par in bytes.chunks(2) {
+            simbolos.push((par[0] - b'0') * 10 + (par[1] - b'0'));
+        }
+    } else {
+        simbolos.push(CODE128_START_B);
+        for &b in bytes {
+            if !(32..=126).contains(&b) {
+                return None;
+            }
+            simbolos.push(b - 32);
+        }
+    }
+
+    let suma: u32 = simbolos
+        .iter()
+        .enumerate()
+        .map(|(i, &s)| s as u32 * i.max(1) as u32)
+        .sum();
+    simbolos.push((suma % 103) as u8);
+    simbolos.push(CODE128_STOP);
+    Some(simbolos)
+}
+
+// Barras como (módulo inicial, ancho en módulos), sin contar la zona de silencio
+pub fn code128_barras(simbolos: &[u8]) -> (Vec<(usize, usize)>, usize)
+{
+    let mut barras = Vec::new();
+    let mut posicion = 0;
+    for &simbolo in simbolos {
+        for (i, ancho) in CODE128_PATRONES[simbolo as usize].bytes().enumerate() {
+            let ancho = (ancho - b'0') as usize;
+            // Los patrones alternan barra, espacio, barra...
+            if i % 2 == 0 {
+                barras.push((posicion, ancho));
+            }
+            posicion += ancho;
+        }
+    }
+    (barras, posicion)
+}
+
+#[derive(Clone, Copy)]
+pub enum Fuente
+{
+    Normal,
+    Negrita,
+}
+
+impl Fuente {
+    fn recurso(&self) -> &'static str {
+        match self {
+            Fuente::Normal => "F1",
+            Fuente::Negrita => "F2",
+        }
+    }
+}
+
+// Ancho aproximado de Helvetica por carácter, en fracción del tamaño de fuente
+pub fn ancho_texto(texto: &str, tamano: f64) -> f64
+{
+    texto.chars().count() as f64 * tamano * 0.55
+}
+
+// Página en puntos (1/72 de pulgada) con origen en la esquina inferior izquierda
+pub struct PaginaPdf
+{
+    contenido: Vec<u8>,
+}
+
+impl PaginaPdf
+{
+    fn new() -> Self
+    {
+        Self { contenido: Vec::new() }
+    }
+
+    pub fn texto(&mut self, x: f64, y: f64, tamano: f64, fuente: Fuente, texto: &str)
+    {
+        self.contenido.extend_from_slice(
+            format!("BT /{} {:.2} Tf {:.2} {:.2} Td (", fuente.recurso(), tamano, x, y).as_bytes(),
+        );
+        self.contenido.extend(codificar_texto(texto));
+        self.contenido.extend_from_slice(b") Tj ET\n");
+    }
+
+    pub fn rectangulo(&mut self, x: f64, y: f64, ancho: f64, alto: f64)
+    {
+        self.contenido
+            .extend_from_slice(format!("{:.3} {:.3} {:.3} {:.3} re f\n", x, y, ancho, alto).as_bytes());
+    }
+}
+
+pub struct DocumentoPdf
+{
+    ancho: f64,
+    alto: f64,
+    paginas: Vec<PaginaPdf>,
+}
+
+impl DocumentoPdf
+{
+    pub fn new(ancho: f64, alto: f64) -> Self
+    {
+        Self { ancho, alto, paginas: Vec::new() }
+    }
+
+    pub fn nueva_pagina(&mut self) -> &mut PaginaPdf
+    {
+        self.paginas.push(PaginaPdf::new());
+        self.paginas.last_mut().unwrap()
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8>
+    {
+        // 1 catálogo, 2 árbol de páginas, 3-4 fuentes, luego página + contenido por cada hoja
+        let mut objetos: Vec<Vec<u8>> = Vec::new();
+        let kids: Vec<String> = (0..self.paginas.len())
+            .map(|i| format!("{} 0 R", 5 + i * 2))
+            .collect();
+
+        objetos.push(b"<< /Type /Catalog /Pages 2 0 R >>".to_vec());
+        objetos.push(
+            format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids.join(" "), self.paginas.len()).into_bytes(),
+        );
+        objetos.push(
+            b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>".to_vec(),
+        );
+        objetos.push(
+            b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>".to_vec(),
+        );
+
+        for (i, pagina) in self.paginas.iter().enumerate() {
+            let id_contenido = 6 + i * 2;
+            objetos.push(
+                format!(
+                    "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {:.2} {:.2}] \
+                     /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+                    self.ancho, self.alto, id_contenido
+                )
+                .into_bytes(),
+            );
+
+            let mut stream = format!("<< /Length {} >>\nstream\n", pagina.contenido.len()).into_bytes();
+            stream.extend_from_slice(&pagina.contenido);
+            stream.extend_from_slice(b"\nendstream");
+            objetos.push(stream);
+        }
+
+        let mut salida = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+        let mut offsets = Vec::new();
+        for (i, objeto) in objetos.iter().enumerate() {
+            offsets.push(salida.len());
+            salida.extend_from_slice(format!("{} 0 obj\n", i + 1).as_bytes());
+            salida.extend_from_slice(objeto);
+            salida.extend_from_slice(b"\nendobj\n");
+        }
+
+        let inicio_xref = salida.len();
+        salida.extend_from_slice(format!("xref\n0 {}\n0000000000 65535 f \n", objetos.len() + 1).as_bytes());
+        for offset in offsets {
+            salida.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+        }
+        salida.extend_from_slice(
+            format!(
+                "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+                objetos.len() + 1,
+                inicio_xref
+            )
+            .as_bytes(),
+        );
+        salida
+    }
+}
+
+// Texto literal en WinAnsi: Latin-1 cubre acentos y ñ; lo demás se reemplaza por '?'
+fn codificar_texto(texto: &str) -> Vec<u8>
+{
+    let mut bytes = Vec::new();
+    for c in texto.chars() {
+        let b = match c as u32 {
+            0x20..=0x7E | 0xA0..=0xFF => c as u32 as u8,
+            _ => b'?',
+        };
+        if matches!(b, b'(' | b')' | b'\\') {
+            bytes.push(b'\\');
+        }
+        bytes.push(b);
+    }
+    bytes
+}
@@ -43,6 +43,37 @@ impl<'a> AbonoVentaRepo<'a>
         Ok(abonos)
     }
 
+    // Abonos de todas las ventas de una clienta, del más antiguo al más reciente
+    pub fn list_by_clienta(&self, id_clienta: i64) -> rusqlite::Result<Vec<AbonoVenta>>
+    {
+        let mut stmt = self.conn.prepare(
+            "SELECT a.id_abono, a.id_venta, a.monto_abono, a.fecha_abono, a.metodo_registro, a.observacion, a.creado_at \
+             FROM abonos_venta a \
+             INNER JOIN ventas v ON v.id_venta = a.id_venta \
+             WHERE v.id_clienta = ?1 \
+             ORDER BY a.fecha_abono, a.id_abono"
+        )?;
+
+        let rows = stmt.query_map(params![id_clienta], |row| {
+            Ok(AbonoVenta {
+                id_abono: row.get(0)?,
+                id_venta: row.get(1)?,
+                monto_abono: row.get(2)?,
+                fecha_abono: row.get(3)?,
+                metodo_registro: row.get(4)?,
+                observacion: row.get(5)?,
+                creado_at: row.get(6)?,
+            })
+        })?;
+
+        let mut abonos = Vec::new();
+        for abono in rows
+        {
+            abonos.push(abono?);
+        }
+        Ok(abonos)
+    }
+
     pub fn sum_by_venta(&self, id_venta: i64) -> rusqlite::Result<f64>
     {
         self.conn.query_row(
@@ -0,0 +1,210 @@
+use rusqlite::{Connection, Row, params};
+use crate::models::{Campana, PrecioCampana};
+
+pub struct CampanaRepo<'a>
+{
+    pub conn: &'a Connection,
+}
+
+const CAMPANA_SELECT: &str =
+    "SELECT c.id_campana, c.nombre, c.fecha_inicio, c.fecha_fin, c.creado_at, \
+            (SELECT COUNT(*) FROM precios_campana pc WHERE pc.id_campana = c.id_campana), \
+            (SELECT COUNT(*) FROM ventas v WHERE v.id_campana = c.id_campana) \
+     FROM campanas c";
+
+impl<'a> CampanaRepo<'a>
+{
+    fn map_row(row: &Row) -> rusqlite::Result<Campana>
+    {
+        Ok(Campana {
+            id_campana: row.get(0)?,
+            nombre: row.get(1)?,
+            fecha_inicio: row.get(2)?,
+            fecha_fin: row.get(3)?,
+            creado_at: row.get(4)?,
+            productos_con_precio: row.get(5)?,
+            ventas: row.get(6)?,
+        })
+    }
+
+    // Más reciente primero
+    pub fn list(&self) -> rusqlite::Result<Vec<Campana>>
+    {
+        let mut stmt = self.conn.prepare(&format!(
+            "{} ORDER BY c.fecha_inicio DESC, c.id_campana DESC",
+            CAMPANA_SELECT
+        ))?;
+
+        let rows = stmt.query_map([], Self::map_row)?;
+        let mut campanas = Vec::new();
+        for campana in rows
+        {
+            campanas.push(campana?);
+        }
+        Ok(campanas)
+    }
+
+    pub fn get(&self, id_campana: i64) -> rusqlite::Result<Campana>
+    {
+        self.conn.query_row(
+            &format!("{} WHERE c.id_campana = ?1", CAMPANA_SELECT),
+            params![id_campana],
+            Self::map_row,
+        )
+    }
+
+    // Campaña cuyo periodo contiene la fecha (YYYY-MM-DD)
+    pub fn find_por_fecha(&self, fecha: &str) -> rusqlite::Result<Option<Campana>>
+    {
+        match self.conn.query_row(
+            &format!(
+                "{} WHERE DATE(?1) BETWEEN c.fecha_inicio AND c.fecha_fin ORDER BY c.fecha_inicio DESC LIMIT 1",
+                CAMPANA_SELECT
+            ),
+            params![fecha],
+            Self::map_row,
+        ) {
+            Ok(campana) => Ok(Some(campana)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    // Otra campaña cuyo periodo se cruza con [fecha_inicio, fecha_fin]
+    pub fn find_empalme(&self, fecha_inicio: &str, fecha_fin: &str, excluir: Option<i64>) -> rusqlite::Result<Option<Campana>>
+    {
+        match self.conn.query_row(
+            &format!(
+                "{} WHERE c.fecha_inicio <= ?2 AND c.fecha_fin >= ?1 AND c.id_campana IS NOT ?3 LIMIT 1",
+                CAMPANA_SELECT
+            ),
+            params![fecha_inicio, fecha_fin, excluir],
+            Self::map_row,
+        ) {
+            Ok(campana) => Ok(Some(campana)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn create(&self, nombre: &str, fecha_inicio: &str, fecha_fin: &str) -> rusqlite::Result<i64>
+    {
+        self.conn.execute(
+            "INSERT INTO campanas (nombre, fecha_inicio, fecha_fin) VALUES (?1, ?2, ?3)",
+            params![nombre, fecha_inicio, fecha_fin],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    pub fn update(&self, id_campana: i64, nombre: &str, fecha_inicio: &str, fecha_fin: &str) -> rusqlite::Result<usize>
+    {
+        self.conn.execute(
+            "UPDATE campanas SET nombre = ?2, fecha_inicio = ?3, fecha_fin = ?4 WHERE id_campana = ?1",
+            params![id_campana, nombre, fecha_inicio, fecha_fin],
+        )
+    }
+
+    pub fn delete(&self, id_campana: i64) -> rusqlite::Result<()>
+    {
+        self.conn.execute("DELETE FROM campanas WHERE id_campana = ?1", params![id_campana])?;
+        Ok(())
+    }
+
+    // Ventas sin campaña cuya fecha cae en el periodo pasan a la campaña
+    pub fn asignar_ventas_sin_campana(&self, id_campana: i64) -> rusqlite::Result<usize>
+    {
+        self.conn.execute(
+            "UPDATE ventas
+             SET id_campana = ?1
+             WHERE id_campana IS NULL
+               AND DATE(fecha) BETWEEN (SELECT fecha_inicio FROM campanas WHERE id_campana = ?1)
+                                   AND (SELECT fecha_fin FROM campanas WHERE id_campana = ?1)",
+            params![id_campana],
+        )
+    }
+
+    // Ventas de la campaña cuya fecha ya no cae en su periodo pasan a la campaña vigente en su fecha (o a ninguna)
+    pub fn reasignar_ventas_fuera_de_periodo(&self, id_campana: i64) -> rusqlite::Result<usize>
+    {
+        self.conn.execute(
+            "UPDATE ventas
+             SET id_campana = (
+               SELECT c.id_campana FROM campanas c
+               WHERE DATE(ventas.fecha) BETWEEN c.fecha_inicio AND c.fecha_fin
+               ORDER BY c.fecha_inicio DESC
+               LIMIT 1
+             )
+             WHERE id_campana = ?1
+               AND DATE(fecha) NOT BETWEEN (SELECT fecha_inicio FROM campanas WHERE id_campana = ?1)
+                                       AND (SELECT fecha_fin FROM campanas WHERE id_campana = ?1)",
+            params![id_campana],
+        )
+    }
+
+    pub fn list_precios(&self, id_campana: i64) -> rusqlite::Result<Vec<PrecioCampana>>
+    {
+        let mut stmt = self.conn.prepare(
+            "SELECT pc.id_campana, pc.id_producto, p.nombre_producto, pc.precio_consultora, pc.precio_publico, \
+                    p.precio_consultora, p.precio_publico \
+             FROM precios_campana pc \
+             INNER JOIN productos p ON p.id_producto = pc.id_producto \
+             WHERE pc.id_campana = ?1 \
+             ORDER BY p.nombre_producto COLLATE NOCASE"
+        )?;
+
+        let rows = stmt.query_map(params![id_campana], |row| {
+            Ok(PrecioCampana {
+                id_campana: row.get(0)?,
+                id_producto: row.get(1)?,
+                nombre_producto: row.get(2)?,
+                precio_consultora: row.get(3)?,
+                precio_publico: row.get(4)?,
+                precio_consultora_base: row.get(5)?,
+                precio_publico_base: row.get(6)?,
+            })
+        })?;
+
+        let mut precios = Vec::new();
+        for precio in rows
+        {
+            precios.push(precio?);
+        }
+        Ok(precios)
+    }
+
+    // (precio_consultora, precio_publico) de campaña del producto, si tiene
+    pub fn find_precio(&self, id_campana: i64, id_producto: i64) -> rusqlite::Result<Option<(f64, f64)>>
+    {
+        match self.conn.query_row(
+            "SELECT precio_consultora, precio_publico FROM precios_campana WHERE id_campana = ?1 AND id_producto = ?2",
+            params![id_campana, id_producto],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        ) {
+            Ok(precio) => Ok(Some(precio)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn upsert_precio(&self, id_campana: i64, id_producto: i64, precio_consultora: f64, precio_publico: f64) -> rusqlite::Result<()>
+    {
+        self.conn.execute(
+            "INSERT INTO precios_campana (id_campana, id_producto, precio_consultora, precio_publico)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(id_campana, id_producto) DO UPDATE SET
+                precio_consultora = excluded.precio_consultora,
+                precio_publico = excluded.precio_publico",
+            params![id_campana, id_producto, precio_consultora, precio_publico],
+        )?;
+        Ok(())
+    }
+
+    pub fn delete_precio(&self, id_campana: i64, id_producto: i64) -> rusqlite::Result<()>
+    {
+        self.conn.execute(
+            "DELETE FROM precios_campana WHERE id_campana = ?1 AND id_producto = ?2",
+            params![id_campana, id_producto],
+        )?;
+        Ok(())
+    }
+}
@@ -1,6 +1,16 @@
 use rusqlite::{Connection, params};
 use crate::models::Categoria;
 
+// CTE `rutas_categoria(id_categoria, ruta)` con la ruta completa de cada categoría ("Maquillaje > Labios")
+pub const RUTAS_CATEGORIA_CTE: &str = "WITH RECURSIVE rutas_categoria(id_categoria, ruta) AS ( \
+        SELECT id_categoria, nombre FROM categorias \
+        WHERE id_categoria_padre IS NULL \
+           OR id_categoria_padre NOT IN (SELECT id_categoria FROM categorias) \
+        UNION ALL \
+        SELECT c.id_categoria, r.ruta || ' > ' || c.nombre \
+        FROM categorias c INNER JOIN rutas_categoria r ON c.id_categoria_padre = r.id_categoria \
+    )";
+
 pub struct CategoriaRepo<'a> {
     pub conn: &'a Connection,
 }
@@ -12,12 +22,14 @@ impl<'a> CategoriaRepo<'a>
 
     pub fn list(&self) -> rusqlite::Result<Vec<Categoria>> 
     {
-        let mut stmt = self.conn.prepare("SELECT id_categoria, nombre FROM categorias")?;
+        let mut stmt = self.conn.prepare("SELECT id_categoria, nombre, stock_minimo_default, id_categoria_padre FROM categorias")?;
 
         let rows = stmt.query_map([], |row| {
             Ok(Categoria {
                 id_categoria: row.get(0)?,
                 nombre: row.get(1)?,
+                stock_minimo_default: row.get(2)?,
+                id_categoria_padre: row.get(3)?,
             })
         })?;
 
@@ -33,12 +45,14 @@ impl<'a> CategoriaRepo<'a>
     pub fn get(&self, id: i64) -> rusqlite::Result<Categoria> 
     {
         self.conn.query_row(
-            "SELECT id_categoria, nombre FROM categorias WHERE id_categoria = ?1", 
+            "SELECT id_categoria, nombre, stock_minimo_default, id_categoria_padre FROM categorias WHERE id_categoria = ?1", 
             params![id],
         |row| {
                 Ok(Categoria {
                     id_categoria:row.get(0)?,
                     nombre: row.get(1)?,
+                    stock_minimo_default: row.get(2)?,
+                    id_categoria_padre: row.get(3)?,
                 })
             }, 
         )   
@@ -59,6 +73,128 @@ impl<'a> CategoriaRepo<'a>
         Ok(())
     }
 
+    //stock mínimo por defecto (no lo modifica update para no pisarlo al renombrar)
+    pub fn update_stock_minimo_default(&self, id: i64, stock_minimo: Option<i64>) -> rusqlite::Result<usize>
+    {
+        self.conn.execute("UPDATE categorias SET stock_minimo_default = ?1 WHERE id_categoria = ?2", params![stock_minimo, id])
+    }
+
+    //categoría con el mismo nombre sin distinguir mayúsculas, sin contar `excluir`
+    pub fn find_by_nombre(&self, nombre: &str, excluir: Option<i64>) -> rusqlite::Result<Option<Categoria>>
+    {
+        let result = self.conn.query_row(
+            "SELECT id_categoria, nombre, stock_minimo_default, id_categoria_padre FROM categorias
+             WHERE TRIM(nombre) = TRIM(?1) COLLATE NOCASE AND (?2 IS NULL OR id_categoria <> ?2)
+             LIMIT 1",
+            params![nombre, excluir],
+            |row| {
+                Ok(Categoria {
+                    id_categoria: row.get(0)?,
+                    nombre: row.get(1)?,
+                    stock_minimo_default: row.get(2)?,
+                    id_categoria_padre: row.get(3)?,
+                })
+            },
+        );
+        match result {
+            Ok(categoria) => Ok(Some(categoria)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    //(productos activos, descontinuados, subcategorías directas, promociones) de la categoría
+    pub fn count_dependientes(&self, id: i64) -> rusqlite::Result<(i64, i64, i64, i64)>
+    {
+        self.conn.query_row(
+            "SELECT
+                (SELECT COUNT(*) FROM productos WHERE id_categoria = ?1 AND activo = 1),
+                (SELECT COUNT(*) FROM productos WHERE id_categoria = ?1 AND activo <> 1),
+                (SELECT COUNT(*) FROM categorias WHERE id_categoria_padre = ?1),
+                (SELECT COUNT(*) FROM promociones WHERE id_categoria = ?1)",
+            params![id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+    }
+
+    //todos los productos (activos o no) de `origen` pasan a `destino` (None: sin categoría)
+    pub fn mover_productos(&self, origen: i64, destino: Option<i64>) -> rusqlite::Result<usize>
+    {
+        self.conn.execute(
+            "UPDATE productos SET id_categoria = ?2, actualizado_at = datetime('now','localtime') WHERE id_categoria = ?1",
+            params![origen, destino],
+        )
+    }
+
+    pub fn mover_promociones(&self, origen: i64, destino: i64) -> rusqlite::Result<usize>
+    {
+        self.conn.execute(
+            "UPDATE promociones SET id_categoria = ?2 WHERE id_categoria = ?1",
+            params![origen, destino],
+        )
+    }
+
+    //las subcategorías directas de `origen` pasan a colgar de `destino`
+    pub fn mover_subcategorias(&self, origen: i64, destino: i64) -> rusqlite::Result<usize>
+    {
+        self.conn.execute(
+            "UPDATE categorias SET id_categoria_padre = ?2 WHERE id_categoria_padre = ?1 AND id_categoria <> ?2",
+            params![origen, destino],
+        )
+    }
+
+    //padre de la categoría (no lo modifica update para no pisarlo al renombrar)
+    pub fn update_padre(&self, id: i64, id_categoria_padre: Option<i64>) -> rusqlite::Result<usize>
+    {
+        self.conn.execute("UPDATE categorias SET id_categoria_padre = ?1 WHERE id_categoria = ?2", params![id_categoria_padre, id])
+    }
+
+    //las subcategorías directas pasan al padre de `id`
+    pub fn subir_subcategorias(&self, id: i64) -> rusqlite::Result<usize>
+    {
+        self.conn.execute(
+            "UPDATE categorias
+             SET id_categoria_padre = (SELECT padre.id_categoria_padre FROM categorias padre WHERE padre.id_categoria = ?1)
+             WHERE id_categoria_padre = ?1",
+            params![id],
+        )
+    }
+
+    //la categoría y todas sus subcategorías, a cualquier profundidad
+    pub fn ids_descendientes(&self, id: i64) -> rusqlite::Result<Vec<i64>>
+    {
+        let mut stmt = self.conn.prepare(
+            "WITH RECURSIVE arbol(id_categoria) AS (
+                SELECT ?1
+                UNION
+                SELECT c.id_categoria FROM categorias c INNER JOIN arbol a ON c.id_categoria_padre = a.id_categoria
+             )
+             SELECT id_categoria FROM arbol"
+        )?;
+
+        let rows = stmt.query_map(params![id], |row| row.get(0))?;
+        let mut ids = Vec::new();
+        for id in rows {
+            ids.push(id?);
+        }
+        Ok(ids)
+    }
+
+    //productos activos por categoría (solo los asignados directamente)
+    pub fn count_productos(&self) -> rusqlite::Result<Vec<(i64, i64)>>
+    {
+        let mut stmt = self.conn.prepare(
+            "SELECT id_categoria, COUNT(*) FROM productos WHERE activo = 1 AND id_categoria IS NOT NULL GROUP BY id_categoria"
+        )?;
+
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        let mut conteos = Vec::new();
+        for conteo in rows {
+            conteos.push(conteo?);
+        }
+        Ok(conteos)
+    }
+
     //delete
     pub fn delete(&self, id: i64) -> rusqlite::Result<()>
     {
@@ -0,0 +1,129 @@
+use rusqlite::{Connection, OptionalExtension, Row, params};
+use crate::models::Clienta;
+
+pub struct ClientaRepo<'a>
+{
+    pub conn: &'a Connection,
+}
+
+const CLIENTA_COLUMNS: &str = "id_clienta, nombre, apellido, telefono, direccion, notas, creado_at";
+
+impl<'a> ClientaRepo<'a>
+{
+    fn map_row(row: &Row) -> rusqlite::Result<Clienta>
+    {
+        Ok(Clienta {
+            id_clienta: row.get(0)?,
+            nombre: row.get(1)?,
+            apellido: row.get(2)?,
+            telefono: row.get(3)?,
+            direccion: row.get(4)?,
+            notas: row.get(5)?,
+            creado_at: row.get(6)?,
+        })
+    }
+
+    pub fn list(&self) -> rusqlite::Result<Vec<Clienta>>
+    {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM clientas ORDER BY nombre COLLATE NOCASE, apellido COLLATE NOCASE",
+            CLIENTA_COLUMNS
+        ))?;
+
+        let rows = stmt.query_map([], Self::map_row)?;
+
+        let mut clientas = Vec::new();
+        for clienta in rows
+        {
+            clientas.push(clienta?);
+        }
+        Ok(clientas)
+    }
+
+    // Busca por nombre completo o teléfono (coincidencia parcial, sin distinguir mayúsculas)
+    pub fn search(&self, texto: &str) -> rusqlite::Result<Vec<Clienta>>
+    {
+        let patron = format!("%{}%", texto);
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM clientas \
+             WHERE (nombre || ' ' || apellido) LIKE ?1 \
+                OR telefono LIKE ?1 \
+             ORDER BY nombre COLLATE NOCASE, apellido COLLATE NOCASE",
+            CLIENTA_COLUMNS
+        ))?;
+
+        let rows = stmt.query_map(params![patron], Self::map_row)?;
+
+        let mut clientas = Vec::new();
+        for clienta in rows
+        {
+            clientas.push(clienta?);
+        }
+        Ok(clientas)
+    }
+
+    pub fn get(&self, id: i64) -> rusqlite::Result<Clienta>
+    {
+        self.conn.query_row(
+            &format!("SELECT {} FROM clientas WHERE id_clienta = ?1", CLIENTA_COLUMNS),
+            params![id],
+            Self::map_row,
+        )
+    }
+
+    pub fn find_by_nombre(&self, nombre: &str, apellido: &str) -> rusqlite::Result<Option<Clienta>>
+    {
+        self.conn
+            .query_row(
+                &format!(
+                    "SELECT {} FROM clientas \
+                     WHERE LOWER(nombre) = LOWER(?1) AND LOWER(apellido) = LOWER(?2) \
+                     ORDER BY id_clienta LIMIT 1",
+                    CLIENTA_COLUMNS
+                ),
+                params![nombre, apellido],
+                Self::map_row,
+            )
+            .optional()
+    }
+
+    pub fn create(&self, nombre: &str, apellido: &str, telefono: &str, direccion: &str, notas: &str) -> rusqlite::Result<i64>
+    {
+        self.conn.execute(
+            "INSERT INTO clientas (nombre, apellido, telefono, direccion, notas) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![nombre, apellido, telefono, direccion, notas],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    pub fn update(&self, clienta: &Clienta) -> rusqlite::Result<()>
+    {
+        self.conn.execute(
+            "UPDATE clientas SET nombre = ?1, apellido = ?2, telefono = ?3, direccion = ?4, notas = ?5 WHERE id_clienta = ?6",
+            params![
+                clienta.nombre,
+                clienta.apellido,
+                clienta.telefono,
+                clienta.direccion,
+                clienta.notas,
+                clienta.id_clienta,
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn count_ventas(&self, id: i64) -> rusqlite::Result<i64>
+    {
+        self.conn.query_row(
+            "SELECT COUNT(*) FROM ventas WHERE id_clienta = ?1",
+            params![id],
+            |row| row.get(0),
+        )
+    }
+
+    pub fn delete(&self, id: i64) -> rusqlite::Result<()>
+    {
+        self.conn.execute("DELETE FROM clientas WHERE id_clienta = ?1", params![id])?;
+        Ok(())
+    }
+}
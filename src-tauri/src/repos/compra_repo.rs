@@ -0,0 +1,138 @@
+use rusqlite::{Connection, Row, params};
+use crate::models::{Compra, CompraDetalle, EstadoCompra};
+
+pub struct CompraRepo<'a>
+{
+    pub conn: &'a Connection,
+}
+
+// Cabecera de compra con el nombre del proveedor y lo pagado hasta ahora
+const COMPRA_SELECT: &str = "SELECT c.id_compra, c.id_proveedor, COALESCE(pr.nombre, ''), c.fecha, c.estado, \
+    c.total_compra, c.notas, \
+    COALESCE((SELECT SUM(pc.monto_pago) FROM pagos_compra pc WHERE pc.id_compra = c.id_compra), 0) \
+    FROM compras c \
+    LEFT JOIN proveedores pr ON pr.id_proveedor = c.id_proveedor";
+
+impl<'a> CompraRepo<'a>
+{
+    fn map_compra(row: &Row) -> rusqlite::Result<Compra>
+    {
+        let estado: String = row.get(4)?;
+        let total_compra: f64 = row.get(5)?;
+        let total_pagado: f64 = row.get(7)?;
+        Ok(Compra {
+            id_compra: row.get(0)?,
+            id_proveedor: row.get(1)?,
+            nombre_proveedor: row.get(2)?,
+            fecha: row.get(3)?,
+            estado: EstadoCompra::from_db(&estado),
+            total_compra,
+            notas: row.get(6)?,
+            total_pagado,
+            saldo_pendiente: if total_compra > total_pagado { total_compra - total_pagado } else { 0.0 },
+        })
+    }
+
+    fn map_detalle(row: &Row) -> rusqlite::Result<CompraDetalle>
+    {
+        Ok(CompraDetalle {
+            id_detalle: row.get(0)?,
+            id_compra: row.get(1)?,
+            id_producto: row.get(2)?,
+            nombre_producto: row.get(3)?,
+            cantidad_pedida: row.get(4)?,
+            cantidad_recibida: row.get(5)?,
+            costo_unitario: row.get(6)?,
+            subtotal: row.get(7)?,
+        })
+    }
+
+    pub fn list(&self) -> rusqlite::Result<Vec<Compra>>
+    {
+        let mut stmt = self.conn.prepare(&format!(
+            "{} ORDER BY c.fecha DESC, c.id_compra DESC",
+            COMPRA_SELECT
+        ))?;
+
+        let rows = stmt.query_map([], Self::map_compra)?;
+
+        let mut compras = Vec::new();
+        for compra in rows
+        {
+            compras.push(compra?);
+        }
+        Ok(compras)
+    }
+
+    pub fn get(&self, id: i64) -> rusqlite::Result<Compra>
+    {
+        self.conn.query_row(
+            &format!("{} WHERE c.id_compra = ?1", COMPRA_SELECT),
+            params![id],
+            Self::map_compra,
+        )
+    }
+
+    pub fn create(&self, id_proveedor: Option<i64>, fecha: &str, total_compra: f64, notas: &str) -> rusqlite::Result<i64>
+    {
+        self.conn.execute(
+            "INSERT INTO compras (id_proveedor, fecha, total_compra, notas) VALUES (?1, ?2, ?3, ?4)",
+            params![id_proveedor, fecha, total_compra, notas],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    pub fn update_estado(&self, id: i64, estado: &EstadoCompra) -> rusqlite::Result<()>
+    {
+        self.conn.execute(
+            "UPDATE compras SET estado = ?1 WHERE id_compra = ?2",
+            params![estado.as_str(), id],
+        )?;
+        Ok(())
+    }
+
+    pub fn delete(&self, id: i64) -> rusqlite::Result<()>
+    {
+        self.conn.execute("DELETE FROM compras WHERE id_compra = ?1", params![id])?;
+        Ok(())
+    }
+
+    pub fn create_detalle(&self, id_compra: i64, id_producto: i64, cantidad_pedida: i64, costo_unitario: f64, subtotal: f64) -> rusqlite::Result<i64>
+    {
+        self.conn.execute(
+            "INSERT INTO compras_detalle (id_compra, id_producto, cantidad_pedida, costo_unitario, subtotal) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![id_compra, id_producto, cantidad_pedida, costo_unitario, subtotal],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    pub fn list_detalle(&self, id_compra: i64) -> rusqlite::Result<Vec<CompraDetalle>>
+    {
+        let mut stmt = self.conn.prepare(
+            "SELECT d.id_detalle, d.id_compra, d.id_producto, COALESCE(p.nombre_producto, ''), \
+                    d.cantidad_pedida, d.cantidad_recibida, d.costo_unitario, d.subtotal \
+             FROM compras_detalle d \
+             LEFT JOIN productos p ON p.id_producto = d.id_producto \
+             WHERE d.id_compra = ?1 \
+             ORDER BY d.id_detalle"
+        )?;
+
+        let rows = stmt.query_map(params![id_compra], Self::map_detalle)?;
+
+        let mut lineas = Vec::new();
+        for linea in rows
+        {
+            lineas.push(linea?);
+        }
+        Ok(lineas)
+    }
+
+    pub fn add_recibido(&self, id_detalle: i64, cantidad: i64) -> rusqlite::Result<()>
+    {
+        self.conn.execute(
+            "UPDATE compras_detalle SET cantidad_recibida = cantidad_recibida + ?1 WHERE id_detalle = ?2",
+            params![cantidad, id_detalle],
+        )?;
+        Ok(())
+    }
+}
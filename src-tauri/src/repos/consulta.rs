@@ -0,0 +1,88 @@
+use rusqlite::ToSql;
+use crate::models::{ConsultaLista, DireccionOrden, EstadoPago};
+use crate::services::business_error;
+
+// Condiciones WHERE de un listado paginado con sus parámetros numerados en orden
+#[derive(Default)]
+pub struct FiltroSql
+{
+    condiciones: Vec<String>,
+    parametros: Vec<Box<dyn ToSql>>,
+}
+
+impl FiltroSql
+{
+    // `condicion` usa `?` para el valor; todas sus apariciones toman el mismo parámetro
+    pub fn con<T: ToSql + 'static>(&mut self, condicion: &str, valor: Option<T>)
+    {
+        if let Some(valor) = valor {
+            self.parametros.push(Box::new(valor));
+            let numerado = condicion.replace('?', &format!("?{}", self.parametros.len()));
+            self.condiciones.push(numerado);
+        }
+    }
+
+    pub fn con_sql(&mut self, condicion: &str)
+    {
+        self.condiciones.push(condicion.to_string());
+    }
+
+    pub fn sql_where(&self) -> String
+    {
+        if self.condiciones.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", self.condiciones.join(" AND "))
+        }
+    }
+
+    pub fn parametros(&self) -> Vec<&dyn ToSql>
+    {
+        self.parametros.iter().map(|parametro| parametro.as_ref()).collect()
+    }
+}
+
+// Categoría `?` y todas sus subcategorías, para usar con `columna IN (...)`
+pub const CATEGORIA_Y_SUBCATEGORIAS: &str = "WITH RECURSIVE arbol(id_categoria) AS ( \
+        SELECT ? \
+        UNION \
+        SELECT c.id_categoria FROM categorias c INNER JOIN arbol a ON c.id_categoria_padre = a.id_categoria \
+    ) SELECT id_categoria FROM arbol";
+
+// ORDER BY con el campo pedido (`campos`: nombre público -> expresión SQL) y `desempate` para que
+// las páginas sean estables; sin campo se usa `por_defecto` y un campo desconocido es un error
+pub fn orden_sql(consulta: &ConsultaLista, campos: &[(&str, &str)], por_defecto: &str, desempate: &str) -> rusqlite::Result<String>
+{
+    let direccion = match consulta.direccion {
+        DireccionOrden::Asc => "ASC",
+        DireccionOrden::Desc => "DESC",
+    };
+    let Some(orden) = consulta.orden.as_deref() else {
+        return Ok(format!("ORDER BY {}", por_defecto));
+    };
+    match campos.iter().find(|(campo, _)| *campo == orden) {
+        Some((_, expresion)) => Ok(format!("ORDER BY {expresion} {direccion}, {desempate} {direccion}")),
+        None => {
+            let permitidos: Vec<&str> = campos.iter().map(|(campo, _)| *campo).collect();
+            Err(business_error(&format!(
+                "No se puede ordenar por '{}'; usa {}",
+                orden,
+                permitidos.join(", ")
+            )))
+        }
+    }
+}
+
+pub fn limite_sql(consulta: &ConsultaLista) -> String
+{
+    format!("LIMIT {} OFFSET {}", consulta.tamano_pagina(), consulta.offset())
+}
+
+pub fn estado_pago_texto(estado: EstadoPago) -> &'static str
+{
+    match estado {
+        EstadoPago::Pendiente => "Pendiente",
+        EstadoPago::Parcial => "Parcial",
+        EstadoPago::Liquidada => "Liquidada",
+    }
+}
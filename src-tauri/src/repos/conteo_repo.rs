@@ -0,0 +1,143 @@
+use rusqlite::{Connection, Row, params};
+use crate::models::{ConteoInventario, EstadoConteo, VarianzaConteo};
+
+pub struct ConteoRepo<'a>
+{
+    pub conn: &'a Connection,
+}
+
+const CONTEO_SELECT: &str = "SELECT c.id_conteo, c.descripcion, c.estado, c.creado_at, c.cerrado_at, \
+    (SELECT COUNT(*) FROM conteos_detalle d WHERE d.id_conteo = c.id_conteo) \
+    FROM conteos_inventario c";
+
+impl<'a> ConteoRepo<'a>
+{
+    fn map_conteo(row: &Row) -> rusqlite::Result<ConteoInventario>
+    {
+        let estado: String = row.get(2)?;
+        Ok(ConteoInventario {
+            id_conteo: row.get(0)?,
+            descripcion: row.get(1)?,
+            estado: EstadoConteo::from_db(&estado),
+            creado_at: row.get(3)?,
+            cerrado_at: row.get(4)?,
+            productos_contados: row.get(5)?,
+        })
+    }
+
+    pub fn list(&self) -> rusqlite::Result<Vec<ConteoInventario>>
+    {
+        let mut stmt = self.conn.prepare(&format!("{} ORDER BY c.creado_at DESC, c.id_conteo DESC", CONTEO_SELECT))?;
+        let rows = stmt.query_map([], Self::map_conteo)?;
+
+        let mut conteos = Vec::new();
+        for conteo in rows
+        {
+            conteos.push(conteo?);
+        }
+        Ok(conteos)
+    }
+
+    pub fn get(&self, id_conteo: i64) -> rusqlite::Result<ConteoInventario>
+    {
+        self.conn.query_row(
+            &format!("{} WHERE c.id_conteo = ?1", CONTEO_SELECT),
+            params![id_conteo],
+            Self::map_conteo,
+        )
+    }
+
+    pub fn count_abiertos(&self) -> rusqlite::Result<i64>
+    {
+        self.conn.query_row(
+            "SELECT COUNT(*) FROM conteos_inventario WHERE estado = 'Abierto'",
+            [],
+            |row| row.get(0),
+        )
+    }
+
+    pub fn create(&self, descripcion: &str) -> rusqlite::Result<i64>
+    {
+        self.conn.execute(
+            "INSERT INTO conteos_inventario (descripcion) VALUES (?1)",
+            params![descripcion],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    // Registrar otra vez el mismo producto reemplaza la cantidad contada
+    pub fn upsert_detalle(&self, id_conteo: i64, id_producto: i64, cantidad_contada: i64) -> rusqlite::Result<()>
+    {
+        self.conn.execute(
+            "INSERT INTO conteos_detalle (id_conteo, id_producto, cantidad_contada) VALUES (?1, ?2, ?3)
+             ON CONFLICT(id_conteo, id_producto)
+             DO UPDATE SET cantidad_contada = excluded.cantidad_contada, contado_at = datetime('now','localtime')",
+            params![id_conteo, id_producto, cantidad_contada],
+        )?;
+        Ok(())
+    }
+
+    pub fn delete_detalle(&self, id_conteo: i64, id_producto: i64) -> rusqlite::Result<usize>
+    {
+        self.conn.execute(
+            "DELETE FROM conteos_detalle WHERE id_conteo = ?1 AND id_producto = ?2",
+            params![id_conteo, id_producto],
+        )
+    }
+
+    // Mientras el conteo está abierto se compara contra el stock y precio actuales
+    pub fn list_varianza(&self, id_conteo: i64) -> rusqlite::Result<Vec<VarianzaConteo>>
+    {
+        let mut stmt = self.conn.prepare(
+            "SELECT d.id_producto, COALESCE(p.nombre_producto, ''), \
+                    COALESCE(d.stock_sistema, p.stock, 0), d.cantidad_contada, \
+                    COALESCE(d.precio_consultora, p.precio_consultora, 0) \
+             FROM conteos_detalle d \
+             LEFT JOIN productos p ON p.id_producto = d.id_producto \
+             WHERE d.id_conteo = ?1 \
+             ORDER BY p.nombre_producto COLLATE NOCASE"
+        )?;
+
+        let rows = stmt.query_map(params![id_conteo], |row| {
+            let stock_sistema: i64 = row.get(2)?;
+            let cantidad_contada: i64 = row.get(3)?;
+            let precio_consultora: f64 = row.get(4)?;
+            let diferencia = cantidad_contada - stock_sistema;
+            Ok(VarianzaConteo {
+                id_producto: row.get(0)?,
+                nombre_producto: row.get(1)?,
+                stock_sistema,
+                cantidad_contada,
+                diferencia,
+                precio_consultora,
+                valor_diferencia: diferencia as f64 * precio_consultora,
+            })
+        })?;
+
+        let mut lineas = Vec::new();
+        for linea in rows
+        {
+            lineas.push(linea?);
+        }
+        Ok(lineas)
+    }
+
+    // Congela stock y precio del producto al momento del cierre
+    pub fn snapshot_detalle(&self, id_conteo: i64, id_producto: i64, stock_sistema: i64, precio_consultora: f64) -> rusqlite::Result<()>
+    {
+        self.conn.execute(
+            "UPDATE conteos_detalle SET stock_sistema = ?3, precio_consultora = ?4 WHERE id_conteo = ?1 AND id_producto = ?2",
+            params![id_conteo, id_producto, stock_sistema, precio_consultora],
+        )?;
+        Ok(())
+    }
+
+    pub fn cerrar(&self, id_conteo: i64) -> rusqlite::Result<()>
+    {
+        self.conn.execute(
+            "UPDATE conteos_inventario SET estado = 'Cerrado', cerrado_at = datetime('now','localtime') WHERE id_conteo = ?1",
+            params![id_conteo],
+        )?;
+        Ok(())
+    }
+}
@@ -0,0 +1,97 @@
+use rusqlite::{Connection, params};
+use crate::models::{GananciaProducto, GananciaVenta};
+
+// Parte del descuento de la venta que le toca a la línea `pv`, proporcional a su subtotal
+const DESCUENTO_VENTA_LINEA: &str = "COALESCE(v.descuento * pv.subtotal / NULLIF((
+        SELECT SUM(x.subtotal) FROM productos_vendidos x WHERE x.id_venta = pv.id_venta
+    ), 0), 0)";
+
+pub struct GananciaRepo<'a>
+{
+    pub conn: &'a Connection,
+}
+
+impl<'a> GananciaRepo<'a>
+{
+    // Ingreso y costo por venta dentro del periodo (fechas inclusivas), opcionalmente de una campaña
+    pub fn por_venta(&self, fecha_inicio: &str, fecha_fin: &str, id_campana: Option<i64>) -> rusqlite::Result<Vec<GananciaVenta>>
+    {
+        let mut stmt = self.conn.prepare(
+            "SELECT v.id_venta, v.fecha, TRIM(v.nombre_clienta || ' ' || COALESCE(v.apellido_clienta, '')), \
+                    COALESCE(SUM(pv.subtotal + pv.descuento), 0), \
+                    COALESCE(SUM(pv.descuento), 0) + v.descuento, \
+                    COALESCE(SUM(pv.subtotal), 0) - v.descuento, \
+                    COALESCE(SUM(pv.cantidad * pv.costo_unitario), 0) \
+             FROM ventas v \
+             LEFT JOIN productos_vendidos pv ON pv.id_venta = v.id_venta \
+             WHERE DATE(v.fecha) BETWEEN DATE(?1) AND DATE(?2) \
+               AND (?3 IS NULL OR v.id_campana = ?3) \
+             GROUP BY v.id_venta \
+             ORDER BY v.fecha DESC, v.id_venta DESC"
+        )?;
+
+        let rows = stmt.query_map(params![fecha_inicio, fecha_fin, id_campana], |row| {
+            let ingreso: f64 = row.get(5)?;
+            let costo: f64 = row.get(6)?;
+            Ok(GananciaVenta {
+                id_venta: row.get(0)?,
+                fecha: row.get(1)?,
+                nombre_cliente: row.get(2)?,
+                bruto: row.get(3)?,
+                descuento: row.get(4)?,
+                ingreso,
+                costo,
+                ganancia: ingreso - costo,
+            })
+        })?;
+
+        let mut ventas = Vec::new();
+        for venta in rows
+        {
+            ventas.push(venta?);
+        }
+        Ok(ventas)
+    }
+
+    // Ingreso y costo agrupado por producto dentro del periodo
+    pub fn por_producto(&self, fecha_inicio: &str, fecha_fin: &str, id_campana: Option<i64>) -> rusqlite::Result<Vec<GananciaProducto>>
+    {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT pv.id_producto, COALESCE(p.nombre_producto, MAX(pv.nombre_producto_snapshot)), \
+                    SUM(pv.cantidad), SUM(pv.subtotal + pv.descuento), \
+                    SUM(pv.descuento + {descuento_venta}), \
+                    SUM(pv.subtotal - {descuento_venta}), \
+                    SUM(pv.cantidad * pv.costo_unitario) \
+             FROM productos_vendidos pv \
+             INNER JOIN ventas v ON v.id_venta = pv.id_venta \
+             LEFT JOIN productos p ON p.id_producto = pv.id_producto \
+             WHERE DATE(v.fecha) BETWEEN DATE(?1) AND DATE(?2) \
+               AND (?3 IS NULL OR v.id_campana = ?3) \
+             GROUP BY pv.id_producto \
+             ORDER BY SUM(pv.subtotal - {descuento_venta}) - SUM(pv.cantidad * pv.costo_unitario) DESC",
+            descuento_venta = DESCUENTO_VENTA_LINEA
+        ))?;
+
+        let rows = stmt.query_map(params![fecha_inicio, fecha_fin, id_campana], |row| {
+            let ingreso: f64 = row.get(5)?;
+            let costo: f64 = row.get(6)?;
+            Ok(GananciaProducto {
+                id_producto: row.get(0)?,
+                nombre_producto: row.get(1)?,
+                unidades: row.get(2)?,
+                bruto: row.get(3)?,
+                descuento: row.get(4)?,
+                ingreso,
+                costo,
+                ganancia: ingreso - costo,
+            })
+        })?;
+
+        let mut productos = Vec::new();
+        for producto in rows
+        {
+            productos.push(producto?);
+        }
+        Ok(productos)
+    }
+}
@@ -0,0 +1,58 @@
+use rusqlite::{Connection, params};
+use crate::models::{CampoPrecio, HistorialPrecio, OrigenCambioPrecio};
+
+pub struct HistorialPrecioRepo<'a>
+{
+    pub conn: &'a Connection,
+}
+
+impl<'a> HistorialPrecioRepo<'a>
+{
+    pub fn create(
+        &self,
+        id_producto: i64,
+        campo: CampoPrecio,
+        precio_anterior: Option<f64>,
+        precio_nuevo: f64,
+        origen: OrigenCambioPrecio,
+    ) -> rusqlite::Result<i64>
+    {
+        self.conn.execute(
+            "INSERT INTO historial_precios (id_producto, campo, precio_anterior, precio_nuevo, origen) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![id_producto, campo.as_str(), precio_anterior, precio_nuevo, origen.as_str()],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    // Línea de tiempo del producto, del cambio más antiguo al más reciente
+    pub fn list_by_producto(&self, id_producto: i64) -> rusqlite::Result<Vec<HistorialPrecio>>
+    {
+        let mut stmt = self.conn.prepare(
+            "SELECT id_historial, id_producto, campo, precio_anterior, precio_nuevo, fecha, origen \
+             FROM historial_precios \
+             WHERE id_producto = ?1 \
+             ORDER BY fecha, id_historial"
+        )?;
+
+        let rows = stmt.query_map(params![id_producto], |row| {
+            let campo: String = row.get(2)?;
+            let origen: String = row.get(6)?;
+            Ok(HistorialPrecio {
+                id_historial: row.get(0)?,
+                id_producto: row.get(1)?,
+                campo: CampoPrecio::from_db(&campo),
+                precio_anterior: row.get(3)?,
+                precio_nuevo: row.get(4)?,
+                fecha: row.get(5)?,
+                origen: OrigenCambioPrecio::from_db(&origen),
+            })
+        })?;
+
+        let mut historial = Vec::new();
+        for registro in rows
+        {
+            historial.push(registro?);
+        }
+        Ok(historial)
+    }
+}
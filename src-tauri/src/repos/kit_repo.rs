@@ -0,0 +1,164 @@
+use rusqlite::{Connection, params};
+use crate::models::{ConsumoKit, KitComponente, KitComponenteInput, KitVendido};
+
+pub struct KitRepo<'a>
+{
+    pub conn: &'a Connection,
+}
+
+impl<'a> KitRepo<'a>
+{
+    pub fn list_componentes(&self, id_kit: i64) -> rusqlite::Result<Vec<KitComponente>>
+    {
+        let mut stmt = self.conn.prepare(
+            "SELECT kc.id_componente, p.nombre_producto, kc.cantidad, p.stock \
+             FROM kit_componentes kc \
+             INNER JOIN productos p ON p.id_producto = kc.id_componente \
+             WHERE kc.id_kit = ?1 \
+             ORDER BY p.nombre_producto COLLATE NOCASE"
+        )?;
+
+        let rows = stmt.query_map(params![id_kit], |row| {
+            Ok(KitComponente {
+                id_componente: row.get(0)?,
+                nombre_producto: row.get(1)?,
+                cantidad: row.get(2)?,
+                stock: row.get(3)?,
+            })
+        })?;
+
+        let mut componentes = Vec::new();
+        for componente in rows
+        {
+            componentes.push(componente?);
+        }
+        Ok(componentes)
+    }
+
+    pub fn replace_componentes(&self, id_kit: i64, componentes: &[KitComponenteInput]) -> rusqlite::Result<()>
+    {
+        self.conn.execute("DELETE FROM kit_componentes WHERE id_kit = ?1", params![id_kit])?;
+        for componente in componentes
+        {
+            self.conn.execute(
+                "INSERT INTO kit_componentes (id_kit, id_componente, cantidad) VALUES (?1, ?2, ?3)",
+                params![id_kit, componente.id_producto, componente.cantidad],
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn set_es_kit(&self, id_producto: i64, es_kit: bool) -> rusqlite::Result<()>
+    {
+        self.conn.execute(
+            "UPDATE productos SET es_kit = ?2, actualizado_at = datetime('now','localtime') WHERE id_producto = ?1",
+            params![id_producto, es_kit],
+        )?;
+        Ok(())
+    }
+
+    // Kits completos que se pueden armar con el stock actual de los componentes (0 si no tiene);
+    // es stock derivado, por eso no se registra en movimientos_inventario
+    pub fn recalcular_stock(&self, id_kit: i64) -> rusqlite::Result<()>
+    {
+        self.conn.execute(
+            "UPDATE productos
+             SET stock = COALESCE((
+                   SELECT MIN(c.stock / kc.cantidad)
+                   FROM kit_componentes kc
+                   INNER JOIN productos c ON c.id_producto = kc.id_componente
+                   WHERE kc.id_kit = ?1
+                 ), 0)
+             WHERE id_producto = ?1",
+            params![id_kit],
+        )?;
+        Ok(())
+    }
+
+    // Costo actual del kit como suma del costo promedio de sus componentes
+    pub fn costo_componentes(&self, id_kit: i64) -> rusqlite::Result<f64>
+    {
+        self.conn.query_row(
+            "SELECT COALESCE(SUM(kc.cantidad * p.costo_promedio), 0) \
+             FROM kit_componentes kc \
+             INNER JOIN productos p ON p.id_producto = kc.id_componente \
+             WHERE kc.id_kit = ?1",
+            params![id_kit],
+            |row| row.get(0),
+        )
+    }
+
+    pub fn count_ventas(&self, id_producto: i64) -> rusqlite::Result<i64>
+    {
+        self.conn.query_row(
+            "SELECT COUNT(*) FROM productos_vendidos WHERE id_producto = ?1",
+            params![id_producto],
+            |row| row.get(0),
+        )
+    }
+
+    // Kits vendidos dentro del periodo (fechas inclusivas)
+    pub fn kits_vendidos(&self, fecha_inicio: &str, fecha_fin: &str) -> rusqlite::Result<Vec<KitVendido>>
+    {
+        let mut stmt = self.conn.prepare(
+            "SELECT pv.id_producto, COALESCE(p.nombre_producto, MAX(pv.nombre_producto_snapshot)), \
+                    SUM(pv.cantidad), SUM(pv.subtotal) \
+             FROM productos_vendidos pv \
+             INNER JOIN ventas v ON v.id_venta = pv.id_venta \
+             INNER JOIN productos p ON p.id_producto = pv.id_producto \
+             WHERE p.es_kit = 1 AND DATE(v.fecha) BETWEEN DATE(?1) AND DATE(?2) \
+             GROUP BY pv.id_producto \
+             ORDER BY SUM(pv.subtotal) DESC"
+        )?;
+
+        let rows = stmt.query_map(params![fecha_inicio, fecha_fin], |row| {
+            Ok(KitVendido {
+                id_kit: row.get(0)?,
+                nombre_kit: row.get(1)?,
+                unidades: row.get(2)?,
+                ingreso: row.get(3)?,
+            })
+        })?;
+
+        let mut kits = Vec::new();
+        for kit in rows
+        {
+            kits.push(kit?);
+        }
+        Ok(kits)
+    }
+
+    // Unidades de cada componente que salieron dentro de kits en el periodo
+    pub fn consumos(&self, fecha_inicio: &str, fecha_fin: &str) -> rusqlite::Result<Vec<ConsumoKit>>
+    {
+        let mut stmt = self.conn.prepare(
+            "SELECT pv.id_producto, COALESCE(kit.nombre_producto, MAX(pv.nombre_producto_snapshot)), \
+                    kc.id_producto, c.nombre_producto, SUM(kc.cantidad) \
+             FROM kit_consumos kc \
+             INNER JOIN productos_vendidos pv ON pv.id_producto_vendido = kc.id_producto_vendido \
+             INNER JOIN ventas v ON v.id_venta = kc.id_venta \
+             INNER JOIN productos c ON c.id_producto = kc.id_producto \
+             LEFT JOIN productos kit ON kit.id_producto = pv.id_producto \
+             WHERE DATE(v.fecha) BETWEEN DATE(?1) AND DATE(?2) \
+             GROUP BY pv.id_producto, kc.id_producto \
+             ORDER BY 2 COLLATE NOCASE, c.nombre_producto COLLATE NOCASE"
+        )?;
+
+        let rows = stmt.query_map(params![fecha_inicio, fecha_fin], |row| {
+            Ok(ConsumoKit {
+                id_kit: row.get(0)?,
+                nombre_kit: row.get(1)?,
+                id_componente: row.get(2)?,
+                nombre_componente: row.get(3)?,
+                unidades: row.get(4)?,
+            })
+        })?;
+
+        let mut consumos = Vec::new();
+        for consumo in rows
+        {
+            consumos.push(consumo?);
+        }
+        Ok(consumos)
+    }
+}
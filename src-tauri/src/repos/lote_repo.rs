@@ -0,0 +1,115 @@
+use rusqlite::{Connection, OptionalExtension, Row, params};
+use crate::models::Lote;
+
+pub struct LoteRepo<'a>
+{
+    pub conn: &'a Connection,
+}
+
+const LOTE_SELECT: &str = "SELECT l.id_lote, l.id_producto, COALESCE(p.nombre_producto, ''), l.codigo_lote, \
+    l.fecha_caducidad, l.cantidad, \
+    CAST(julianday(l.fecha_caducidad) - julianday(DATE('now','localtime')) AS INTEGER), l.creado_at \
+    FROM lotes l \
+    LEFT JOIN productos p ON p.id_producto = l.id_producto";
+
+// Orden de consumo: primero el que caduca antes, los lotes sin caducidad al final
+const ORDEN_FEFO: &str = "l.fecha_caducidad IS NULL, l.fecha_caducidad, l.id_lote";
+
+impl<'a> LoteRepo<'a>
+{
+    fn map_row(row: &Row) -> rusqlite::Result<Lote>
+    {
+        Ok(Lote {
+            id_lote: row.get(0)?,
+            id_producto: row.get(1)?,
+            nombre_producto: row.get(2)?,
+            codigo_lote: row.get(3)?,
+            fecha_caducidad: row.get(4)?,
+            cantidad: row.get(5)?,
+            dias_para_vencer: row.get(6)?,
+            creado_at: row.get(7)?,
+        })
+    }
+
+    fn query(&self, filtro: &str, parametros: &[&dyn rusqlite::ToSql]) -> rusqlite::Result<Vec<Lote>>
+    {
+        let mut stmt = self.conn.prepare(&format!("{} {} ORDER BY {}", LOTE_SELECT, filtro, ORDEN_FEFO))?;
+        let rows = stmt.query_map(parametros, Self::map_row)?;
+
+        let mut lotes = Vec::new();
+        for lote in rows
+        {
+            lotes.push(lote?);
+        }
+        Ok(lotes)
+    }
+
+    pub fn list_by_producto(&self, id_producto: i64) -> rusqlite::Result<Vec<Lote>>
+    {
+        self.query("WHERE l.id_producto = ?1", &[&id_producto])
+    }
+
+    // Lotes con existencia en orden FEFO
+    pub fn list_disponibles(&self, id_producto: i64) -> rusqlite::Result<Vec<Lote>>
+    {
+        self.query("WHERE l.id_producto = ?1 AND l.cantidad > 0", &[&id_producto])
+    }
+
+    // Lotes con existencia que vencen en `dias` o menos (incluye los ya vencidos)
+    pub fn list_por_vencer(&self, dias: i64) -> rusqlite::Result<Vec<Lote>>
+    {
+        self.query(
+            "WHERE l.cantidad > 0 AND l.fecha_caducidad IS NOT NULL \
+             AND DATE(l.fecha_caducidad) <= DATE('now', 'localtime', '+' || ?1 || ' days')",
+            &[&dias],
+        )
+    }
+
+    pub fn list_vencidos(&self) -> rusqlite::Result<Vec<Lote>>
+    {
+        self.query(
+            "WHERE l.cantidad > 0 AND l.fecha_caducidad IS NOT NULL \
+             AND DATE(l.fecha_caducidad) < DATE('now', 'localtime')",
+            &[],
+        )
+    }
+
+    pub fn find_id(&self, id_producto: i64, codigo_lote: &str, fecha_caducidad: Option<&str>) -> rusqlite::Result<Option<i64>>
+    {
+        self.conn
+            .query_row(
+                "SELECT id_lote FROM lotes \
+                 WHERE id_producto = ?1 AND codigo_lote = ?2 AND IFNULL(fecha_caducidad, '') = IFNULL(?3, '')",
+                params![id_producto, codigo_lote, fecha_caducidad],
+                |row| row.get(0),
+            )
+            .optional()
+    }
+
+    pub fn create(&self, id_producto: i64, codigo_lote: &str, fecha_caducidad: Option<&str>, cantidad: i64) -> rusqlite::Result<i64>
+    {
+        self.conn.execute(
+            "INSERT INTO lotes (id_producto, codigo_lote, fecha_caducidad, cantidad) VALUES (?1, ?2, ?3, ?4)",
+            params![id_producto, codigo_lote, fecha_caducidad, cantidad],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    pub fn add_cantidad(&self, id_lote: i64, delta: i64) -> rusqlite::Result<()>
+    {
+        self.conn.execute(
+            "UPDATE lotes SET cantidad = cantidad + ?2 WHERE id_lote = ?1",
+            params![id_lote, delta],
+        )?;
+        Ok(())
+    }
+
+    pub fn total_por_producto(&self, id_producto: i64) -> rusqlite::Result<i64>
+    {
+        self.conn.query_row(
+            "SELECT COALESCE(SUM(cantidad), 0) FROM lotes WHERE id_producto = ?1",
+            params![id_producto],
+            |row| row.get(0),
+        )
+    }
+}
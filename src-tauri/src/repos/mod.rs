@@ -3,4 +3,17 @@ pub mod categoria_repo;
 pub mod venta_repo;
 pub mod abono_venta_repo;
 pub mod producto_vendido_repo;
-pub mod perfil_repo;
\ No newline at end of file
+pub mod perfil_repo;
+pub mod clienta_repo;
+pub mod movimiento_inventario_repo;
+pub mod proveedor_repo;
+pub mod compra_repo;
+pub mod pago_compra_repo;
+pub mod ganancia_repo;
+pub mod historial_precio_repo;
+pub mod lote_repo;
+pub mod conteo_repo;
+pub mod kit_repo;
+pub mod campana_repo;
+pub mod promocion_repo;
+pub mod consulta;
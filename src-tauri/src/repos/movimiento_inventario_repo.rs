@@ -0,0 +1,88 @@
+use rusqlite::{Connection, Row, params};
+use crate::models::{MotivoAjuste, MotivoMovimiento, MovimientoInventario};
+
+pub struct MovimientoInventarioRepo<'a>
+{
+    pub conn: &'a Connection,
+}
+
+const MOVIMIENTO_SELECT: &str = "SELECT m.id_movimiento, m.id_producto, COALESCE(p.nombre_producto, ''), m.delta, \
+    m.stock_resultante, m.motivo, m.id_referencia, m.nota, m.fecha, m.motivo_ajuste \
+    FROM movimientos_inventario m \
+    LEFT JOIN productos p ON p.id_producto = m.id_producto";
+
+impl<'a> MovimientoInventarioRepo<'a>
+{
+    fn map_row(row: &Row) -> rusqlite::Result<MovimientoInventario>
+    {
+        let motivo: String = row.get(5)?;
+        let motivo_ajuste: Option<String> = row.get(9)?;
+        Ok(MovimientoInventario {
+            id_movimiento: row.get(0)?,
+            id_producto: row.get(1)?,
+            nombre_producto: row.get(2)?,
+            delta: row.get(3)?,
+            stock_resultante: row.get(4)?,
+            motivo: MotivoMovimiento::from_db(&motivo),
+            motivo_ajuste: motivo_ajuste.as_deref().and_then(MotivoAjuste::from_db),
+            id_referencia: row.get(6)?,
+            nota: row.get(7)?,
+            fecha: row.get(8)?,
+        })
+    }
+
+    // Registra el movimiento tomando el stock actual del producto como stock resultante
+    pub fn create(
+        &self,
+        id_producto: i64,
+        delta: i64,
+        motivo: MotivoMovimiento,
+        motivo_ajuste: Option<MotivoAjuste>,
+        id_referencia: Option<i64>,
+        nota: &str,
+    ) -> rusqlite::Result<i64>
+    {
+        self.conn.execute(
+            "INSERT INTO movimientos_inventario (id_producto, delta, stock_resultante, motivo, motivo_ajuste, id_referencia, nota) \
+             SELECT id_producto, ?2, stock, ?3, ?4, ?5, ?6 FROM productos WHERE id_producto = ?1",
+            params![id_producto, delta, motivo.as_str(), motivo_ajuste.map(|m| m.as_str()), id_referencia, nota],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    pub fn list_by_producto(&self, id_producto: i64) -> rusqlite::Result<Vec<MovimientoInventario>>
+    {
+        let mut stmt = self.conn.prepare(&format!(
+            "{} WHERE m.id_producto = ?1 ORDER BY m.fecha DESC, m.id_movimiento DESC",
+            MOVIMIENTO_SELECT
+        ))?;
+
+        let rows = stmt.query_map(params![id_producto], Self::map_row)?;
+
+        let mut movimientos = Vec::new();
+        for movimiento in rows
+        {
+            movimientos.push(movimiento?);
+        }
+        Ok(movimientos)
+    }
+
+    // Sin kits: su stock es derivado y sus movimientos están en los de los componentes
+    pub fn list_between_dates(&self, desde: &str, hasta: &str) -> rusqlite::Result<Vec<MovimientoInventario>>
+    {
+        let mut stmt = self.conn.prepare(&format!(
+            "{} WHERE DATE(m.fecha) BETWEEN DATE(?1) AND DATE(?2) AND COALESCE(p.es_kit, 0) = 0 \
+             ORDER BY m.fecha DESC, m.id_movimiento DESC",
+            MOVIMIENTO_SELECT
+        ))?;
+
+        let rows = stmt.query_map(params![desde, hasta], Self::map_row)?;
+
+        let mut movimientos = Vec::new();
+        for movimiento in rows
+        {
+            movimientos.push(movimiento?);
+        }
+        Ok(movimientos)
+    }
+}
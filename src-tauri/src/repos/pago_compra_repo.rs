@@ -0,0 +1,44 @@
+use rusqlite::{Connection, params};
+use crate::models::PagoCompra;
+
+pub struct PagoCompraRepo<'a>
+{
+    pub conn: &'a Connection,
+}
+
+impl<'a> PagoCompraRepo<'a>
+{
+    pub fn create(&self, id_compra: i64, monto_pago: f64, fecha_pago: &str, observacion: &str) -> rusqlite::Result<i64>
+    {
+        self.conn.execute(
+            "INSERT INTO pagos_compra (id_compra, monto_pago, fecha_pago, observacion) VALUES (?1, ?2, ?3, ?4)",
+            params![id_compra, monto_pago, fecha_pago, observacion],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    pub fn list_by_compra(&self, id_compra: i64) -> rusqlite::Result<Vec<PagoCompra>>
+    {
+        let mut stmt = self.conn.prepare(
+            "SELECT id_pago, id_compra, monto_pago, fecha_pago, observacion, creado_at FROM pagos_compra WHERE id_compra = ?1 ORDER BY fecha_pago DESC, id_pago DESC"
+        )?;
+
+        let rows = stmt.query_map(params![id_compra], |row| {
+            Ok(PagoCompra {
+                id_pago: row.get(0)?,
+                id_compra: row.get(1)?,
+                monto_pago: row.get(2)?,
+                fecha_pago: row.get(3)?,
+                observacion: row.get(4)?,
+                creado_at: row.get(5)?,
+            })
+        })?;
+
+        let mut pagos = Vec::new();
+        for pago in rows
+        {
+            pagos.push(pago?);
+        }
+        Ok(pagos)
+    }
+}
@@ -1,4 +1,5 @@
 use rusqlite::{Connection, params};
+use crate::imagen;
 use crate::models::Perfil;
 
 pub struct PerfilRepo<'a> {
@@ -16,7 +17,7 @@ impl<'a> PerfilRepo<'a> {
                 id: row.get(0)?,
                 nombre: row.get(1)?,
                 cargo: row.get(2)?,
-                ruta_foto: row.get(3)?,
+                ruta_foto: imagen::resolver_ruta(self.conn, row.get(3)?),
                 miniatura_base64: row.get(4)?,
             })
         });
@@ -35,6 +36,7 @@ impl<'a> PerfilRepo<'a> {
         ruta_foto: Option<&str>,
         miniatura_base64: Option<&str>,
     ) -> rusqlite::Result<()> {
+        let ruta_foto = ruta_foto.map(imagen::ruta_relativa);
         self.conn.execute(
             "INSERT INTO perfil (id, nombre, cargo, ruta_foto, miniatura_base64)
              VALUES (1, ?1, ?2, ?3, ?4)
@@ -47,4 +49,12 @@ impl<'a> PerfilRepo<'a> {
         )?;
         Ok(())
     }
+
+    pub fn update_miniatura(&self, miniatura_base64: Option<&str>) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "UPDATE perfil SET miniatura_base64 = ?1 WHERE id = 1",
+            params![miniatura_base64],
+        )?;
+        Ok(())
+    }
 }
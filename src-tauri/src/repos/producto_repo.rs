@@ -1,5 +1,7 @@
-use rusqlite::{Connection, params};
-use crate::models::Producto;
+use rusqlite::{Connection, Row, params};
+use crate::imagen;
+use crate::models::{ConsultaLista, FiltrosBusqueda, Producto, ProductoBajoStock};
+use crate::repos::consulta::{self, FiltroSql};
 
 pub struct ProductoRepo<'a> {
     pub conn : &'a Connection,
@@ -7,26 +9,54 @@ pub struct ProductoRepo<'a> {
 
 //métodos CRUD (create, update, delete)
 
+const PRODUCTO_COLUMNS: &str = "id_producto, nombre_producto, id_categoria, ruta_imagen, miniatura_base64, stock, precio, \
+    precio_consultora, precio_publico, creado_at, actualizado_at, activo, costo_promedio, stock_minimo, \
+    id_producto_padre, tono, tamano, fragancia, precio_propio, sku, codigo_barras, es_kit, notas";
+
+//campos de orden de list_paginado
+pub const ORDEN_PRODUCTOS: &[(&str, &str)] = &[
+    ("nombre", "nombre_producto COLLATE NOCASE"),
+    ("stock", "stock"),
+    ("precio_publico", "precio_publico"),
+    ("precio_consultora", "precio_consultora"),
+    ("creado_at", "creado_at"),
+    ("actualizado_at", "actualizado_at"),
+];
+
 impl<'a> ProductoRepo<'a> {
+    //la ruta de la imagen se guarda relativa a `images/` y se entrega completa
+    fn map_row(&self, row: &Row) -> rusqlite::Result<Producto> {
+        Ok(Producto {
+            id_producto: row.get(0)?,
+            nombre_producto: row.get(1)?,
+            id_categoria: row.get(2)?,
+            ruta_imagen: imagen::resolver_ruta(self.conn, row.get(3)?),
+            miniatura_base64: row.get(4)?,
+            stock: row.get(5)?,
+            precio: row.get(6)?,
+            precio_consultora: row.get(7)?,
+            precio_publico: row.get(8)?,
+            creado_at: row.get(9)?,
+            actualizado_at: row.get(10)?,
+            activo: row.get(11)?,
+            costo_promedio: row.get(12)?,
+            stock_minimo: row.get(13)?,
+            id_producto_padre: row.get(14)?,
+            tono: row.get(15)?,
+            tamano: row.get(16)?,
+            fragancia: row.get(17)?,
+            precio_propio: row.get(18)?,
+            sku: row.get(19)?,
+            codigo_barras: row.get(20)?,
+            es_kit: row.get(21)?,
+            notas: row.get(22)?,
+        })
+    }
+
     pub fn list(&self) -> rusqlite::Result<Vec<Producto>> {
-        let mut stmt = self.conn.prepare("SELECT id_producto, nombre_producto, id_categoria, ruta_imagen, miniatura_base64, stock, precio, precio_consultora, precio_publico, creado_at, actualizado_at, activo FROM productos WHERE activo = 1")?;
+        let mut stmt = self.conn.prepare(&format!("SELECT {} FROM productos WHERE activo = 1", PRODUCTO_COLUMNS))?;
 
-        let rows = stmt.query_map([], |row| {
-            Ok(Producto {
-                id_producto: row.get(0)?,
-                nombre_producto: row.get(1)?,
-                id_categoria: row.get(2)?,
-                ruta_imagen: row.get(3)?,
-                miniatura_base64: row.get(4)?,
-                stock: row.get(5)?,
-                precio: row.get(6)?,
-                precio_consultora: row.get(7)?,
-                precio_publico: row.get(8)?,
-                creado_at: row.get(9)?,
-                actualizado_at: row.get(10)?,
-                activo: row.get(11)?,
-            })
-        })?;
+        let rows = stmt.query_map([], |row| self.map_row(row))?;
         let mut productos = Vec::new();
         for producto in rows {
             productos.push(producto?);
@@ -34,30 +64,90 @@ impl<'a> ProductoRepo<'a> {
         Ok(productos)
     }
 
+    //página de productos con el total que cumple los filtros; sin miniaturas salvo que se pidan
+    pub fn list_paginado(&self, consulta: &ConsultaLista) -> rusqlite::Result<(Vec<Producto>, i64)> {
+        let filtros = &consulta.filtros;
+        let mut filtro = FiltroSql::default();
+        if !filtros.incluir_descontinuados {
+            filtro.con_sql("activo = 1");
+        }
+        filtro.con(&format!("id_categoria IN ({})", consulta::CATEGORIA_Y_SUBCATEGORIAS), filtros.id_categoria);
+        filtro.con("stock >= ?", filtros.stock_desde);
+        filtro.con("stock <= ?", filtros.stock_hasta);
+
+        let total: i64 = self.conn.query_row(
+            &format!("SELECT COUNT(*) FROM productos {}", filtro.sql_where()),
+            filtro.parametros().as_slice(),
+            |row| row.get(0),
+        )?;
+
+        let columnas = if consulta.incluir_miniaturas {
+            PRODUCTO_COLUMNS.to_string()
+        } else {
+            PRODUCTO_COLUMNS.replacen("miniatura_base64", "NULL", 1)
+        };
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM productos {} {} {}",
+            columnas,
+            filtro.sql_where(),
+            consulta::orden_sql(consulta, ORDEN_PRODUCTOS, "nombre_producto COLLATE NOCASE, id_producto", "id_producto")?,
+            consulta::limite_sql(consulta),
+        ))?;
+
+        let rows = stmt.query_map(filtro.parametros().as_slice(), |row| self.map_row(row))?;
+        let mut productos = Vec::new();
+        for producto in rows {
+            productos.push(producto?);
+        }
+        Ok((productos, total))
+    }
+
     //get
     pub fn get(&self, id: i64) -> rusqlite::Result<Producto> {
         self.conn.query_row(
-            "SELECT id_producto, nombre_producto, id_categoria, ruta_imagen, miniatura_base64, stock, precio, precio_consultora, precio_publico, creado_at, actualizado_at, activo FROM productos WHERE id_producto = ?1",
+            &format!("SELECT {} FROM productos WHERE id_producto = ?1", PRODUCTO_COLUMNS),
             params![id],
-            |row| {
-                Ok(Producto {
-                    id_producto: row.get(0)?,
-                    nombre_producto: row.get(1)?,
-                    id_categoria: row.get(2)?,
-                    ruta_imagen: row.get(3)?,
-                    miniatura_base64: row.get(4)?,
-                    stock: row.get(5)?,
-                    precio: row.get(6)?,
-                    precio_consultora: row.get(7)?,
-                    precio_publico: row.get(8)?,
-                    creado_at: row.get(9)?,
-                    actualizado_at: row.get(10)?,
-                    activo: row.get(11)?,
-                })
-            },
+            |row| self.map_row(row),
         )
     }
 
+    //productos activos de la categoría y, si se pide, de todas sus subcategorías
+    pub fn list_por_categoria(&self, id_categoria: i64, incluir_subcategorias: bool) -> rusqlite::Result<Vec<Producto>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "WITH RECURSIVE arbol(id_categoria) AS ( \
+                SELECT ?1 \
+                UNION \
+                SELECT c.id_categoria FROM categorias c INNER JOIN arbol a ON c.id_categoria_padre = a.id_categoria WHERE ?2 \
+             ) \
+             SELECT {} FROM productos \
+             WHERE activo = 1 AND id_categoria IN (SELECT id_categoria FROM arbol) \
+             ORDER BY nombre_producto COLLATE NOCASE",
+            PRODUCTO_COLUMNS
+        ))?;
+
+        let rows = stmt.query_map(params![id_categoria, incluir_subcategorias], |row| self.map_row(row))?;
+        let mut productos = Vec::new();
+        for producto in rows {
+            productos.push(producto?);
+        }
+        Ok(productos)
+    }
+
+    //variantes activas de un producto
+    pub fn list_variantes(&self, id_producto_padre: i64) -> rusqlite::Result<Vec<Producto>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM productos WHERE activo = 1 AND id_producto_padre = ?1 ORDER BY nombre_producto COLLATE NOCASE",
+            PRODUCTO_COLUMNS
+        ))?;
+
+        let rows = stmt.query_map(params![id_producto_padre], |row| self.map_row(row))?;
+        let mut productos = Vec::new();
+        for producto in rows {
+            productos.push(producto?);
+        }
+        Ok(productos)
+    }
+
     //create legacy-compatible (same value for all price fields)
     pub fn create(&self, nombre_producto: &str, id_categoria: Option<i64>, ruta_imagen: Option<&str>, miniatura_base64: Option<&str>, stock: i64, precio: f64) -> rusqlite::Result<i64> {
         self.create_with_prices(
@@ -83,8 +173,9 @@ impl<'a> ProductoRepo<'a> {
         precio_consultora: f64,
         precio_publico: f64,
     ) -> rusqlite::Result<i64> {
+        let ruta_imagen = ruta_imagen.map(imagen::ruta_relativa);
         self.conn.execute(
-            "INSERT INTO productos (nombre_producto, id_categoria, ruta_imagen, miniatura_base64, stock, precio, precio_consultora, precio_publico, creado_at, actualizado_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, datetime('now'), datetime('now'))",
+            "INSERT INTO productos (nombre_producto, id_categoria, ruta_imagen, miniatura_base64, stock, precio, precio_consultora, precio_publico, costo_promedio, creado_at, actualizado_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?7, datetime('now'), datetime('now'))",
             params![
                 nombre_producto,
                 id_categoria,
@@ -99,39 +190,328 @@ impl<'a> ProductoRepo<'a> {
         Ok(self.conn.last_insert_rowid())
     }
 
-    //update
+    //update (el stock solo cambia con ventas y ajustes, nunca desde la edición)
     pub fn update(&self, producto: &Producto) -> rusqlite::Result<()> {
+        let ruta_imagen = producto.ruta_imagen.as_deref().map(imagen::ruta_relativa);
         self.conn.execute(
             "UPDATE productos
              SET nombre_producto = ?1,
                  id_categoria = ?2,
                  ruta_imagen = ?3,
                  miniatura_base64 = ?4,
-                 stock = ?5,
-                 precio = ?6,
-                 precio_consultora = CASE WHEN ?7 > 0 THEN ?7 ELSE ?6 END,
-                 precio_publico = CASE WHEN ?8 > 0 THEN ?8 ELSE ?6 END,
+                 precio = ?5,
+                 precio_consultora = CASE WHEN ?6 > 0 THEN ?6 ELSE ?5 END,
+                 precio_publico = CASE WHEN ?7 > 0 THEN ?7 ELSE ?5 END,
+                 sku = ?9,
+                 codigo_barras = ?10,
                  actualizado_at = datetime('now')
-             WHERE id_producto = ?9",
+             WHERE id_producto = ?8",
             params![
                 producto.nombre_producto,
                 producto.id_categoria,
-                producto.ruta_imagen.as_deref(),
+                ruta_imagen,
                 producto.miniatura_base64.as_deref(),
-                producto.stock,
                 producto.precio,
                 producto.precio_consultora,
                 producto.precio_publico,
                 producto.id_producto,
+                producto.sku.as_deref(),
+                producto.codigo_barras.as_deref(),
             ],
         )?;
         Ok(())
     }
 
+    pub fn update_codigos(&self, id: i64, sku: Option<&str>, codigo_barras: Option<&str>) -> rusqlite::Result<usize> {
+        self.conn.execute(
+            "UPDATE productos SET sku = ?2, codigo_barras = ?3, actualizado_at = datetime('now') WHERE id_producto = ?1",
+            params![id, sku, codigo_barras],
+        )
+    }
+
+    //producto (activo o no) que ya usa el sku, sin contar `excluir`
+    pub fn find_by_sku(&self, sku: &str, excluir: Option<i64>) -> rusqlite::Result<Option<Producto>> {
+        self.find_one(
+            &format!(
+                "SELECT {} FROM productos WHERE sku = ?1 COLLATE NOCASE AND id_producto IS NOT ?2",
+                PRODUCTO_COLUMNS
+            ),
+            params![sku, excluir],
+        )
+    }
+
+    pub fn find_by_codigo_barras(&self, codigo_barras: &str, excluir: Option<i64>) -> rusqlite::Result<Option<Producto>> {
+        self.find_one(
+            &format!(
+                "SELECT {} FROM productos WHERE codigo_barras = ?1 AND id_producto IS NOT ?2",
+                PRODUCTO_COLUMNS
+            ),
+            params![codigo_barras, excluir],
+        )
+    }
+
+    //producto con el mismo nombre sin importar mayúsculas ni espacios; primero los activos
+    pub fn find_by_nombre(&self, nombre: &str) -> rusqlite::Result<Option<Producto>> {
+        self.find_one(
+            &format!(
+                "SELECT {} FROM productos WHERE TRIM(nombre_producto) = TRIM(?1) COLLATE NOCASE \
+                 ORDER BY activo DESC, id_producto LIMIT 1",
+                PRODUCTO_COLUMNS
+            ),
+            params![nombre],
+        )
+    }
+
+    //producto activo cuyo código de barras o sku coincide con lo escaneado
+    pub fn find_activo_por_codigo(&self, codigos: &[String]) -> rusqlite::Result<Option<Producto>> {
+        for codigo in codigos {
+            let encontrado = self.find_one(
+                &format!(
+                    "SELECT {} FROM productos \
+                     WHERE activo = 1 AND (codigo_barras = ?1 OR sku = ?1 COLLATE NOCASE) \
+                     ORDER BY codigo_barras = ?1 DESC \
+                     LIMIT 1",
+                    PRODUCTO_COLUMNS
+                ),
+                params![codigo],
+            )?;
+            if encontrado.is_some() {
+                return Ok(encontrado);
+            }
+        }
+        Ok(None)
+    }
+
+    fn find_one(&self, sql: &str, params: &[&dyn rusqlite::ToSql]) -> rusqlite::Result<Option<Producto>> {
+        match self.conn.query_row(sql, params, |row| self.map_row(row)) {
+            Ok(producto) => Ok(Some(producto)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn update_notas(&self, id: i64, notas: Option<&str>) -> rusqlite::Result<usize> {
+        self.conn.execute(
+            "UPDATE productos SET notas = ?2, actualizado_at = datetime('now') WHERE id_producto = ?1",
+            params![id, notas],
+        )
+    }
+
+    //búsqueda de texto completo en `productos_fts`; `consulta` ya viene en sintaxis FTS5.
+    //el nombre pesa más que los códigos, la categoría y las notas
+    pub fn buscar(&self, consulta: &str, filtros: &FiltrosBusqueda, limite: i64) -> rusqlite::Result<Vec<Producto>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "WITH RECURSIVE arbol(id_categoria) AS ( \
+                SELECT ?4 WHERE ?4 IS NOT NULL \
+                UNION \
+                SELECT c.id_categoria FROM categorias c INNER JOIN arbol a ON c.id_categoria_padre = a.id_categoria \
+             ), \
+             coincidencias(id, rango) AS ( \
+                SELECT rowid, bm25(productos_fts, 10.0, 4.0, 2.0, 6.0, 1.0) FROM productos_fts WHERE productos_fts MATCH ?1 \
+             ) \
+             SELECT {} FROM productos \
+             INNER JOIN coincidencias ON coincidencias.id = productos.id_producto \
+             WHERE (?2 OR activo = 1) \
+               AND (NOT ?3 OR stock > 0) \
+               AND (?4 IS NULL OR id_categoria IN (SELECT id_categoria FROM arbol)) \
+             ORDER BY coincidencias.rango, activo DESC, nombre_producto COLLATE NOCASE \
+             LIMIT ?5",
+            PRODUCTO_COLUMNS
+        ))?;
+
+        let rows = stmt.query_map(
+            params![consulta, filtros.incluir_descontinuados, filtros.solo_con_stock, filtros.id_categoria, limite],
+            |row| self.map_row(row),
+        )?;
+        let mut productos = Vec::new();
+        for producto in rows {
+            productos.push(producto?);
+        }
+        Ok(productos)
+    }
+
+    //cambio relativo de stock; devuelve 0 filas si el resultado quedaría negativo
+    pub fn adjust_stock(&self, id: i64, delta: i64) -> rusqlite::Result<usize> {
+        self.conn.execute(
+            "UPDATE productos
+             SET stock = stock + ?2,
+                 actualizado_at = datetime('now','localtime')
+             WHERE id_producto = ?1 AND stock + ?2 >= 0",
+            params![id, delta],
+        )
+    }
+
+    //(id, nombre, ruta) de todos los productos con imagen, incluidos los descontinuados
+    pub fn list_con_imagen(&self) -> rusqlite::Result<Vec<(i64, String, String)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id_producto, nombre_producto, ruta_imagen FROM productos \
+             WHERE ruta_imagen IS NOT NULL AND TRIM(ruta_imagen) <> '' ORDER BY id_producto",
+        )?;
+
+        let rows = stmt.query_map([], |row| {
+            let ruta = imagen::resolver_ruta(self.conn, row.get(2)?).unwrap_or_default();
+            Ok((row.get(0)?, row.get(1)?, ruta))
+        })?;
+        let mut productos = Vec::new();
+        for producto in rows {
+            productos.push(producto?);
+        }
+        Ok(productos)
+    }
+
+    pub fn update_miniatura(&self, id: i64, miniatura_base64: Option<&str>) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "UPDATE productos SET miniatura_base64 = ?2 WHERE id_producto = ?1",
+            params![id, miniatura_base64],
+        )?;
+        Ok(())
+    }
+
+    //costo promedio ponderado (lo recalcula la recepción de compras)
+    pub fn update_costo_promedio(&self, id: i64, costo_promedio: f64) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "UPDATE productos SET costo_promedio = ?2 WHERE id_producto = ?1",
+            params![id, costo_promedio],
+        )?;
+        Ok(())
+    }
+
+    //stock mínimo propio del producto (None vuelve al de la categoría)
+    pub fn update_stock_minimo(&self, id: i64, stock_minimo: Option<i64>) -> rusqlite::Result<usize> {
+        self.conn.execute(
+            "UPDATE productos SET stock_minimo = ?2 WHERE id_producto = ?1",
+            params![id, stock_minimo],
+        )
+    }
+
+    //productos activos con stock <= umbral efectivo y unidades vendidas en los últimos `dias`
+    //(la velocidad de venta la completa el servicio); sin kits, que se reponen por sus componentes
+    pub fn list_bajo_stock(&self, dias: i64) -> rusqlite::Result<Vec<ProductoBajoStock>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT p.id_producto, p.nombre_producto, p.id_categoria, c.nombre, p.stock, \
+                    COALESCE(p.stock_minimo, c.stock_minimo_default, 0) AS umbral, \
+                    COALESCE(( \
+                        SELECT SUM(pv.cantidad) \
+                        FROM productos_vendidos pv \
+                        INNER JOIN ventas v ON v.id_venta = pv.id_venta \
+                        WHERE pv.id_producto = p.id_producto \
+                          AND DATE(v.fecha) > DATE('now', 'localtime', '-' || ?1 || ' days') \
+                    ), 0) AS vendidas \
+             FROM productos p \
+             LEFT JOIN categorias c ON c.id_categoria = p.id_categoria \
+             WHERE p.activo = 1 AND p.es_kit = 0 AND p.stock <= COALESCE(p.stock_minimo, c.stock_minimo_default, 0) \
+             ORDER BY p.stock - umbral, vendidas DESC, p.nombre_producto"
+        )?;
+
+        let rows = stmt.query_map(params![dias], |row| {
+            Ok(ProductoBajoStock {
+                id_producto: row.get(0)?,
+                nombre_producto: row.get(1)?,
+                id_categoria: row.get(2)?,
+                nombre_categoria: row.get(3)?,
+                stock: row.get(4)?,
+                stock_minimo: row.get(5)?,
+                unidades_vendidas: row.get(6)?,
+                dias_periodo: dias,
+                venta_promedio_diaria: 0.0,
+                dias_cobertura: None,
+            })
+        })?;
+        let mut productos = Vec::new();
+        for producto in rows {
+            productos.push(producto?);
+        }
+        Ok(productos)
+    }
+
+    //marca la fila como variante del producto padre con sus atributos
+    pub fn update_variante(
+        &self,
+        id: i64,
+        id_producto_padre: i64,
+        tono: Option<&str>,
+        tamano: Option<&str>,
+        fragancia: Option<&str>,
+        precio_propio: bool,
+    ) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "UPDATE productos
+             SET id_producto_padre = ?2, tono = ?3, tamano = ?4, fragancia = ?5, precio_propio = ?6
+             WHERE id_producto = ?1",
+            params![id, id_producto_padre, tono, tamano, fragancia, precio_propio],
+        )?;
+        Ok(())
+    }
+
+    pub fn update_precio_propio(&self, id: i64, precio_propio: bool) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "UPDATE productos SET precio_propio = ?2 WHERE id_producto = ?1",
+            params![id, precio_propio],
+        )?;
+        Ok(())
+    }
+
     //soft delete (marca como producto descontinuado)
     pub fn delete(&self, id: i64) -> rusqlite::Result<()> {
         self.conn.execute("UPDATE productos SET activo = 0, actualizado_at = datetime('now','localtime') WHERE id_producto = ?1", params![id])?;
         Ok(())
     }
 
+    //productos descontinuados, los más recientes primero
+    pub fn list_descontinuados(&self) -> rusqlite::Result<Vec<Producto>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM productos WHERE activo = 0 ORDER BY actualizado_at DESC, nombre_producto COLLATE NOCASE",
+            PRODUCTO_COLUMNS
+        ))?;
+
+        let rows = stmt.query_map([], |row| self.map_row(row))?;
+        let mut productos = Vec::new();
+        for producto in rows {
+            productos.push(producto?);
+        }
+        Ok(productos)
+    }
+
+    pub fn reactivar(&self, id: i64) -> rusqlite::Result<usize> {
+        self.conn.execute(
+            "UPDATE productos SET activo = 1, actualizado_at = datetime('now','localtime') WHERE id_producto = ?1",
+            params![id],
+        )
+    }
+
+    //registros que impiden borrar el producto por completo: (ventas, compras, kits, variantes, conteos, promociones)
+    //las ventas incluyen los consumos como componente de un kit vendido
+    pub fn count_referencias(&self, id: i64) -> rusqlite::Result<(i64, i64, i64, i64, i64, i64)> {
+        self.conn.query_row(
+            "SELECT
+                (SELECT COUNT(*) FROM productos_vendidos WHERE id_producto = ?1)
+                  + (SELECT COUNT(*) FROM kit_consumos WHERE id_producto = ?1),
+                (SELECT COUNT(*) FROM compras_detalle WHERE id_producto = ?1),
+                (SELECT COUNT(*) FROM kit_componentes WHERE id_componente = ?1),
+                (SELECT COUNT(*) FROM productos WHERE id_producto_padre = ?1),
+                (SELECT COUNT(*) FROM conteos_detalle WHERE id_producto = ?1),
+                (SELECT COUNT(*) FROM promociones WHERE id_producto = ?1)",
+            params![id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?)),
+        )
+    }
+
+    //historial que se pierde con el borrado definitivo: (movimientos, historial de precios, lotes, precios de campaña)
+    pub fn count_historial(&self, id: i64) -> rusqlite::Result<(i64, i64, i64, i64)> {
+        self.conn.query_row(
+            "SELECT
+                (SELECT COUNT(*) FROM movimientos_inventario WHERE id_producto = ?1),
+                (SELECT COUNT(*) FROM historial_precios WHERE id_producto = ?1),
+                (SELECT COUNT(*) FROM lotes WHERE id_producto = ?1),
+                (SELECT COUNT(*) FROM precios_campana WHERE id_producto = ?1)",
+            params![id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+    }
+
+    //hard delete; movimientos_inventario, historial_precios, lotes y precios_campana se borran en cascada
+    pub fn delete_definitivo(&self, id: i64) -> rusqlite::Result<usize> {
+        self.conn.execute("DELETE FROM productos WHERE id_producto = ?1", params![id])
+    }
+
 }
@@ -1,5 +1,25 @@
 use rusqlite::{Connection, params};
-use crate::models::ProductoVendido;
+use crate::models::{ConsultaLista, LineaCalculada, ProductoVendido, TipoDescuento, TopProducto};
+use crate::repos::consulta::{self, FiltroSql};
+
+// Costo vigente de ?2: el costo promedio, o para un kit la suma del de sus componentes
+const COSTO_VIGENTE: &str = "COALESCE((
+        SELECT CASE WHEN p.es_kit = 1 THEN (
+                 SELECT SUM(kc.cantidad * c.costo_promedio)
+                 FROM kit_componentes kc INNER JOIN productos c ON c.id_producto = kc.id_componente
+                 WHERE kc.id_kit = p.id_producto
+               ) ELSE p.costo_promedio END
+        FROM productos p WHERE p.id_producto = ?2
+    ), 0.0)";
+
+// Campos de orden del listado paginado de productos vendidos
+pub const ORDEN_PRODUCTOS_VENDIDOS: &[(&str, &str)] = &[
+    ("fecha", "v.fecha"),
+    ("cantidad", "pv.cantidad"),
+    ("subtotal", "pv.subtotal"),
+    ("nombre", "pv.nombre_producto_snapshot COLLATE NOCASE"),
+    ("id_venta", "pv.id_venta"),
+];
 
 pub struct ProductoVendidoRepo<'a> 
 {
@@ -13,7 +33,7 @@ impl<'a> ProductoVendidoRepo<'a>
     pub fn list(&self) -> rusqlite::Result<Vec<ProductoVendido>> 
     {
         let mut stmt = self.conn.prepare(
-            "SELECT id_producto_vendido, id_venta, id_producto, nombre_producto_snapshot, cantidad, precio_unitario, subtotal FROM productos_vendidos"
+            "SELECT id_producto_vendido, id_venta, id_producto, nombre_producto_snapshot, cantidad, precio_unitario, subtotal, costo_unitario, descuento, id_promocion FROM productos_vendidos"
         )?;
 
         let rows = stmt.query_map([], |row| {
@@ -25,6 +45,9 @@ impl<'a> ProductoVendidoRepo<'a>
                 cantidad: row.get(4)?,
                 precio_unitario: row.get(5)?,
                 subtotal: row.get(6)?,
+                costo_unitario: row.get(7)?,
+                descuento: row.get(8)?,
+                id_promocion: row.get(9)?,
             })
         })?;
 
@@ -35,11 +58,67 @@ impl<'a> ProductoVendidoRepo<'a>
         Ok(productos_vendidos)
     }
 
+    // Página de productos vendidos filtrada por venta, producto, fechas, clienta, campaña o categoría
+    pub fn list_paginado(&self, consulta: &ConsultaLista) -> rusqlite::Result<(Vec<ProductoVendido>, i64)>
+    {
+        let filtros = &consulta.filtros;
+        let mut filtro = FiltroSql::default();
+        filtro.con("pv.id_venta = ?", filtros.id_venta);
+        filtro.con("pv.id_producto = ?", filtros.id_producto);
+        filtro.con("DATE(v.fecha) >= DATE(?)", filtros.fecha_desde.clone());
+        filtro.con("DATE(v.fecha) <= DATE(?)", filtros.fecha_hasta.clone());
+        filtro.con("v.id_clienta = ?", filtros.id_clienta);
+        filtro.con("v.id_campana = ?", filtros.id_campana);
+        filtro.con(
+            &format!("p.id_categoria IN ({})", consulta::CATEGORIA_Y_SUBCATEGORIAS),
+            filtros.id_categoria,
+        );
+
+        let from = "FROM productos_vendidos pv \
+                    INNER JOIN ventas v ON v.id_venta = pv.id_venta \
+                    LEFT JOIN productos p ON p.id_producto = pv.id_producto";
+        let total: i64 = self.conn.query_row(
+            &format!("SELECT COUNT(*) {} {}", from, filtro.sql_where()),
+            filtro.parametros().as_slice(),
+            |row| row.get(0),
+        )?;
+
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT pv.id_producto_vendido, pv.id_venta, pv.id_producto, pv.nombre_producto_snapshot, pv.cantidad, \
+                    pv.precio_unitario, pv.subtotal, pv.costo_unitario, pv.descuento, pv.id_promocion \
+             {} {} {} {}",
+            from,
+            filtro.sql_where(),
+            consulta::orden_sql(consulta, ORDEN_PRODUCTOS_VENDIDOS, "v.fecha DESC, pv.id_producto_vendido DESC", "pv.id_producto_vendido")?,
+            consulta::limite_sql(consulta),
+        ))?;
+        let rows = stmt.query_map(filtro.parametros().as_slice(), |row| {
+            Ok(ProductoVendido {
+                id_producto_vendido: row.get(0)?,
+                id_venta: row.get(1)?,
+                id_producto: row.get(2)?,
+                nombre_producto_snapshot: row.get(3)?,
+                cantidad: row.get(4)?,
+                precio_unitario: row.get(5)?,
+                subtotal: row.get(6)?,
+                costo_unitario: row.get(7)?,
+                descuento: row.get(8)?,
+                id_promocion: row.get(9)?,
+            })
+        })?;
+
+        let mut productos_vendidos = Vec::new();
+        for producto_vendido in rows {
+            productos_vendidos.push(producto_vendido?);
+        }
+        Ok((productos_vendidos, total))
+    }
+
     // Obtener un producto vendido por ID
     pub fn get(&self, id: i64) -> rusqlite::Result<ProductoVendido> 
     {
         self.conn.query_row(
-            "SELECT id_producto_vendido, id_venta, id_producto, nombre_producto_snapshot, cantidad, precio_unitario, subtotal FROM productos_vendidos WHERE id_producto_vendido = ?1",
+            "SELECT id_producto_vendido, id_venta, id_producto, nombre_producto_snapshot, cantidad, precio_unitario, subtotal, costo_unitario, descuento, id_promocion FROM productos_vendidos WHERE id_producto_vendido = ?1",
             params![id],
             |row| {
                 Ok(ProductoVendido {
@@ -50,6 +129,9 @@ impl<'a> ProductoVendidoRepo<'a>
                     cantidad: row.get(4)?,
                     precio_unitario: row.get(5)?,
                     subtotal: row.get(6)?,
+                    costo_unitario: row.get(7)?,
+                    descuento: row.get(8)?,
+                    id_promocion: row.get(9)?,
                 })
             },
         )
@@ -59,7 +141,7 @@ impl<'a> ProductoVendidoRepo<'a>
     pub fn get_by_venta(&self, id_venta: i64) -> rusqlite::Result<Vec<ProductoVendido>> 
     {
         let mut stmt = self.conn.prepare(
-            "SELECT id_producto_vendido, id_venta, id_producto, nombre_producto_snapshot, cantidad, precio_unitario, subtotal FROM productos_vendidos WHERE id_venta = ?1"
+            "SELECT id_producto_vendido, id_venta, id_producto, nombre_producto_snapshot, cantidad, precio_unitario, subtotal, costo_unitario, descuento, id_promocion FROM productos_vendidos WHERE id_venta = ?1"
         )?;
 
         let rows = stmt.query_map(params![id_venta], |row| {
@@ -71,6 +153,9 @@ impl<'a> ProductoVendidoRepo<'a>
                 cantidad: row.get(4)?,
                 precio_unitario: row.get(5)?,
                 subtotal: row.get(6)?,
+                costo_unitario: row.get(7)?,
+                descuento: row.get(8)?,
+                id_promocion: row.get(9)?,
             })
         })?;
 
@@ -81,21 +166,59 @@ impl<'a> ProductoVendidoRepo<'a>
         Ok(productos_vendidos)
     }
 
-    // Crear un producto vendido
+    // Crear un producto vendido (congela el costo promedio vigente del producto)
     pub fn create(&self, id_venta: i64, id_producto: i64, nombre_producto_snapshot: &str, cantidad: i64, precio_unitario: f64, subtotal: f64) -> rusqlite::Result<i64> 
     {
         self.conn.execute(
-            "INSERT INTO productos_vendidos (id_venta, id_producto, nombre_producto_snapshot, cantidad, precio_unitario, subtotal) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            &format!(
+                "INSERT INTO productos_vendidos (id_venta, id_producto, nombre_producto_snapshot, cantidad, precio_unitario, subtotal, costo_unitario)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, {})",
+                COSTO_VIGENTE
+            ),
             params![id_venta, id_producto, nombre_producto_snapshot, cantidad, precio_unitario, subtotal],
         )?;
         Ok(self.conn.last_insert_rowid())
     }
 
+    // Guarda el desglose de descuentos de la línea (el subtotal ya se insertó neto)
+    pub fn set_descuento(&self, id_producto_vendido: i64, linea: &LineaCalculada) -> rusqlite::Result<()>
+    {
+        let tipo = linea.descuento_tipo.map(|tipo| match tipo {
+            TipoDescuento::Porcentaje => "Porcentaje",
+            TipoDescuento::Monto => "Monto",
+        });
+        self.conn.execute(
+            "UPDATE productos_vendidos
+             SET descuento_tipo = ?2, descuento_valor = ?3, descuento = ?4, descuento_promocion = ?5, id_promocion = ?6
+             WHERE id_producto_vendido = ?1",
+            params![
+                id_producto_vendido,
+                tipo,
+                linea.descuento_valor,
+                linea.descuento,
+                linea.descuento_promocion,
+                linea.id_promocion,
+            ],
+        )?;
+        Ok(())
+    }
+
     // Actualizar un producto vendido
     pub fn update(&self, producto_vendido: &ProductoVendido) -> rusqlite::Result<()> 
     {
         self.conn.execute(
-            "UPDATE productos_vendidos SET id_venta = ?1, id_producto = ?2, nombre_producto_snapshot = ?3, cantidad = ?4, precio_unitario = ?5, subtotal = ?6 WHERE id_producto_vendido = ?7",
+            &format!(
+                "UPDATE productos_vendidos
+                 SET id_venta = ?1,
+                     costo_unitario = CASE WHEN id_producto = ?2 THEN costo_unitario ELSE {} END,
+                     id_producto = ?2,
+                     nombre_producto_snapshot = ?3,
+                     cantidad = ?4,
+                     precio_unitario = ?5,
+                     subtotal = ?6
+                 WHERE id_producto_vendido = ?7",
+                COSTO_VIGENTE
+            ),
             params![
                 producto_vendido.id_venta,
                 producto_vendido.id_producto,
@@ -122,4 +245,44 @@ impl<'a> ProductoVendidoRepo<'a>
         self.conn.execute("DELETE FROM productos_vendidos WHERE id_venta = ?1", params![id_venta])?;
         Ok(())
     }
+
+    // Unidades e ingreso por producto; con `agrupar_por_padre` las variantes suman en su producto padre
+    pub fn resumen_por_producto(&self, agrupar_por_padre: bool, limite: Option<i64>) -> rusqlite::Result<Vec<TopProducto>> 
+    {
+        let sql = if agrupar_por_padre {
+            "SELECT COALESCE(padre.nombre_producto, p.nombre_producto, MAX(pv.nombre_producto_snapshot)), \
+                    SUM(pv.cantidad) AS total_unidades, \
+                    SUM(pv.subtotal) AS total_ingreso \
+             FROM productos_vendidos pv \
+             LEFT JOIN productos p ON p.id_producto = pv.id_producto \
+             LEFT JOIN productos padre ON padre.id_producto = p.id_producto_padre \
+             GROUP BY COALESCE(p.id_producto_padre, pv.id_producto) \
+             ORDER BY total_ingreso DESC \
+             LIMIT ?1"
+        } else {
+            "SELECT nombre_producto_snapshot, \
+                    SUM(cantidad) AS total_unidades, \
+                    SUM(subtotal) AS total_ingreso \
+             FROM productos_vendidos \
+             GROUP BY nombre_producto_snapshot \
+             ORDER BY total_ingreso DESC \
+             LIMIT ?1"
+        };
+
+        let mut stmt = self.conn.prepare(sql)?;
+        // LIMIT -1 equivale a sin límite en SQLite
+        let rows = stmt.query_map(params![limite.unwrap_or(-1)], |row| {
+            Ok(TopProducto {
+                nombre: row.get(0)?,
+                unidades: row.get(1)?,
+                ingreso: row.get(2)?,
+            })
+        })?;
+
+        let mut resumen = Vec::new();
+        for producto in rows {
+            resumen.push(producto?);
+        }
+        Ok(resumen)
+    }
 }
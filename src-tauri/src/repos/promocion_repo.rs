@@ -0,0 +1,157 @@
+use rusqlite::{Connection, Row, params};
+use crate::models::{Promocion, PromocionInput, TipoPromocion};
+
+pub struct PromocionRepo<'a>
+{
+    pub conn: &'a Connection,
+}
+
+const PROMOCION_SELECT: &str =
+    "SELECT id_promocion, nombre, tipo, id_producto, id_categoria, cantidad_lleva, cantidad_paga, \
+            precio_paquete, porcentaje, fecha_inicio, fecha_fin, activa, creado_at \
+     FROM promociones";
+
+impl<'a> PromocionRepo<'a>
+{
+    fn map_row(row: &Row) -> rusqlite::Result<Promocion>
+    {
+        let tipo_str: String = row.get(2)?;
+        let tipo = match tipo_str.as_str() {
+            "NxM" => TipoPromocion::NxM,
+            "PrecioPaquete" => TipoPromocion::PrecioPaquete,
+            _ => TipoPromocion::Porcentaje,
+        };
+        let activa: i64 = row.get(11)?;
+        Ok(Promocion {
+            id_promocion: row.get(0)?,
+            nombre: row.get(1)?,
+            tipo,
+            id_producto: row.get(3)?,
+            id_categoria: row.get(4)?,
+            cantidad_lleva: row.get(5)?,
+            cantidad_paga: row.get(6)?,
+            precio_paquete: row.get(7)?,
+            porcentaje: row.get(8)?,
+            fecha_inicio: row.get(9)?,
+            fecha_fin: row.get(10)?,
+            activa: activa == 1,
+            creado_at: row.get(12)?,
+        })
+    }
+
+    fn tipo_str(tipo: TipoPromocion) -> &'static str
+    {
+        match tipo {
+            TipoPromocion::NxM => "NxM",
+            TipoPromocion::PrecioPaquete => "PrecioPaquete",
+            TipoPromocion::Porcentaje => "Porcentaje",
+        }
+    }
+
+    fn query(&self, sql: &str, parametros: &[&dyn rusqlite::ToSql]) -> rusqlite::Result<Vec<Promocion>>
+    {
+        let mut stmt = self.conn.prepare(sql)?;
+        let rows = stmt.query_map(parametros, Self::map_row)?;
+        let mut promociones = Vec::new();
+        for promocion in rows
+        {
+            promociones.push(promocion?);
+        }
+        Ok(promociones)
+    }
+
+    pub fn list(&self) -> rusqlite::Result<Vec<Promocion>>
+    {
+        self.query(
+            &format!("{} ORDER BY activa DESC, nombre COLLATE NOCASE", PROMOCION_SELECT),
+            &[],
+        )
+    }
+
+    pub fn get(&self, id_promocion: i64) -> rusqlite::Result<Promocion>
+    {
+        self.conn.query_row(
+            &format!("{} WHERE id_promocion = ?1", PROMOCION_SELECT),
+            params![id_promocion],
+            Self::map_row,
+        )
+    }
+
+    // Promociones activas cuyo periodo (si tiene) incluye la fecha
+    pub fn list_vigentes(&self, fecha: &str) -> rusqlite::Result<Vec<Promocion>>
+    {
+        self.query(
+            &format!(
+                "{} WHERE activa = 1 \
+                   AND (fecha_inicio IS NULL OR DATE(?1) >= fecha_inicio) \
+                   AND (fecha_fin IS NULL OR DATE(?1) <= fecha_fin) \
+                 ORDER BY id_promocion",
+                PROMOCION_SELECT
+            ),
+            &[&fecha],
+        )
+    }
+
+    pub fn create(&self, input: &PromocionInput) -> rusqlite::Result<i64>
+    {
+        self.conn.execute(
+            "INSERT INTO promociones (nombre, tipo, id_producto, id_categoria, cantidad_lleva, cantidad_paga, \
+                                      precio_paquete, porcentaje, fecha_inicio, fecha_fin, activa) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![
+                input.nombre,
+                Self::tipo_str(input.tipo),
+                input.id_producto,
+                input.id_categoria,
+                input.cantidad_lleva,
+                input.cantidad_paga,
+                input.precio_paquete,
+                input.porcentaje,
+                input.fecha_inicio,
+                input.fecha_fin,
+                input.activa,
+            ],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    pub fn update(&self, id_promocion: i64, input: &PromocionInput) -> rusqlite::Result<usize>
+    {
+        self.conn.execute(
+            "UPDATE promociones \
+             SET nombre = ?2, tipo = ?3, id_producto = ?4, id_categoria = ?5, cantidad_lleva = ?6, cantidad_paga = ?7, \
+                 precio_paquete = ?8, porcentaje = ?9, fecha_inicio = ?10, fecha_fin = ?11, activa = ?12 \
+             WHERE id_promocion = ?1",
+            params![
+                id_promocion,
+                input.nombre,
+                Self::tipo_str(input.tipo),
+                input.id_producto,
+                input.id_categoria,
+                input.cantidad_lleva,
+                input.cantidad_paga,
+                input.precio_paquete,
+                input.porcentaje,
+                input.fecha_inicio,
+                input.fecha_fin,
+                input.activa,
+            ],
+        )
+    }
+
+    pub fn delete(&self, id_promocion: i64) -> rusqlite::Result<()>
+    {
+        self.conn.execute("DELETE FROM promociones WHERE id_promocion = ?1", params![id_promocion])?;
+        Ok(())
+    }
+
+    // Líneas de venta que registraron la promoción
+    pub fn count_usos(&self, id_promocion: i64) -> rusqlite::Result<i64>
+    {
+        self.conn.query_row(
+            "SELECT COUNT(*) FROM productos_vendidos WHERE id_promocion = ?1",
+            params![id_promocion],
+            |row| row.get(0),
+        )
+    }
+}
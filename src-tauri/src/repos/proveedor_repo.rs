@@ -0,0 +1,103 @@
+use rusqlite::{Connection, OptionalExtension, params};
+use crate::models::Proveedor;
+
+pub struct ProveedorRepo<'a>
+{
+    pub conn: &'a Connection,
+}
+
+impl<'a> ProveedorRepo<'a>
+{
+    pub fn list(&self) -> rusqlite::Result<Vec<Proveedor>>
+    {
+        let mut stmt = self.conn.prepare(
+            "SELECT id_proveedor, nombre, contacto, telefono, notas, creado_at FROM proveedores ORDER BY nombre COLLATE NOCASE"
+        )?;
+
+        let rows = stmt.query_map([], |row| {
+            Ok(Proveedor {
+                id_proveedor: row.get(0)?,
+                nombre: row.get(1)?,
+                contacto: row.get(2)?,
+                telefono: row.get(3)?,
+                notas: row.get(4)?,
+                creado_at: row.get(5)?,
+            })
+        })?;
+
+        let mut proveedores = Vec::new();
+        for proveedor in rows
+        {
+            proveedores.push(proveedor?);
+        }
+        Ok(proveedores)
+    }
+
+    pub fn get(&self, id: i64) -> rusqlite::Result<Proveedor>
+    {
+        self.conn.query_row(
+            "SELECT id_proveedor, nombre, contacto, telefono, notas, creado_at FROM proveedores WHERE id_proveedor = ?1",
+            params![id],
+            |row| {
+                Ok(Proveedor {
+                    id_proveedor: row.get(0)?,
+                    nombre: row.get(1)?,
+                    contacto: row.get(2)?,
+                    telefono: row.get(3)?,
+                    notas: row.get(4)?,
+                    creado_at: row.get(5)?,
+                })
+            },
+        )
+    }
+
+    pub fn find_id_by_nombre(&self, nombre: &str) -> rusqlite::Result<Option<i64>>
+    {
+        self.conn
+            .query_row(
+                "SELECT id_proveedor FROM proveedores WHERE LOWER(nombre) = LOWER(?1)",
+                params![nombre],
+                |row| row.get(0),
+            )
+            .optional()
+    }
+
+    pub fn create(&self, nombre: &str, contacto: &str, telefono: &str, notas: &str) -> rusqlite::Result<i64>
+    {
+        self.conn.execute(
+            "INSERT INTO proveedores (nombre, contacto, telefono, notas) VALUES (?1, ?2, ?3, ?4)",
+            params![nombre, contacto, telefono, notas],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    pub fn update(&self, proveedor: &Proveedor) -> rusqlite::Result<()>
+    {
+        self.conn.execute(
+            "UPDATE proveedores SET nombre = ?1, contacto = ?2, telefono = ?3, notas = ?4 WHERE id_proveedor = ?5",
+            params![
+                proveedor.nombre,
+                proveedor.contacto,
+                proveedor.telefono,
+                proveedor.notas,
+                proveedor.id_proveedor,
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn count_compras(&self, id: i64) -> rusqlite::Result<i64>
+    {
+        self.conn.query_row(
+            "SELECT COUNT(*) FROM compras WHERE id_proveedor = ?1",
+            params![id],
+            |row| row.get(0),
+        )
+    }
+
+    pub fn delete(&self, id: i64) -> rusqlite::Result<()>
+    {
+        self.conn.execute("DELETE FROM proveedores WHERE id_proveedor = ?1", params![id])?;
+        Ok(())
+    }
+}
@@ -1,5 +1,28 @@
-use rusqlite::{Connection, params};
-use crate::models::{EstadoPago, TipoPago, Venta, VentaCobranzaView};
+use rusqlite::{Connection, Row, ToSql, params};
+use crate::models::{CalculoVenta, ConsultaLista, EstadoPago, TipoDescuento, TipoPago, Venta, VentaCobranzaView};
+use crate::repos::consulta::{self, FiltroSql};
+
+// Ventas con lo abonado a cada una, para filtrar y ordenar por estado de pago o saldo
+const VENTAS_CON_ABONADO: &str = "(SELECT v.*, \
+        COALESCE((SELECT SUM(a.monto_abono) FROM abonos_venta a WHERE a.id_venta = v.id_venta), 0) AS abonado \
+    FROM ventas v) AS ventas";
+
+// Mismo criterio que map_cobranza_row: de contado siempre está liquidada
+const ESTADO_PAGO_SQL: &str = "(CASE \
+        WHEN ventas.tipo_pago <> 'Abono' THEN 'Liquidada' \
+        WHEN ventas.abonado <= 0 THEN 'Pendiente' \
+        WHEN ventas.abonado >= ventas.total_venta THEN 'Liquidada' \
+        ELSE 'Parcial' \
+    END)";
+
+// Campos de orden de los listados paginados de ventas
+pub const ORDEN_VENTAS: &[(&str, &str)] = &[
+    ("fecha", "ventas.fecha"),
+    ("total", "ventas.total_venta"),
+    ("clienta", "(ventas.nombre_clienta || ' ' || ventas.apellido_clienta) COLLATE NOCASE"),
+    ("saldo", "(CASE WHEN ventas.tipo_pago = 'Abono' THEN MAX(ventas.total_venta - ventas.abonado, 0) ELSE 0 END)"),
+    ("id_venta", "ventas.id_venta"),
+];
 
 pub struct VentaRepo<'a> {
    pub conn: &'a Connection,
@@ -11,9 +34,30 @@ impl<'a> VentaRepo<'a>
 
     pub fn list_with_cobranza(&self) -> rusqlite::Result<Vec<VentaCobranzaView>>
     {
-        let mut stmt = self.conn.prepare(
+        self.query_with_cobranza("", "", &[])
+    }
+
+    pub fn list_with_cobranza_by_campana(&self, id_campana: i64) -> rusqlite::Result<Vec<VentaCobranzaView>>
+    {
+        self.query_with_cobranza("WHERE ventas.id_campana = ?1", "", &[&id_campana])
+    }
+
+    // Ventas de una clienta en orden cronológico, con su estado de cobranza
+    pub fn list_with_cobranza_by_clienta(&self, id_clienta: i64) -> rusqlite::Result<Vec<VentaCobranzaView>>
+    {
+        self.query_with_cobranza(
+            "WHERE ventas.id_clienta = ?1",
+            "ORDER BY ventas.fecha, ventas.id_venta",
+            &[&id_clienta],
+        )
+    }
+
+    fn query_with_cobranza(&self, filtro: &str, orden: &str, filtro_params: &[&dyn ToSql]) -> rusqlite::Result<Vec<VentaCobranzaView>>
+    {
+        let sql = format!(
             "SELECT \
                 ventas.id_venta, \
+                ventas.id_clienta, \
                 ventas.fecha, \
                 ventas.nombre_clienta, \
                 ventas.apellido_clienta, \
@@ -24,56 +68,27 @@ impl<'a> VentaRepo<'a>
                     WHEN (ventas.total_venta - COALESCE(SUM(abonos_venta.monto_abono), 0)) > 0 \
                     THEN (ventas.total_venta - COALESCE(SUM(abonos_venta.monto_abono), 0)) \
                     ELSE 0 \
-                END AS saldo_pendiente \
+                END AS saldo_pendiente, \
+                ventas.id_campana \
             FROM ventas \
             LEFT JOIN abonos_venta ON abonos_venta.id_venta = ventas.id_venta \
+            {} \
             GROUP BY \
                 ventas.id_venta, \
+                ventas.id_clienta, \
                 ventas.fecha, \
                 ventas.nombre_clienta, \
                 ventas.apellido_clienta, \
                 ventas.total_venta, \
-                ventas.tipo_pago"
-        )?;
+                ventas.tipo_pago, \
+                ventas.id_campana \
+            {}",
+            filtro,
+            orden
+        );
 
-        let rows = stmt.query_map([], |row| {
-            let tipo_pago_str: String = row.get(5)?;
-            let tipo_pago = match tipo_pago_str.as_str() {
-                "Abono" => TipoPago::Abono,
-                "De Contado" => TipoPago::Contado,
-                _ => TipoPago::Contado,
-            };
-
-            let total_venta: f64 = row.get(4)?;
-            let total_abonado_db: f64 = row.get(6)?;
-            let saldo_pendiente_db: f64 = row.get(7)?;
-
-            let (total_abonado, saldo_pendiente, estado_pago) = match tipo_pago {
-                TipoPago::Contado => (total_venta, 0.0, EstadoPago::Liquidada),
-                TipoPago::Abono => {
-                    let estado = if total_abonado_db <= 0.0 {
-                        EstadoPago::Pendiente
-                    } else if total_abonado_db >= total_venta {
-                        EstadoPago::Liquidada
-                    } else {
-                        EstadoPago::Parcial
-                    };
-                    (total_abonado_db, saldo_pendiente_db, estado)
-                }
-            };
-
-            Ok(VentaCobranzaView {
-                id_venta: row.get(0)?,
-                fecha: row.get(1)?,
-                nombre_clienta: row.get(2)?,
-                apellido_clienta: row.get(3)?,
-                total_venta,
-                tipo_pago,
-                total_abonado,
-                saldo_pendiente,
-                estado_pago,
-            })
-        })?;
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(filtro_params, Self::map_cobranza_row)?;
 
         let mut ventas = Vec::new();
         for venta in rows
@@ -83,26 +98,74 @@ impl<'a> VentaRepo<'a>
         Ok(ventas)
     }
 
+    fn map_cobranza_row(row: &Row) -> rusqlite::Result<VentaCobranzaView>
+    {
+        let tipo_pago_str: String = row.get(6)?;
+        let tipo_pago = match tipo_pago_str.as_str() {
+            "Abono" => TipoPago::Abono,
+            "De Contado" => TipoPago::Contado,
+            _ => TipoPago::Contado,
+        };
+
+        let total_venta: f64 = row.get(5)?;
+        let total_abonado_db: f64 = row.get(7)?;
+        let saldo_pendiente_db: f64 = row.get(8)?;
+
+        let (total_abonado, saldo_pendiente, estado_pago) = match tipo_pago {
+            TipoPago::Contado => (total_venta, 0.0, EstadoPago::Liquidada),
+            TipoPago::Abono => {
+                let estado = if total_abonado_db <= 0.0 {
+                    EstadoPago::Pendiente
+                } else if total_abonado_db >= total_venta {
+                    EstadoPago::Liquidada
+                } else {
+                    EstadoPago::Parcial
+                };
+                (total_abonado_db, saldo_pendiente_db, estado)
+            }
+        };
+
+        Ok(VentaCobranzaView {
+            id_venta: row.get(0)?,
+            id_clienta: row.get(1)?,
+            fecha: row.get(2)?,
+            nombre_clienta: row.get(3)?,
+            apellido_clienta: row.get(4)?,
+            total_venta,
+            tipo_pago,
+            total_abonado,
+            saldo_pendiente,
+            estado_pago,
+            id_campana: row.get(9)?,
+        })
+    }
+
+    fn map_venta_row(row: &Row) -> rusqlite::Result<Venta>
+    {
+        let tipo_pago_str: String = row.get(5)?;
+        let tipo_pago = match tipo_pago_str.as_str() {
+            "Abono" => TipoPago::Abono,
+            "De Contado" => TipoPago::Contado,
+            _ => TipoPago::Contado,
+        };
+        Ok(Venta {
+            id_venta: row.get(0)?,
+            fecha: row.get(1)?,
+            nombre_clienta: row.get(2)?,
+            apellido_clienta: row.get(3)?,
+            total_venta: row.get(4)?,
+            tipo_pago,
+            id_clienta: row.get(6)?,
+            id_campana: row.get(7)?,
+            descuento: row.get(8)?,
+        })
+    }
+
     pub fn list(&self) -> rusqlite::Result<Vec<Venta>> 
     {
-        let mut stmt = self.conn.prepare("SELECT id_venta, fecha, nombre_clienta, apellido_clienta, total_venta, tipo_pago FROM ventas")?;
-
-        let rows = stmt.query_map([], |row| {
-            let tipo_pago_str: String = row.get(5)?;
-            let tipo_pago = match tipo_pago_str.as_str() {
-                "Abono" => TipoPago::Abono,
-                "De Contado" => TipoPago::Contado,
-                _ => TipoPago::Contado,
-            };
-            Ok(Venta {
-                id_venta: row.get(0)?,
-                fecha: row.get(1)?,
-                nombre_clienta: row.get(2)?,
-                apellido_clienta: row.get(3)?,
-                total_venta: row.get(4)?,
-                tipo_pago,
-            })
-        })?;
+        let mut stmt = self.conn.prepare("SELECT id_venta, fecha, nombre_clienta, apellido_clienta, total_venta, tipo_pago, id_clienta, id_campana, descuento FROM ventas")?;
+
+        let rows = stmt.query_map([], Self::map_venta_row)?;
 
         let mut ventas = Vec::new();
         for venta in rows
@@ -112,41 +175,101 @@ impl<'a> VentaRepo<'a>
         Ok(ventas)
     }
 
+    // Página de ventas con el total que cumple los filtros
+    pub fn list_paginado(&self, consulta: &ConsultaLista) -> rusqlite::Result<(Vec<Venta>, i64)>
+    {
+        let filtro = Self::filtro_ventas(consulta);
+        let total = self.count_ventas(&filtro)?;
+
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT ventas.id_venta, ventas.fecha, ventas.nombre_clienta, ventas.apellido_clienta, ventas.total_venta, \
+                    ventas.tipo_pago, ventas.id_clienta, ventas.id_campana, ventas.descuento \
+             FROM {} {} {} {}",
+            VENTAS_CON_ABONADO,
+            filtro.sql_where(),
+            consulta::orden_sql(consulta, ORDEN_VENTAS, "ventas.fecha DESC, ventas.id_venta DESC", "ventas.id_venta")?,
+            consulta::limite_sql(consulta),
+        ))?;
+        let rows = stmt.query_map(filtro.parametros().as_slice(), Self::map_venta_row)?;
+
+        let mut ventas = Vec::new();
+        for venta in rows
+        {
+            ventas.push(venta?);
+        }
+        Ok((ventas, total))
+    }
+
+    // Página de ventas con su estado de cobranza; admite filtrar y ordenar por estado y saldo
+    pub fn list_with_cobranza_paginado(&self, consulta: &ConsultaLista) -> rusqlite::Result<(Vec<VentaCobranzaView>, i64)>
+    {
+        let filtro = Self::filtro_ventas(consulta);
+        let total = self.count_ventas(&filtro)?;
+
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT ventas.id_venta, ventas.id_clienta, ventas.fecha, ventas.nombre_clienta, ventas.apellido_clienta, \
+                    ventas.total_venta, ventas.tipo_pago, ventas.abonado, MAX(ventas.total_venta - ventas.abonado, 0), \
+                    ventas.id_campana \
+             FROM {} {} {} {}",
+            VENTAS_CON_ABONADO,
+            filtro.sql_where(),
+            consulta::orden_sql(consulta, ORDEN_VENTAS, "ventas.fecha DESC, ventas.id_venta DESC", "ventas.id_venta")?,
+            consulta::limite_sql(consulta),
+        ))?;
+        let rows = stmt.query_map(filtro.parametros().as_slice(), Self::map_cobranza_row)?;
+
+        let mut ventas = Vec::new();
+        for venta in rows
+        {
+            ventas.push(venta?);
+        }
+        Ok((ventas, total))
+    }
+
+    fn filtro_ventas(consulta: &ConsultaLista) -> FiltroSql
+    {
+        let filtros = &consulta.filtros;
+        let mut filtro = FiltroSql::default();
+        filtro.con("DATE(ventas.fecha) >= DATE(?)", filtros.fecha_desde.clone());
+        filtro.con("DATE(ventas.fecha) <= DATE(?)", filtros.fecha_hasta.clone());
+        filtro.con("ventas.id_clienta = ?", filtros.id_clienta);
+        filtro.con("ventas.id_campana = ?", filtros.id_campana);
+        filtro.con(
+            &format!("{} = ?", ESTADO_PAGO_SQL),
+            filtros.estado_pago.map(consulta::estado_pago_texto),
+        );
+        filtro
+    }
+
+    fn count_ventas(&self, filtro: &FiltroSql) -> rusqlite::Result<i64>
+    {
+        self.conn.query_row(
+            &format!("SELECT COUNT(*) FROM {} {}", VENTAS_CON_ABONADO, filtro.sql_where()),
+            filtro.parametros().as_slice(),
+            |row| row.get(0),
+        )
+    }
+
     pub fn get(&self, id: i64) -> rusqlite::Result<Venta> 
     {
         self.conn.query_row
         (
-            "SELECT id_venta, fecha, nombre_clienta, apellido_clienta, total_venta, tipo_pago FROM ventas WHERE id_venta = ?1",
+            "SELECT id_venta, fecha, nombre_clienta, apellido_clienta, total_venta, tipo_pago, id_clienta, id_campana, descuento FROM ventas WHERE id_venta = ?1",
             params![id],
-            |row| {
-                let tipo_pago_str: String = row.get(5)?;
-                let tipo_pago = match tipo_pago_str.as_str() {
-                    "Abono" => TipoPago::Abono,
-                    "De Contado" => TipoPago::Contado,
-                    _ => TipoPago::Contado,
-                };
-                Ok(Venta {
-                    id_venta: row.get(0)?,
-                    fecha: row.get(1)?,
-                    nombre_clienta: row.get(2)?,
-                    apellido_clienta: row.get(3)?,
-                    total_venta: row.get(4)?,
-                    tipo_pago,
-                })
-            },
+            Self::map_venta_row,
         )
     }
 
 
-    pub fn create(&self, fecha: &str, nombre_clienta: &str, apellido_clienta: &str, total_venta: f64, tipo_pago: &TipoPago) -> rusqlite::Result<i64>
+    pub fn create(&self, fecha: &str, id_clienta: Option<i64>, nombre_clienta: &str, apellido_clienta: &str, total_venta: f64, tipo_pago: &TipoPago) -> rusqlite::Result<i64>
     {
         let tipo_pago_str = match tipo_pago {
             TipoPago::Abono => "Abono",
             TipoPago::Contado => "De Contado",
         };
         self.conn.execute(
-            "INSERT INTO ventas (fecha, nombre_clienta, apellido_clienta, total_venta, tipo_pago) VALUES (?1, ?2, ?3, ?4, ?5)",
-            params![fecha, nombre_clienta, apellido_clienta, total_venta, tipo_pago_str],
+            "INSERT INTO ventas (fecha, id_clienta, nombre_clienta, apellido_clienta, total_venta, tipo_pago) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![fecha, id_clienta, nombre_clienta, apellido_clienta, total_venta, tipo_pago_str],
         )?;
         Ok(self.conn.last_insert_rowid())
     }
@@ -158,9 +281,42 @@ impl<'a> VentaRepo<'a>
             TipoPago::Abono => "Abono",
             TipoPago::Contado => "De Contado",
         };
+        // id_clienta solo se reemplaza cuando se indica uno nuevo; id_campana ya viene resuelto por VentaService
+        self.conn.execute(
+            "UPDATE ventas SET fecha = ?1, nombre_clienta = ?2, apellido_clienta = ?3, total_venta = ?4, tipo_pago = ?5, id_clienta = COALESCE(?6, id_clienta), id_campana = ?8 WHERE id_venta = ?7",
+            params![venta.fecha, venta.nombre_clienta, venta.apellido_clienta, venta.total_venta, tipo_pago_str, venta.id_clienta, venta.id_venta, venta.id_campana],
+        )?;
+        Ok(())
+    }
+
+    // Guarda el descuento de la venta tal como se capturó y el monto que resultó
+    pub fn set_descuento(&self, id_venta: i64, calculo: &CalculoVenta) -> rusqlite::Result<()>
+    {
+        let tipo = calculo.descuento_tipo.map(|tipo| match tipo {
+            TipoDescuento::Porcentaje => "Porcentaje",
+            TipoDescuento::Monto => "Monto",
+        });
+        self.conn.execute(
+            "UPDATE ventas SET descuento_tipo = ?2, descuento_valor = ?3, descuento = ?4 WHERE id_venta = ?1",
+            params![id_venta, tipo, calculo.descuento_valor, calculo.descuento_venta],
+        )?;
+        Ok(())
+    }
+
+    pub fn set_campana(&self, id_venta: i64, id_campana: i64) -> rusqlite::Result<()>
+    {
+        self.conn.execute(
+            "UPDATE ventas SET id_campana = ?2 WHERE id_venta = ?1",
+            params![id_venta, id_campana],
+        )?;
+        Ok(())
+    }
+
+    pub fn update_clienta_snapshot(&self, id_clienta: i64, nombre_clienta: &str, apellido_clienta: &str) -> rusqlite::Result<()>
+    {
         self.conn.execute(
-            "UPDATE ventas SET fecha = ?1, nombre_clienta = ?2, apellido_clienta = ?3, total_venta = ?4, tipo_pago = ?5 WHERE id_venta = ?6",
-            params![venta.fecha, venta.nombre_clienta, venta.apellido_clienta, venta.total_venta, tipo_pago_str, venta.id_venta],
+            "UPDATE ventas SET nombre_clienta = ?1, apellido_clienta = ?2 WHERE id_clienta = ?3",
+            params![nombre_clienta, apellido_clienta, id_clienta],
         )?;
         Ok(())
     }
@@ -109,6 +109,16 @@ impl<'a> AbonoVentaService<'a>
         Ok(abonos)
     }
 
+    pub fn listar_abonos_por_clienta(&self, id_clienta: i64) -> Result<Vec<AbonoVenta>>
+    {
+        let repo = AbonoVentaRepo { conn: self.conn };
+        let mut abonos = repo.list_by_clienta(id_clienta)?;
+        for abono in &mut abonos {
+            abono.monto_abono = Self::normalize_money(abono.monto_abono);
+        }
+        Ok(abonos)
+    }
+
     pub fn obtener_total_abonado_por_venta(&self, id_venta: i64) -> Result<f64>
     {
         let repo = AbonoVentaRepo { conn: self.conn };
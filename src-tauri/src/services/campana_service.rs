@@ -0,0 +1,206 @@
+use chrono::NaiveDate;
+use rusqlite::{Connection, Error, Result};
+use crate::models::{Campana, CampanaInput, PrecioCampana, PrecioCampanaInput, PrecioVigente, VentaCompletaInput};
+use crate::repos::campana_repo::CampanaRepo;
+use crate::repos::producto_repo::ProductoRepo;
+use crate::services::abono_venta_service::AbonoVentaService;
+use crate::services::business_error;
+
+pub struct CampanaService<'a>
+{
+    pub conn: &'a Connection,
+}
+
+impl<'a> CampanaService<'a>
+{
+    pub fn new(conn: &'a Connection) -> Self
+    {
+        Self { conn }
+    }
+
+    pub fn listar_campanas(&self) -> Result<Vec<Campana>>
+    {
+        let repo = CampanaRepo { conn: self.conn };
+        repo.list()
+    }
+
+    pub fn get_campana(&self, id_campana: i64) -> Result<Campana>
+    {
+        let repo = CampanaRepo { conn: self.conn };
+        match repo.get(id_campana) {
+            Ok(campana) => Ok(campana),
+            Err(Error::QueryReturnedNoRows) => Err(business_error("La campaña indicada no existe")),
+            Err(err) => Err(err),
+        }
+    }
+
+    // Campaña vigente en la fecha (por defecto hoy)
+    pub fn get_campana_vigente(&self, fecha: Option<&str>) -> Result<Option<Campana>>
+    {
+        let fecha = self.fecha_o_hoy(fecha)?;
+        let repo = CampanaRepo { conn: self.conn };
+        repo.find_por_fecha(&fecha)
+    }
+
+    // Las campañas no se empalman; las ventas ya registradas en el periodo quedan asignadas a la nueva
+    pub fn create_campana(&self, input: &CampanaInput) -> Result<i64>
+    {
+        let (nombre, fecha_inicio, fecha_fin) = self.validate_campana(input, None)?;
+        let repo = CampanaRepo { conn: self.conn };
+        let id_campana = repo.create(&nombre, &fecha_inicio, &fecha_fin)?;
+        repo.asignar_ventas_sin_campana(id_campana)?;
+        Ok(id_campana)
+    }
+
+    pub fn update_campana(&self, id_campana: i64, input: &CampanaInput) -> Result<()>
+    {
+        self.get_campana(id_campana)?;
+        let (nombre, fecha_inicio, fecha_fin) = self.validate_campana(input, Some(id_campana))?;
+        let repo = CampanaRepo { conn: self.conn };
+        repo.update(id_campana, &nombre, &fecha_inicio, &fecha_fin)?;
+        // Las ventas que quedaron fuera del nuevo periodo salen de la campaña antes de asignar las que entraron
+        repo.reasignar_ventas_fuera_de_periodo(id_campana)?;
+        repo.asignar_ventas_sin_campana(id_campana)?;
+        Ok(())
+    }
+
+    pub fn delete_campana(&self, id_campana: i64) -> Result<()>
+    {
+        let campana = self.get_campana(id_campana)?;
+        if campana.ventas > 0 {
+            return Err(business_error(&format!(
+                "La campaña '{}' tiene {} venta(s) registradas y no se puede eliminar",
+                campana.nombre, campana.ventas
+            )));
+        }
+        let repo = CampanaRepo { conn: self.conn };
+        repo.delete(id_campana)
+    }
+
+    pub fn listar_precios(&self, id_campana: i64) -> Result<Vec<PrecioCampana>>
+    {
+        self.get_campana(id_campana)?;
+        let repo = CampanaRepo { conn: self.conn };
+        repo.list_precios(id_campana)
+    }
+
+    // Precio de catálogo del producto durante la campaña (reemplaza el anterior si existía)
+    pub fn set_precio(&self, id_campana: i64, input: &PrecioCampanaInput) -> Result<()>
+    {
+        self.get_campana(id_campana)?;
+        if let Err(Error::QueryReturnedNoRows) = (ProductoRepo { conn: self.conn }).get(input.id_producto) {
+            return Err(business_error("El producto indicado no existe"));
+        }
+
+        let precio_consultora = AbonoVentaService::normalize_money(input.precio_consultora);
+        let precio_publico = AbonoVentaService::normalize_money(input.precio_publico);
+        if precio_consultora < 0.0 || precio_publico < 0.0 {
+            return Err(business_error("Los precios de campaña no pueden ser negativos"));
+        }
+        if precio_publico < precio_consultora {
+            return Err(business_error("El precio público de campaña debe ser mayor o igual al precio consultora"));
+        }
+
+        let repo = CampanaRepo { conn: self.conn };
+        repo.upsert_precio(id_campana, input.id_producto, precio_consultora, precio_publico)
+    }
+
+    pub fn quitar_precio(&self, id_campana: i64, id_producto: i64) -> Result<()>
+    {
+        let repo = CampanaRepo { conn: self.conn };
+        repo.delete_precio(id_campana, id_producto)
+    }
+
+    // Campaña de la venta (la indicada o la vigente en su fecha) y precio de cada línea:
+    // el capturado en `precio_unitario`, o el precio público de la campaña (o del producto)
+    pub fn precios_venta(&self, input: &VentaCompletaInput) -> Result<(Option<i64>, Vec<f64>)>
+    {
+        let id_campana = match input.id_campana {
+            Some(id_campana) => Some(self.get_campana(id_campana)?.id_campana),
+            None => self.get_campana_vigente(Some(&input.fecha))?.map(|campana| campana.id_campana),
+        };
+
+        let mut precios = Vec::with_capacity(input.productos.len());
+        for item in &input.productos {
+            let precio = match item.precio_unitario {
+                Some(precio) => precio,
+                None => self.precio_vigente(item.id_producto, id_campana, Some(&input.fecha))?.precio_publico,
+            };
+            precios.push(precio);
+        }
+        Ok((id_campana, precios))
+    }
+
+    // Precio que aplica al producto: el de la campaña indicada (o la vigente en la fecha) y si no tiene, el del producto
+    pub fn precio_vigente(&self, id_producto: i64, id_campana: Option<i64>, fecha: Option<&str>) -> Result<PrecioVigente>
+    {
+        let producto = match (ProductoRepo { conn: self.conn }).get(id_producto) {
+            Ok(producto) => producto,
+            Err(Error::QueryReturnedNoRows) => {
+                return Err(business_error(&format!("Producto con ID {} no encontrado", id_producto)));
+            }
+            Err(err) => return Err(err),
+        };
+
+        let id_campana = match id_campana {
+            Some(id_campana) => Some(self.get_campana(id_campana)?.id_campana),
+            None => self.get_campana_vigente(fecha)?.map(|c| c.id_campana),
+        };
+
+        let repo = CampanaRepo { conn: self.conn };
+        if let Some(id_campana) = id_campana {
+            if let Some((precio_consultora, precio_publico)) = repo.find_precio(id_campana, id_producto)? {
+                return Ok(PrecioVigente {
+                    id_producto,
+                    id_campana: Some(id_campana),
+                    precio_consultora,
+                    precio_publico,
+                });
+            }
+        }
+
+        Ok(PrecioVigente {
+            id_producto,
+            id_campana: None,
+            precio_consultora: producto.precio_consultora,
+            precio_publico: producto.precio_publico,
+        })
+    }
+
+    fn validate_campana(&self, input: &CampanaInput, id_campana: Option<i64>) -> Result<(String, String, String)>
+    {
+        let nombre = input.nombre.trim();
+        if nombre.is_empty() {
+            return Err(business_error("El nombre de la campaña es obligatorio"));
+        }
+
+        let fecha_inicio = input.fecha_inicio.trim();
+        let fecha_fin = input.fecha_fin.trim();
+        for fecha in [fecha_inicio, fecha_fin] {
+            if NaiveDate::parse_from_str(fecha, "%Y-%m-%d").is_err() {
+                return Err(business_error("Las fechas de la campaña deben tener formato AAAA-MM-DD"));
+            }
+        }
+        if fecha_inicio > fecha_fin {
+            return Err(business_error("La fecha de inicio no puede ser posterior a la fecha fin"));
+        }
+
+        let repo = CampanaRepo { conn: self.conn };
+        if let Some(otra) = repo.find_empalme(fecha_inicio, fecha_fin, id_campana)? {
+            return Err(business_error(&format!(
+                "El periodo se empalma con la campaña '{}' ({} a {})",
+                otra.nombre, otra.fecha_inicio, otra.fecha_fin
+            )));
+        }
+
+        Ok((nombre.to_string(), fecha_inicio.to_string(), fecha_fin.to_string()))
+    }
+
+    fn fecha_o_hoy(&self, fecha: Option<&str>) -> Result<String>
+    {
+        match fecha.map(str::trim).filter(|f| !f.is_empty()) {
+            Some(fecha) => Ok(fecha.to_string()),
+            None => self.conn.query_row("SELECT DATE('now','localtime')", [], |row| row.get(0)),
+        }
+    }
+}
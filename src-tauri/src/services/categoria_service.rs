@@ -1,6 +1,8 @@
+use std::collections::{HashMap, HashSet};
 use rusqlite::{Connection, Result};
-use crate::models::Categoria;
+use crate::models::{Categoria, CategoriaNodo, ImpactoCategoria};
 use crate::repos::categoria_repo::CategoriaRepo;
+use crate::services::business_error;
 
 pub struct CategoriaService<'a> {
     pub conn: &'a Connection,
@@ -27,20 +29,227 @@ impl<'a> CategoriaService<'a> {
 
     pub fn create_categoria(&self, nombre: &str) -> Result<i64>
     {
+        let nombre = self.validar_nombre(nombre, None)?;
         let repo = CategoriaRepo { conn: self.conn};
-        repo.create(nombre)
+        repo.create(&nombre).map_err(|err| Self::error_nombre(err, &nombre))
+    }
+
+    pub fn create_subcategoria(&self, nombre: &str, id_categoria_padre: i64) -> Result<i64>
+    {
+        let repo = CategoriaRepo { conn: self.conn};
+        if let Err(rusqlite::Error::QueryReturnedNoRows) = repo.get(id_categoria_padre) {
+            return Err(business_error("La categoría padre indicada no existe"));
+        }
+        let id = self.create_categoria(nombre)?;
+        repo.update_padre(id, Some(id_categoria_padre))?;
+        Ok(id)
+    }
+
+    // Cambia el padre de la categoría (None la deja como raíz); no puede colgar de sí misma
+    // ni de una de sus subcategorías
+    pub fn mover_categoria(&self, id: i64, id_categoria_padre: Option<i64>) -> Result<()>
+    {
+        let repo = CategoriaRepo { conn: self.conn};
+        let categoria = repo.get(id)?;
+        if let Some(id_padre) = id_categoria_padre {
+            let padre = match repo.get(id_padre) {
+                Ok(padre) => padre,
+                Err(rusqlite::Error::QueryReturnedNoRows) => {
+                    return Err(business_error("La categoría padre indicada no existe"));
+                }
+                Err(err) => return Err(err),
+            };
+            if repo.ids_descendientes(id)?.contains(&id_padre) {
+                return Err(business_error(&format!(
+                    "'{}' no puede quedar dentro de '{}' porque es ella misma o una de sus subcategorías",
+                    categoria.nombre, padre.nombre
+                )));
+            }
+        }
+        repo.update_padre(id, id_categoria_padre)?;
+        Ok(())
+    }
+
+    // Id de la categoría más los de todas sus subcategorías
+    pub fn ids_con_subcategorias(&self, id: i64) -> Result<Vec<i64>>
+    {
+        let repo = CategoriaRepo { conn: self.conn};
+        repo.ids_descendientes(id)
+    }
+
+    // Categorías raíz con sus subcategorías anidadas, ordenadas por nombre
+    pub fn arbol_categorias(&self) -> Result<Vec<CategoriaNodo>>
+    {
+        let repo = CategoriaRepo { conn: self.conn};
+        let mut categorias = repo.list()?;
+        categorias.sort_by_key(|categoria| categoria.nombre.to_lowercase());
+        let productos: HashMap<i64, i64> = repo.count_productos()?.into_iter().collect();
+
+        // Una categoría cuyo padre ya no existe se muestra como raíz
+        let ids: HashSet<i64> = categorias.iter().map(|categoria| categoria.id_categoria).collect();
+        let mut hijos: HashMap<Option<i64>, Vec<Categoria>> = HashMap::new();
+        for categoria in categorias {
+            let padre = categoria.id_categoria_padre.filter(|id_padre| ids.contains(id_padre));
+            hijos.entry(padre).or_default().push(categoria);
+        }
+        Ok(Self::nodos(None, "", &mut hijos, &productos))
+    }
+
+    fn nodos(
+        id_padre: Option<i64>,
+        ruta_padre: &str,
+        hijos: &mut HashMap<Option<i64>, Vec<Categoria>>,
+        productos: &HashMap<i64, i64>,
+    ) -> Vec<CategoriaNodo>
+    {
+        let categorias = hijos.remove(&id_padre).unwrap_or_default();
+        categorias
+            .into_iter()
+            .map(|categoria| {
+                let ruta = if ruta_padre.is_empty() {
+                    categoria.nombre.clone()
+                } else {
+                    format!("{} > {}", ruta_padre, categoria.nombre)
+                };
+                CategoriaNodo {
+                    hijos: Self::nodos(Some(categoria.id_categoria), &ruta, hijos, productos),
+                    productos: productos.get(&categoria.id_categoria).copied().unwrap_or(0),
+                    id_categoria: categoria.id_categoria,
+                    nombre: categoria.nombre,
+                    id_categoria_padre: categoria.id_categoria_padre,
+                    stock_minimo_default: categoria.stock_minimo_default,
+                    ruta,
+                }
+            })
+            .collect()
     }
 
     pub fn update_categoria(&self, categoria: &Categoria) -> Result<()>
     {
+        let nombre = self.validar_nombre(&categoria.nombre, Some(categoria.id_categoria))?;
+        let repo = CategoriaRepo { conn: self.conn};
+        let normalizada = Categoria { nombre, ..*categoria };
+        repo.update(&normalizada).map_err(|err| Self::error_nombre(err, &normalizada.nombre))
+    }
+
+    pub fn set_stock_minimo_default(&self, id: i64, stock_minimo: Option<i64>) -> Result<()>
+    {
+        if stock_minimo.is_some_and(|minimo| minimo < 0) {
+            return Err(business_error("El stock mínimo no puede ser negativo"));
+        }
+
+        let repo = CategoriaRepo { conn: self.conn};
+        if repo.update_stock_minimo_default(id, stock_minimo)? == 0 {
+            return Err(business_error("La categoría indicada no existe"));
+        }
+        Ok(())
+    }
+
+    // Qué se vería afectado al eliminar la categoría, para confirmarlo antes
+    pub fn impacto_eliminacion(&self, id: i64) -> Result<ImpactoCategoria>
+    {
+        let repo = CategoriaRepo { conn: self.conn};
+        let categoria = repo.get(id)?;
+        let (productos_activos, productos_descontinuados, subcategorias, promociones) = repo.count_dependientes(id)?;
+        Ok(ImpactoCategoria {
+            id_categoria: categoria.id_categoria,
+            nombre: categoria.nombre,
+            productos_activos,
+            productos_descontinuados,
+            subcategorias,
+            promociones,
+            id_categoria_destino: None,
+        })
+    }
+
+    // Elimina la categoría moviendo sus productos y promociones a `id_destino`; sin destino los productos
+    // quedan sin categoría. Las subcategorías suben al padre de la categoría borrada.
+    // Debe ejecutarse dentro de una transacción
+    pub fn delete_categoria(&self, id: i64, id_destino: Option<i64>) -> Result<ImpactoCategoria>
+    {
+        let mut impacto = self.impacto_eliminacion(id)?;
         let repo = CategoriaRepo { conn: self.conn};
-        repo.update(categoria)
+        match id_destino {
+            Some(id_destino) => {
+                self.validar_destino(id, id_destino)?;
+                repo.mover_productos(id, Some(id_destino))?;
+                repo.mover_promociones(id, id_destino)?;
+            }
+            None if impacto.promociones > 0 => {
+                return Err(business_error(&format!(
+                    "'{}' tiene {} promoción(es); indica a qué categoría moverlas",
+                    impacto.nombre, impacto.promociones
+                )));
+            }
+            None => {
+                repo.mover_productos(id, None)?;
+            }
+        }
+        repo.subir_subcategorias(id)?;
+        repo.delete(id)?;
+        impacto.id_categoria_destino = id_destino;
+        Ok(impacto)
     }
 
-    pub fn delete_categoria(&self, id: i64) -> Result<()>
+    // Pasa productos, promociones y subcategorías de `origen` a `destino` y elimina `origen`;
+    // debe ejecutarse dentro de una transacción
+    pub fn merge_categorias(&self, origen: i64, destino: i64) -> Result<ImpactoCategoria>
     {
+        let mut impacto = self.impacto_eliminacion(origen)?;
+        self.validar_destino(origen, destino)?;
         let repo = CategoriaRepo { conn: self.conn};
-        repo.delete(id)
+        if repo.ids_descendientes(origen)?.contains(&destino) {
+            return Err(business_error(&format!(
+                "No se puede fusionar '{}' dentro de una de sus subcategorías",
+                impacto.nombre
+            )));
+        }
+        repo.mover_productos(origen, Some(destino))?;
+        repo.mover_promociones(origen, destino)?;
+        repo.mover_subcategorias(origen, destino)?;
+        repo.delete(origen)?;
+        impacto.id_categoria_destino = Some(destino);
+        Ok(impacto)
+    }
+
+    fn validar_destino(&self, id: i64, id_destino: i64) -> Result<()>
+    {
+        if id_destino == id {
+            return Err(business_error("La categoría destino debe ser distinta a la que se elimina"));
+        }
+        let repo = CategoriaRepo { conn: self.conn};
+        match repo.get(id_destino) {
+            Ok(_) => Ok(()),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Err(business_error("La categoría destino no existe")),
+            Err(err) => Err(err),
+        }
+    }
+
+    // Nombre sin espacios sobrantes y único sin distinguir mayúsculas
+    fn validar_nombre(&self, nombre: &str, excluir: Option<i64>) -> Result<String>
+    {
+        let nombre = nombre.trim();
+        if nombre.is_empty() {
+            return Err(business_error("El nombre de la categoría no puede estar vacío"));
+        }
+        let repo = CategoriaRepo { conn: self.conn};
+        if let Some(existente) = repo.find_by_nombre(nombre, excluir)? {
+            return Err(business_error(&format!("Ya existe una categoría llamada '{}'", existente.nombre)));
+        }
+        Ok(nombre.to_string())
+    }
+
+    // El UNIQUE de `categorias.nombre` como mensaje claro
+    fn error_nombre(err: rusqlite::Error, nombre: &str) -> rusqlite::Error
+    {
+        match &err {
+            rusqlite::Error::SqliteFailure(fallo, Some(mensaje))
+                if fallo.code == rusqlite::ErrorCode::ConstraintViolation && mensaje.contains("categorias.nombre") =>
+            {
+                business_error(&format!("Ya existe una categoría llamada '{}'", nombre))
+            }
+            _ => err,
+        }
     }
 
 
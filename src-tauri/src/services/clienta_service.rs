@@ -0,0 +1,137 @@
+use rusqlite::{Connection, Error, Result};
+use crate::models::{Clienta, ClientaInput, VentaCompletaInput};
+use crate::repos::clienta_repo::ClientaRepo;
+use crate::repos::venta_repo::VentaRepo;
+use crate::services::business_error;
+
+pub struct ClientaService<'a>
+{
+    pub conn: &'a Connection,
+}
+
+impl<'a> ClientaService<'a>
+{
+    pub fn new(conn: &'a Connection) -> Self
+    {
+        Self { conn }
+    }
+
+    pub fn list_clientas(&self) -> Result<Vec<Clienta>>
+    {
+        let repo = ClientaRepo { conn: self.conn };
+        repo.list()
+    }
+
+    pub fn buscar_clientas(&self, texto: &str) -> Result<Vec<Clienta>>
+    {
+        let texto = texto.trim();
+        let repo = ClientaRepo { conn: self.conn };
+        if texto.is_empty() {
+            return repo.list();
+        }
+        repo.search(texto)
+    }
+
+    pub fn get_clienta(&self, id: i64) -> Result<Clienta>
+    {
+        let repo = ClientaRepo { conn: self.conn };
+        match repo.get(id) {
+            Err(Error::QueryReturnedNoRows) => Err(business_error("La clienta indicada no existe")),
+            other => other,
+        }
+    }
+
+    pub fn create_clienta(&self, input: &ClientaInput) -> Result<i64>
+    {
+        let nombre = input.nombre.trim();
+        if nombre.is_empty() {
+            return Err(business_error("Debe ingresar el nombre de la clienta"));
+        }
+
+        let repo = ClientaRepo { conn: self.conn };
+        repo.create(
+            nombre,
+            input.apellido.trim(),
+            input.telefono.as_deref().map(str::trim).unwrap_or(""),
+            input.direccion.as_deref().map(str::trim).unwrap_or(""),
+            input.notas.as_deref().map(str::trim).unwrap_or(""),
+        )
+    }
+
+    pub fn update_clienta(&self, clienta: &Clienta) -> Result<()>
+    {
+        let nombre = clienta.nombre.trim();
+        if nombre.is_empty() {
+            return Err(business_error("Debe ingresar el nombre de la clienta"));
+        }
+
+        self.get_clienta(clienta.id_clienta)?;
+
+        let normalizada = Clienta {
+            id_clienta: clienta.id_clienta,
+            nombre: nombre.to_string(),
+            apellido: clienta.apellido.trim().to_string(),
+            telefono: clienta.telefono.trim().to_string(),
+            direccion: clienta.direccion.trim().to_string(),
+            notas: clienta.notas.trim().to_string(),
+            creado_at: clienta.creado_at.clone(),
+        };
+
+        let repo = ClientaRepo { conn: self.conn };
+        repo.update(&normalizada)?;
+
+        // Mantiene el nombre mostrado en las ventas alineado con el directorio
+        let venta_repo = VentaRepo { conn: self.conn };
+        venta_repo.update_clienta_snapshot(normalizada.id_clienta, &normalizada.nombre, &normalizada.apellido)
+    }
+
+    pub fn delete_clienta(&self, id: i64) -> Result<()>
+    {
+        let repo = ClientaRepo { conn: self.conn };
+        if repo.count_ventas(id)? > 0 {
+            return Err(business_error("No se puede eliminar una clienta con ventas registradas"));
+        }
+        repo.delete(id)
+    }
+
+    // Devuelve la clienta con ese nombre exacto (sin distinguir mayúsculas) o la registra
+    pub fn obtener_o_crear_por_nombre(&self, nombre: &str, apellido: &str) -> Result<Clienta>
+    {
+        let nombre = nombre.trim();
+        let apellido = apellido.trim();
+        if nombre.is_empty() {
+            return Err(business_error("Debe ingresar el nombre de la clienta"));
+        }
+
+        let repo = ClientaRepo { conn: self.conn };
+        if let Some(clienta) = repo.find_by_nombre(nombre, apellido)? {
+            return Ok(clienta);
+        }
+
+        let id = repo.create(nombre, apellido, "", "", "")?;
+        repo.get(id)
+    }
+
+    // Resuelve la clienta de una venta completa: id existente, clienta nueva o nombre libre
+    pub fn resolver_clienta_venta(&self, input: &VentaCompletaInput) -> Result<Clienta>
+    {
+        if let Some(id_clienta) = input.id_clienta {
+            return self.get_clienta(id_clienta);
+        }
+
+        if let Some(nueva) = input.clienta_nueva.as_ref() {
+            let id = self.create_clienta(nueva)?;
+            return self.get_clienta(id);
+        }
+
+        if input.nombre_clienta.trim().is_empty() {
+            return Err(business_error("Debe ingresar el nombre de la clienta"));
+        }
+
+        if input.apellido_clienta.trim().is_empty() {
+            return Err(business_error("Debe ingresar el apellido de la clienta"));
+        }
+
+        self.obtener_o_crear_por_nombre(&input.nombre_clienta, &input.apellido_clienta)
+    }
+}
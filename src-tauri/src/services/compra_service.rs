@@ -0,0 +1,234 @@
+use rusqlite::{Connection, Error, Result};
+use crate::models::{
+    Compra,
+    CompraCompleta,
+    CompraInput,
+    EstadoCompra,
+    MotivoMovimiento,
+    RecepcionLineaInput,
+    RegistrarPagoCompraInput,
+};
+use crate::repos::compra_repo::CompraRepo;
+use crate::repos::pago_compra_repo::PagoCompraRepo;
+use crate::repos::producto_repo::ProductoRepo;
+use crate::services::abono_venta_service::AbonoVentaService;
+use crate::services::business_error;
+use crate::services::lote_service::LoteService;
+use crate::services::movimiento_inventario_service::MovimientoInventarioService;
+use crate::services::proveedor_service::ProveedorService;
+
+pub struct CompraService<'a>
+{
+    pub conn: &'a Connection,
+}
+
+impl<'a> CompraService<'a>
+{
+    pub fn new(conn: &'a Connection) -> Self
+    {
+        Self { conn }
+    }
+
+    pub fn list_compras(&self) -> Result<Vec<Compra>>
+    {
+        let repo = CompraRepo { conn: self.conn };
+        repo.list()
+    }
+
+    pub fn get_compra(&self, id_compra: i64) -> Result<CompraCompleta>
+    {
+        let repo = CompraRepo { conn: self.conn };
+        let compra = match repo.get(id_compra) {
+            Ok(compra) => compra,
+            Err(Error::QueryReturnedNoRows) => {
+                return Err(business_error("La compra indicada no existe"));
+            }
+            Err(err) => return Err(err),
+        };
+
+        let lineas = repo.list_detalle(id_compra)?;
+        let pagos = PagoCompraRepo { conn: self.conn }.list_by_compra(id_compra)?;
+
+        Ok(CompraCompleta { compra, lineas, pagos })
+    }
+
+    // Crea la orden de compra; el stock no cambia hasta registrar la recepción
+    pub fn create_compra(&self, input: &CompraInput) -> Result<i64>
+    {
+        if input.lineas.is_empty() {
+            return Err(business_error("Debe agregar al menos un producto a la compra"));
+        }
+
+        if let Some(id_proveedor) = input.id_proveedor {
+            ProveedorService::new(self.conn).get_proveedor(id_proveedor)?;
+        }
+
+        let producto_repo = ProductoRepo { conn: self.conn };
+        let mut total_compra = 0.0;
+        for linea in &input.lineas {
+            if linea.cantidad <= 0 {
+                return Err(business_error("La cantidad de cada producto debe ser mayor a 0"));
+            }
+            if linea.costo_unitario < 0.0 {
+                return Err(business_error("El costo unitario no puede ser negativo"));
+            }
+            match producto_repo.get(linea.id_producto) {
+                Ok(producto) if producto.es_kit => {
+                    return Err(business_error(&format!(
+                        "'{}' es un kit: registra la compra de sus componentes",
+                        producto.nombre_producto
+                    )));
+                }
+                Ok(_) => {}
+                Err(Error::QueryReturnedNoRows) => {
+                    return Err(business_error(&format!("Producto con ID {} no encontrado", linea.id_producto)));
+                }
+                Err(err) => return Err(err),
+            }
+            total_compra += linea.cantidad as f64 * AbonoVentaService::normalize_money(linea.costo_unitario);
+        }
+
+        let fecha = match input.fecha.as_deref() {
+            Some(fecha) if !fecha.trim().is_empty() => fecha.to_string(),
+            _ => self.current_local_datetime()?,
+        };
+        let notas = input.notas.as_deref().map(str::trim).unwrap_or("");
+
+        let repo = CompraRepo { conn: self.conn };
+        let id_compra = repo.create(
+            input.id_proveedor,
+            &fecha,
+            AbonoVentaService::normalize_money(total_compra),
+            notas,
+        )?;
+
+        for linea in &input.lineas {
+            let costo = AbonoVentaService::normalize_money(linea.costo_unitario);
+            repo.create_detalle(
+                id_compra,
+                linea.id_producto,
+                linea.cantidad,
+                costo,
+                AbonoVentaService::normalize_money(linea.cantidad as f64 * costo),
+            )?;
+        }
+
+        Ok(id_compra)
+    }
+
+    // Marca mercancía como recibida e incrementa el stock; debe ejecutarse dentro de una transacción
+    pub fn recibir_compra(&self, id_compra: i64, recepciones: &[RecepcionLineaInput]) -> Result<Compra>
+    {
+        if recepciones.is_empty() {
+            return Err(business_error("Debe indicar al menos una línea recibida"));
+        }
+
+        let repo = CompraRepo { conn: self.conn };
+        let lineas = self.get_compra(id_compra)?.lineas;
+        let producto_repo = ProductoRepo { conn: self.conn };
+        let movimientos = MovimientoInventarioService::new(self.conn);
+        let lotes = LoteService::new(self.conn);
+
+        for recepcion in recepciones {
+            let linea = lineas
+                .iter()
+                .find(|l| l.id_detalle == recepcion.id_detalle)
+                .ok_or_else(|| business_error("La línea indicada no pertenece a la compra"))?;
+
+            if recepcion.cantidad <= 0 {
+                return Err(business_error("La cantidad recibida debe ser mayor a 0"));
+            }
+
+            let pendiente = linea.cantidad_pedida - linea.cantidad_recibida;
+            if recepcion.cantidad > pendiente {
+                return Err(business_error(&format!(
+                    "La cantidad recibida de '{}' excede lo pendiente. Pendiente: {}, Recibido: {}",
+                    linea.nombre_producto, pendiente, recepcion.cantidad
+                )));
+            }
+
+            // Costo promedio ponderado con el stock existente antes de sumar lo recibido
+            let producto = producto_repo.get(linea.id_producto)?;
+            let stock_previo = producto.stock.max(0);
+            let costo_promedio = if stock_previo == 0 {
+                linea.costo_unitario
+            } else {
+                (stock_previo as f64 * producto.costo_promedio + recepcion.cantidad as f64 * linea.costo_unitario)
+                    / (stock_previo + recepcion.cantidad) as f64
+            };
+            producto_repo.update_costo_promedio(linea.id_producto, AbonoVentaService::normalize_money(costo_promedio))?;
+
+            repo.add_recibido(linea.id_detalle, recepcion.cantidad)?;
+            producto_repo.adjust_stock(linea.id_producto, recepcion.cantidad)?;
+            lotes.registrar_entrada(
+                linea.id_producto,
+                recepcion.cantidad,
+                recepcion.codigo_lote.as_deref(),
+                recepcion.fecha_caducidad.as_deref(),
+            )?;
+            movimientos.registrar(
+                linea.id_producto,
+                recepcion.cantidad,
+                MotivoMovimiento::Compra,
+                Some(id_compra),
+                "Recepción de compra",
+            )?;
+        }
+
+        let lineas = repo.list_detalle(id_compra)?;
+        let estado = if lineas.iter().all(|l| l.cantidad_recibida >= l.cantidad_pedida) {
+            EstadoCompra::Recibida
+        } else if lineas.iter().any(|l| l.cantidad_recibida > 0) {
+            EstadoCompra::Parcial
+        } else {
+            EstadoCompra::Pendiente
+        };
+        repo.update_estado(id_compra, &estado)?;
+
+        repo.get(id_compra)
+    }
+
+    pub fn registrar_pago(&self, input: &RegistrarPagoCompraInput) -> Result<i64>
+    {
+        let monto_pago = AbonoVentaService::normalize_money(input.monto_pago);
+        if monto_pago <= 0.0 {
+            return Err(business_error("El monto del pago debe ser mayor a 0"));
+        }
+
+        let compra = self.get_compra(input.id_compra)?.compra;
+        let saldo = AbonoVentaService::calculate_outstanding_balance(compra.total_compra, compra.total_pagado);
+        if monto_pago > saldo {
+            return Err(business_error("El monto del pago excede el saldo pendiente de la compra"));
+        }
+
+        let fecha_pago = match input.fecha_pago.as_deref() {
+            Some(fecha) if !fecha.trim().is_empty() => fecha.to_string(),
+            _ => self.current_local_datetime()?,
+        };
+        let observacion = input.observacion.as_deref().map(str::trim).unwrap_or("");
+
+        let repo = PagoCompraRepo { conn: self.conn };
+        repo.create(input.id_compra, monto_pago, &fecha_pago, observacion)
+    }
+
+    // Solo se eliminan órdenes sin mercancía recibida (no hay stock que revertir)
+    pub fn delete_compra(&self, id_compra: i64) -> Result<()>
+    {
+        let compra = self.get_compra(id_compra)?;
+        if compra.lineas.iter().any(|l| l.cantidad_recibida > 0) {
+            return Err(business_error("No se puede eliminar una compra con mercancía recibida"));
+        }
+
+        let repo = CompraRepo { conn: self.conn };
+        repo.delete(id_compra)
+    }
+
+    fn current_local_datetime(&self) -> Result<String>
+    {
+        self.conn.query_row(
+            "SELECT datetime('now','localtime')",
+            [],
+            |row| row.get(0),
+        )
+    }
+}
@@ -0,0 +1,132 @@
+use rusqlite::{Connection, Error, Result};
+use crate::models::{ConteoInventario, EstadoConteo, MotivoAjuste, ReporteConteo};
+use crate::repos::conteo_repo::ConteoRepo;
+use crate::repos::producto_repo::ProductoRepo;
+use crate::services::abono_venta_service::AbonoVentaService;
+use crate::services::business_error;
+use crate::services::producto_service::ProductoService;
+
+pub struct ConteoService<'a>
+{
+    pub conn: &'a Connection,
+}
+
+impl<'a> ConteoService<'a>
+{
+    pub fn new(conn: &'a Connection) -> Self
+    {
+        Self { conn }
+    }
+
+    pub fn listar_conteos(&self) -> Result<Vec<ConteoInventario>>
+    {
+        let repo = ConteoRepo { conn: self.conn };
+        repo.list()
+    }
+
+    pub fn get_conteo(&self, id_conteo: i64) -> Result<ConteoInventario>
+    {
+        let repo = ConteoRepo { conn: self.conn };
+        match repo.get(id_conteo) {
+            Ok(conteo) => Ok(conteo),
+            Err(Error::QueryReturnedNoRows) => Err(business_error("El conteo indicado no existe")),
+            Err(err) => Err(err),
+        }
+    }
+
+    // Solo puede haber un conteo abierto a la vez
+    pub fn abrir_conteo(&self, descripcion: &str) -> Result<i64>
+    {
+        let repo = ConteoRepo { conn: self.conn };
+        if repo.count_abiertos()? > 0 {
+            return Err(business_error("Ya existe un conteo abierto; aplíquelo antes de iniciar otro"));
+        }
+        repo.create(descripcion.trim())
+    }
+
+    // Conteo parcial: solo los productos registrados se comparan y ajustan
+    pub fn registrar_cantidad(&self, id_conteo: i64, id_producto: i64, cantidad_contada: i64) -> Result<()>
+    {
+        self.get_conteo_abierto(id_conteo)?;
+        if cantidad_contada < 0 {
+            return Err(business_error("La cantidad contada no puede ser negativa"));
+        }
+        match (ProductoRepo { conn: self.conn }).get(id_producto) {
+            Ok(producto) if producto.es_kit => {
+                return Err(business_error("El stock de un kit se calcula de sus componentes; cuenta los componentes"));
+            }
+            Ok(_) => {}
+            Err(Error::QueryReturnedNoRows) => return Err(business_error("El producto indicado no existe")),
+            Err(err) => return Err(err),
+        }
+
+        let repo = ConteoRepo { conn: self.conn };
+        repo.upsert_detalle(id_conteo, id_producto, cantidad_contada)
+    }
+
+    pub fn quitar_producto(&self, id_conteo: i64, id_producto: i64) -> Result<()>
+    {
+        self.get_conteo_abierto(id_conteo)?;
+        let repo = ConteoRepo { conn: self.conn };
+        repo.delete_detalle(id_conteo, id_producto)?;
+        Ok(())
+    }
+
+    pub fn get_reporte(&self, id_conteo: i64) -> Result<ReporteConteo>
+    {
+        let conteo = self.get_conteo(id_conteo)?;
+        let lineas = ConteoRepo { conn: self.conn }.list_varianza(id_conteo)?;
+
+        let valor_faltante: f64 = lineas
+            .iter()
+            .filter(|l| l.diferencia < 0)
+            .map(|l| -l.valor_diferencia)
+            .sum();
+        let valor_sobrante: f64 = lineas
+            .iter()
+            .filter(|l| l.diferencia > 0)
+            .map(|l| l.valor_diferencia)
+            .sum();
+
+        Ok(ReporteConteo {
+            conteo,
+            lineas,
+            valor_faltante: AbonoVentaService::normalize_money(valor_faltante),
+            valor_sobrante: AbonoVentaService::normalize_money(valor_sobrante),
+            valor_neto: AbonoVentaService::normalize_money(valor_sobrante - valor_faltante),
+        })
+    }
+
+    // Ajusta el stock de todos los productos contados y cierra la sesión; debe ejecutarse dentro de una transacción
+    pub fn aplicar_conteo(&self, id_conteo: i64) -> Result<ReporteConteo>
+    {
+        self.get_conteo_abierto(id_conteo)?;
+
+        let repo = ConteoRepo { conn: self.conn };
+        let lineas = repo.list_varianza(id_conteo)?;
+        if lineas.is_empty() {
+            return Err(business_error("El conteo no tiene productos registrados"));
+        }
+
+        let producto_service = ProductoService::new(self.conn);
+        let nota = format!("Conteo físico #{}", id_conteo);
+        for linea in &lineas {
+            repo.snapshot_detalle(id_conteo, linea.id_producto, linea.stock_sistema, linea.precio_consultora)?;
+            if linea.diferencia != 0 {
+                producto_service.ajustar_stock(linea.id_producto, linea.diferencia, MotivoAjuste::Reconteo, &nota)?;
+            }
+        }
+        repo.cerrar(id_conteo)?;
+
+        self.get_reporte(id_conteo)
+    }
+
+    fn get_conteo_abierto(&self, id_conteo: i64) -> Result<ConteoInventario>
+    {
+        let conteo = self.get_conteo(id_conteo)?;
+        if conteo.estado != EstadoConteo::Abierto {
+            return Err(business_error("El conteo ya fue aplicado y no puede modificarse"));
+        }
+        Ok(conteo)
+    }
+}
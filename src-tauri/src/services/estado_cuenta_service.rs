@@ -0,0 +1,105 @@
+use rusqlite::{Connection, Result};
+use crate::models::{EstadoCuentaClienta, EstadoPago, MovimientoEstadoCuenta, TipoPago};
+use crate::repos::venta_repo::VentaRepo;
+use crate::services::abono_venta_service::AbonoVentaService;
+use crate::services::clienta_service::ClientaService;
+
+pub struct EstadoCuentaService<'a>
+{
+    pub conn: &'a Connection,
+}
+
+impl<'a> EstadoCuentaService<'a>
+{
+    pub fn new(conn: &'a Connection) -> Self
+    {
+        Self { conn }
+    }
+
+    pub fn get_estado_cuenta(&self, id_clienta: i64) -> Result<EstadoCuentaClienta>
+    {
+        let clienta = ClientaService::new(self.conn).get_clienta(id_clienta)?;
+
+        let venta_repo = VentaRepo { conn: self.conn };
+        let ventas = venta_repo.list_with_cobranza_by_clienta(id_clienta)?;
+
+        let abono_service = AbonoVentaService::new(self.conn);
+        let abonos = abono_service.listar_abonos_por_clienta(id_clienta)?;
+
+        // (clave de orden, prioridad, movimiento): a la misma fecha la venta va antes que sus abonos
+        let mut pendientes: Vec<(String, u8, MovimientoEstadoCuenta)> = Vec::new();
+
+        for venta in &ventas {
+            let total = AbonoVentaService::normalize_money(venta.total_venta);
+            let (concepto, abono) = match venta.tipo_pago {
+                TipoPago::Contado => ("Venta de contado", total),
+                TipoPago::Abono => ("Venta a abonos", 0.0),
+            };
+            pendientes.push((
+                Self::sort_key(&venta.fecha),
+                0,
+                MovimientoEstadoCuenta {
+                    fecha: venta.fecha.clone(),
+                    concepto: concepto.to_string(),
+                    id_venta: venta.id_venta,
+                    id_abono: None,
+                    cargo: total,
+                    abono,
+                    saldo: 0.0,
+                },
+            ));
+        }
+
+        for abono in &abonos {
+            pendientes.push((
+                Self::sort_key(&abono.fecha_abono),
+                1,
+                MovimientoEstadoCuenta {
+                    fecha: abono.fecha_abono.clone(),
+                    concepto: "Abono".to_string(),
+                    id_venta: abono.id_venta,
+                    id_abono: Some(abono.id_abono),
+                    cargo: 0.0,
+                    abono: abono.monto_abono,
+                    saldo: 0.0,
+                },
+            ));
+        }
+
+        pendientes.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.cmp(&b.1)));
+
+        let mut saldo = 0.0;
+        let mut total_comprado = 0.0;
+        let mut total_abonado = 0.0;
+        let mut movimientos = Vec::with_capacity(pendientes.len());
+        for (_, _, mut movimiento) in pendientes {
+            saldo = AbonoVentaService::normalize_money(saldo + movimiento.cargo - movimiento.abono);
+            total_comprado += movimiento.cargo;
+            total_abonado += movimiento.abono;
+            movimiento.saldo = saldo;
+            movimientos.push(movimiento);
+        }
+
+        let abiertas: Vec<_> = ventas
+            .iter()
+            .filter(|v| matches!(v.tipo_pago, TipoPago::Abono) && !matches!(v.estado_pago, EstadoPago::Liquidada))
+            .collect();
+        let saldo_total = abiertas.iter().fold(0.0, |acc, v| acc + v.saldo_pendiente);
+
+        Ok(EstadoCuentaClienta {
+            clienta,
+            ventas_abiertas: abiertas.len(),
+            ventas,
+            movimientos,
+            total_comprado: AbonoVentaService::normalize_money(total_comprado),
+            total_abonado: AbonoVentaService::normalize_money(total_abonado),
+            saldo_total: AbonoVentaService::normalize_money(saldo_total),
+        })
+    }
+
+    // Las fechas llegan como "YYYY-MM-DD", "YYYY-MM-DD HH:MM:SS" o ISO con 'T'
+    fn sort_key(fecha: &str) -> String
+    {
+        fecha.replacen('T', " ", 1).trim_end_matches('Z').to_string()
+    }
+}
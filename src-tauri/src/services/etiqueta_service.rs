@@ -0,0 +1,213 @@
+use rusqlite::{Connection, Error, Result};
+use crate::models::{EtiquetaInput, FormatoEtiquetas};
+use crate::pdf::{self, DocumentoPdf, Fuente, PaginaPdf};
+use crate::repos::producto_repo::ProductoRepo;
+use crate::services::business_error;
+
+const PULGADA: f64 = 72.0;
+const MILIMETRO: f64 = 72.0 / 25.4;
+// Hojas por documento; evita reservar memoria sin límite por una cantidad mal escrita
+const MAXIMO_HOJAS: i64 = 300;
+
+// Medidas de la hoja en puntos
+struct Hoja
+{
+    ancho: f64,
+    alto: f64,
+    columnas: usize,
+    filas: usize,
+    margen_izquierdo: f64,
+    margen_superior: f64,
+    ancho_etiqueta: f64,
+    alto_etiqueta: f64,
+    paso_horizontal: f64,
+    paso_vertical: f64,
+}
+
+impl Hoja
+{
+    fn de(formato: FormatoEtiquetas) -> Self
+    {
+        match formato {
+            FormatoEtiquetas::Carta3x10 => Self {
+                ancho: 8.5 * PULGADA,
+                alto: 11.0 * PULGADA,
+                columnas: 3,
+                filas: 10,
+                margen_izquierdo: 0.1875 * PULGADA,
+                margen_superior: 0.5 * PULGADA,
+                ancho_etiqueta: 2.625 * PULGADA,
+                alto_etiqueta: PULGADA,
+                paso_horizontal: 2.75 * PULGADA,
+                paso_vertical: PULGADA,
+            },
+            FormatoEtiquetas::Carta2x10 => Self {
+                ancho: 8.5 * PULGADA,
+                alto: 11.0 * PULGADA,
+                columnas: 2,
+                filas: 10,
+                margen_izquierdo: 0.15625 * PULGADA,
+                margen_superior: 0.5 * PULGADA,
+                ancho_etiqueta: 4.0 * PULGADA,
+                alto_etiqueta: PULGADA,
+                paso_horizontal: 4.1875 * PULGADA,
+                paso_vertical: PULGADA,
+            },
+            FormatoEtiquetas::A4_3x7 => Self {
+                ancho: 210.0 * MILIMETRO,
+                alto: 297.0 * MILIMETRO,
+                columnas: 3,
+                filas: 7,
+                margen_izquierdo: 7.2 * MILIMETRO,
+                margen_superior: 15.15 * MILIMETRO,
+                ancho_etiqueta: 63.5 * MILIMETRO,
+                alto_etiqueta: 38.1 * MILIMETRO,
+                paso_horizontal: 66.0 * MILIMETRO,
+                paso_vertical: 38.1 * MILIMETRO,
+            },
+        }
+    }
+
+    fn por_hoja(&self) -> usize
+    {
+        self.columnas * self.filas
+    }
+
+    // Esquina inferior izquierda de la etiqueta en la posición dada (de izquierda a derecha, de arriba abajo)
+    fn origen(&self, posicion: usize) -> (f64, f64)
+    {
+        let columna = posicion % self.columnas;
+        let fila = posicion / self.columnas;
+        let x = self.margen_izquierdo + columna as f64 * self.paso_horizontal;
+        let y = self.alto - self.margen_superior - fila as f64 * self.paso_vertical - self.alto_etiqueta;
+        (x, y)
+    }
+}
+
+struct Etiqueta
+{
+    nombre: String,
+    precio: String,
+    codigo: String,
+    simbolos: Vec<u8>,
+}
+
+pub struct EtiquetaService<'a>
+{
+    pub conn: &'a Connection,
+}
+
+impl<'a> EtiquetaService<'a>
+{
+    pub fn new(conn: &'a Connection) -> Self
+    {
+        Self { conn }
+    }
+
+    // PDF con las etiquetas de precio en el orden recibido, llenando hojas completas del formato elegido
+    pub fn generar_pdf(&self, items: &[EtiquetaInput], formato: FormatoEtiquetas) -> Result<Vec<u8>>
+    {
+        if items.is_empty() {
+            return Err(business_error("Selecciona al menos un producto para imprimir etiquetas"));
+        }
+
+        let hoja = Hoja::de(formato);
+        let maximo = hoja.por_hoja() as i64 * MAXIMO_HOJAS;
+        let repo = ProductoRepo { conn: self.conn };
+        let mut etiquetas = Vec::new();
+        let mut cantidades = Vec::new();
+        let mut total: i64 = 0;
+        for item in items {
+            if item.cantidad <= 0 {
+                return Err(business_error("La cantidad de etiquetas debe ser mayor a 0"));
+            }
+            total = total.saturating_add(item.cantidad);
+            if total > maximo {
+                return Err(business_error(&format!(
+                    "No se pueden imprimir más de {} etiquetas ({} hojas) a la vez",
+                    maximo, MAXIMO_HOJAS
+                )));
+            }
+
+            let producto = match repo.get(item.id_producto) {
+                Ok(producto) => producto,
+                Err(Error::QueryReturnedNoRows) => {
+                    return Err(business_error(&format!("El producto #{} no existe", item.id_producto)));
+                }
+                Err(err) => return Err(err),
+            };
+
+            let codigo = producto.id_producto.to_string();
+            let simbolos = pdf::code128_simbolos(&codigo)
+                .ok_or_else(|| business_error("No se pudo generar el código de barras"))?;
+            etiquetas.push(Etiqueta {
+                nombre: producto.nombre_producto.trim().to_string(),
+                precio: format!("${:.2}", producto.precio_publico),
+                codigo,
+                simbolos,
+            });
+            cantidades.push(item.cantidad as usize);
+        }
+
+        let mut documento = DocumentoPdf::new(hoja.ancho, hoja.alto);
+        let copias: Vec<&Etiqueta> = etiquetas
+            .iter()
+            .zip(cantidades)
+            .flat_map(|(etiqueta, cantidad)| std::iter::repeat(etiqueta).take(cantidad))
+            .collect();
+
+        for contenido in copias.chunks(hoja.por_hoja()) {
+            let pagina = documento.nueva_pagina();
+            for (posicion, etiqueta) in contenido.iter().enumerate() {
+                let (x, y) = hoja.origen(posicion);
+                dibujar_etiqueta(pagina, etiqueta, x, y, hoja.ancho_etiqueta, hoja.alto_etiqueta);
+            }
+        }
+        Ok(documento.to_bytes())
+    }
+}
+
+// Nombre arriba, precio en negrita y el código de barras con el id legible debajo
+fn dibujar_etiqueta(pagina: &mut PaginaPdf, etiqueta: &Etiqueta, x: f64, y: f64, ancho: f64, alto: f64)
+{
+    let relleno = 4.0;
+    let grande = alto > 90.0;
+    let tamano_nombre = if grande { 9.0 } else { 7.0 };
+    let tamano_precio = if grande { 15.0 } else { 11.0 };
+    let tamano_codigo = 6.0;
+    let ancho_util = ancho - relleno * 2.0;
+
+    let linea_nombre = y + alto - relleno - tamano_nombre;
+    pagina.texto(x + relleno, linea_nombre, tamano_nombre, Fuente::Normal, &recortar(&etiqueta.nombre, ancho_util, tamano_nombre));
+
+    let linea_precio = linea_nombre - tamano_precio - 2.0;
+    pagina.texto(x + relleno, linea_precio, tamano_precio, Fuente::Negrita, &etiqueta.precio);
+
+    // Código centrado; el módulo se achica si el código no cabe a 1 punto
+    let (barras, modulos) = pdf::code128_barras(&etiqueta.simbolos);
+    let modulos_totales = modulos + pdf::CODE128_ZONA_SILENCIO * 2;
+    let modulo = (ancho_util / modulos_totales as f64).min(1.0);
+    let inicio = x + (ancho - modulos as f64 * modulo) / 2.0;
+    let base = y + relleno + tamano_codigo + 1.0;
+    let alto_barras = (linea_precio - 4.0 - base).max(8.0);
+    for (posicion, grosor) in barras {
+        pagina.rectangulo(inicio + posicion as f64 * modulo, base, grosor as f64 * modulo, alto_barras);
+    }
+
+    let ancho_codigo = pdf::ancho_texto(&etiqueta.codigo, tamano_codigo);
+    pagina.texto(x + (ancho - ancho_codigo) / 2.0, y + relleno, tamano_codigo, Fuente::Normal, &etiqueta.codigo);
+}
+
+// Corta el nombre con "..." cuando no cabe en una línea
+fn recortar(texto: &str, ancho: f64, tamano: f64) -> String
+{
+    if pdf::ancho_texto(texto, tamano) <= ancho {
+        return texto.to_string();
+    }
+
+    let mut recortado: String = texto.chars().collect();
+    while !recortado.is_empty() && pdf::ancho_texto(&format!("{}...", recortado), tamano) > ancho {
+        recortado.pop();
+    }
+    format!("{}...", recortado.trim_end())
+}
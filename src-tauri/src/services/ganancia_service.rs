@@ -0,0 +1,60 @@
+use rusqlite::{Connection, Result};
+use crate::models::ReporteGanancias;
+use crate::repos::ganancia_repo::GananciaRepo;
+use crate::services::abono_venta_service::AbonoVentaService;
+use crate::services::business_error;
+
+pub struct GananciaService<'a>
+{
+    pub conn: &'a Connection,
+}
+
+impl<'a> GananciaService<'a>
+{
+    pub fn new(conn: &'a Connection) -> Self
+    {
+        Self { conn }
+    }
+
+    // Ganancia por venta, por producto y total del periodo usando el costo congelado en cada venta
+    pub fn get_reporte(&self, fecha_inicio: &str, fecha_fin: &str, id_campana: Option<i64>) -> Result<ReporteGanancias>
+    {
+        let fecha_inicio = fecha_inicio.trim();
+        let fecha_fin = fecha_fin.trim();
+        if fecha_inicio.is_empty() || fecha_fin.is_empty() {
+            return Err(business_error("Debe indicar la fecha de inicio y fin del periodo"));
+        }
+        if fecha_inicio > fecha_fin {
+            return Err(business_error("La fecha de inicio no puede ser posterior a la fecha fin"));
+        }
+
+        let repo = GananciaRepo { conn: self.conn };
+        let por_venta = repo.por_venta(fecha_inicio, fecha_fin, id_campana)?;
+        let por_producto = repo.por_producto(fecha_inicio, fecha_fin, id_campana)?;
+
+        let bruto = AbonoVentaService::normalize_money(por_venta.iter().map(|v| v.bruto).sum());
+        let descuento = AbonoVentaService::normalize_money(por_venta.iter().map(|v| v.descuento).sum());
+        let ingreso = AbonoVentaService::normalize_money(por_venta.iter().map(|v| v.ingreso).sum());
+        let costo = AbonoVentaService::normalize_money(por_venta.iter().map(|v| v.costo).sum());
+        let ganancia = AbonoVentaService::normalize_money(ingreso - costo);
+        let margen_porcentaje = if ingreso > 0.0 {
+            AbonoVentaService::normalize_money(ganancia / ingreso * 100.0)
+        } else {
+            0.0
+        };
+
+        Ok(ReporteGanancias {
+            fecha_inicio: fecha_inicio.to_string(),
+            fecha_fin: fecha_fin.to_string(),
+            id_campana,
+            bruto,
+            descuento,
+            ingreso,
+            costo,
+            ganancia,
+            margen_porcentaje,
+            por_venta,
+            por_producto,
+        })
+    }
+}
@@ -0,0 +1,54 @@
+use rusqlite::{Connection, Result};
+use crate::models::{CampoPrecio, HistorialPrecio, OrigenCambioPrecio, Producto};
+use crate::repos::historial_precio_repo::HistorialPrecioRepo;
+use crate::repos::producto_repo::ProductoRepo;
+use crate::services::business_error;
+
+pub struct HistorialPrecioService<'a>
+{
+    pub conn: &'a Connection,
+}
+
+impl<'a> HistorialPrecioService<'a>
+{
+    pub fn new(conn: &'a Connection) -> Self
+    {
+        Self { conn }
+    }
+
+    // Precios iniciales del producto (sin precio anterior)
+    pub fn registrar_alta(&self, producto: &Producto) -> Result<()>
+    {
+        let repo = HistorialPrecioRepo { conn: self.conn };
+        repo.create(producto.id_producto, CampoPrecio::PrecioConsultora, None, producto.precio_consultora, OrigenCambioPrecio::Alta)?;
+        repo.create(producto.id_producto, CampoPrecio::PrecioPublico, None, producto.precio_publico, OrigenCambioPrecio::Alta)?;
+        Ok(())
+    }
+
+    // Registra solo los precios que realmente cambiaron entre ambas versiones del producto
+    pub fn registrar_cambios(&self, anterior: &Producto, actual: &Producto, origen: OrigenCambioPrecio) -> Result<()>
+    {
+        let repo = HistorialPrecioRepo { conn: self.conn };
+        let cambios = [
+            (CampoPrecio::PrecioConsultora, anterior.precio_consultora, actual.precio_consultora),
+            (CampoPrecio::PrecioPublico, anterior.precio_publico, actual.precio_publico),
+        ];
+
+        for (campo, precio_anterior, precio_nuevo) in cambios {
+            if (precio_anterior - precio_nuevo).abs() > f64::EPSILON {
+                repo.create(actual.id_producto, campo, Some(precio_anterior), precio_nuevo, origen)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn listar_por_producto(&self, id_producto: i64) -> Result<Vec<HistorialPrecio>>
+    {
+        if let Err(rusqlite::Error::QueryReturnedNoRows) = (ProductoRepo { conn: self.conn }).get(id_producto) {
+            return Err(business_error("El producto indicado no existe"));
+        }
+
+        let repo = HistorialPrecioRepo { conn: self.conn };
+        repo.list_by_producto(id_producto)
+    }
+}
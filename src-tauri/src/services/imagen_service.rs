@@ -0,0 +1,177 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use rusqlite::{Connection, Result};
+use crate::imagen;
+use crate::models::{ArchivoImagen, ImagenFallida, LimpiezaImagenes, RegeneracionMiniaturas, ReporteImagenes};
+use crate::repos::perfil_repo::PerfilRepo;
+use crate::repos::producto_repo::ProductoRepo;
+use crate::services::business_error;
+
+pub struct ImagenService<'a>
+{
+    pub conn: &'a Connection,
+}
+
+impl<'a> ImagenService<'a>
+{
+    pub fn new(conn: &'a Connection) -> Self
+    {
+        Self { conn }
+    }
+
+    // Vuelve a generar la miniatura de cada producto (y del perfil) a partir de su imagen guardada;
+    // las imágenes que no se pueden leer se reportan sin detener el proceso
+    pub fn regenerar_miniaturas(&self) -> Result<RegeneracionMiniaturas>
+    {
+        let mut reporte = RegeneracionMiniaturas {
+            actualizadas: 0,
+            fallidas: Vec::new(),
+        };
+
+        let productos = ProductoRepo { conn: self.conn };
+        for (id_producto, nombre, ruta) in productos.list_con_imagen()? {
+            match imagen::miniatura_de_archivo(Path::new(&ruta)) {
+                Ok(miniatura) => {
+                    productos.update_miniatura(id_producto, Some(&miniatura))?;
+                    reporte.actualizadas += 1;
+                }
+                Err(error) => reporte.fallidas.push(ImagenFallida {
+                    id_producto: Some(id_producto),
+                    nombre,
+                    ruta,
+                    error,
+                }),
+            }
+        }
+
+        let perfiles = PerfilRepo { conn: self.conn };
+        if let Some(perfil) = perfiles.get()? {
+            if let Some(ruta) = perfil.ruta_foto.filter(|r| !r.trim().is_empty()) {
+                match imagen::miniatura_de_archivo(Path::new(&ruta)) {
+                    Ok(miniatura) => {
+                        perfiles.update_miniatura(Some(&miniatura))?;
+                        reporte.actualizadas += 1;
+                    }
+                    Err(error) => reporte.fallidas.push(ImagenFallida {
+                        id_producto: None,
+                        nombre: perfil.nombre,
+                        ruta,
+                        error,
+                    }),
+                }
+            }
+        }
+
+        Ok(reporte)
+    }
+
+    // Uso de la carpeta de imágenes: archivos que nadie referencia y filas que apuntan a un archivo inexistente
+    pub fn reporte_almacenamiento(&self, carpeta: &Path) -> Result<ReporteImagenes>
+    {
+        let archivos = listar_archivos(carpeta)?;
+        let referenciadas = self.referenciadas()?;
+
+        let mut reporte = ReporteImagenes {
+            total_archivos: archivos.len() as i64,
+            bytes_totales: archivos.iter().map(|archivo| archivo.bytes).sum(),
+            huerfanas: Vec::new(),
+            bytes_huerfanas: 0,
+            faltantes: Vec::new(),
+        };
+        for archivo in archivos {
+            if !referenciadas.iter().any(|(_, _, ruta)| *ruta == archivo.nombre) {
+                reporte.bytes_huerfanas += archivo.bytes;
+                reporte.huerfanas.push(archivo);
+            }
+        }
+        for (id_producto, nombre, ruta) in referenciadas {
+            let completa = imagen::ruta_completa(carpeta, &ruta);
+            if !completa.is_file() {
+                reporte.faltantes.push(ImagenFallida {
+                    id_producto,
+                    nombre,
+                    ruta: completa.to_string_lossy().to_string(),
+                    error: "El archivo no existe".to_string(),
+                });
+            }
+        }
+
+        Ok(reporte)
+    }
+
+    // Borra los archivos confirmados por el usuario; se vuelve a revisar que sigan sin usarse
+    // por si se asignaron a un producto después de generar el reporte
+    pub fn limpiar_huerfanas(&self, carpeta: &Path, nombres: &[String]) -> Result<LimpiezaImagenes>
+    {
+        let en_uso: HashSet<String> = self.referenciadas()?.into_iter().map(|(_, _, ruta)| ruta).collect();
+        let huerfanas: Vec<ArchivoImagen> = listar_archivos(carpeta)?
+            .into_iter()
+            .filter(|archivo| !en_uso.contains(&archivo.nombre))
+            .collect();
+
+        let mut limpieza = LimpiezaImagenes {
+            eliminadas: 0,
+            bytes_liberados: 0,
+        };
+        for nombre in nombres {
+            let nombre = imagen::ruta_relativa(nombre);
+            if let Some(archivo) = huerfanas.iter().find(|archivo| archivo.nombre == nombre) {
+                fs::remove_file(&archivo.ruta).map_err(|e| {
+                    business_error(&format!("No se pudo eliminar '{}': {}", archivo.nombre, e))
+                })?;
+                limpieza.eliminadas += 1;
+                limpieza.bytes_liberados += archivo.bytes;
+            }
+        }
+        Ok(limpieza)
+    }
+
+    // (id_producto, nombre, ruta relativa) de cada imagen en uso; id None es la foto del perfil
+    fn referenciadas(&self) -> Result<Vec<(Option<i64>, String, String)>>
+    {
+        let mut referenciadas: Vec<(Option<i64>, String, String)> = ProductoRepo { conn: self.conn }
+            .list_con_imagen()?
+            .into_iter()
+            .map(|(id_producto, nombre, ruta)| (Some(id_producto), nombre, imagen::ruta_relativa(&ruta)))
+            .collect();
+        if let Some(perfil) = (PerfilRepo { conn: self.conn }).get()? {
+            if let Some(ruta) = perfil.ruta_foto.filter(|r| !r.trim().is_empty()) {
+                referenciadas.push((None, perfil.nombre, imagen::ruta_relativa(&ruta)));
+            }
+        }
+        Ok(referenciadas)
+    }
+}
+
+// Archivos de la carpeta (y subcarpetas) con su ruta relativa; si la carpeta no existe no hay archivos
+fn listar_archivos(carpeta: &Path) -> Result<Vec<ArchivoImagen>>
+{
+    let mut archivos = Vec::new();
+    let mut pendientes = vec![(carpeta.to_path_buf(), String::new())];
+    while let Some((dir, prefijo)) = pendientes.pop() {
+        let entradas = match fs::read_dir(&dir) {
+            Ok(entradas) => entradas,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(business_error(&format!("No se pudo leer la carpeta de imágenes: {}", e))),
+        };
+        for entrada in entradas.flatten() {
+            let nombre = format!("{}{}", prefijo, entrada.file_name().to_string_lossy());
+            let metadata = match entrada.metadata() {
+                Ok(metadata) => metadata,
+                Err(_) => continue,
+            };
+            if metadata.is_dir() {
+                pendientes.push((entrada.path(), format!("{}/", nombre)));
+            } else if metadata.is_file() {
+                archivos.push(ArchivoImagen {
+                    nombre,
+                    ruta: entrada.path().to_string_lossy().to_string(),
+                    bytes: metadata.len() as i64,
+                });
+            }
+        }
+    }
+    archivos.sort_by(|a, b| a.nombre.cmp(&b.nombre));
+    Ok(archivos)
+}
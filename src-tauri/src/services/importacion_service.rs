@@ -0,0 +1,389 @@
+use rusqlite::{Connection, Result};
+use crate::importacion::TablaImportacion;
+use crate::models::{AccionImportacion, FilaImportacion, MotivoAjuste, OpcionesImportacion, Producto, ResultadoImportacion};
+use crate::repos::categoria_repo::CategoriaRepo;
+use crate::repos::producto_repo::ProductoRepo;
+use crate::services::business_error;
+use crate::services::categoria_service::CategoriaService;
+use crate::services::producto_service::ProductoService;
+
+// Encabezados que se reconocen para cada campo cuando no se indica el mapeo
+const ENCABEZADOS_NOMBRE: &[&str] = &["nombre", "nombre_producto", "producto"];
+const ENCABEZADOS_CATEGORIA: &[&str] = &["categoria"];
+const ENCABEZADOS_STOCK: &[&str] = &["stock", "existencia", "existencias", "cantidad"];
+const ENCABEZADOS_PRECIO_CONSULTORA: &[&str] = &["precio_consultora", "costo"];
+const ENCABEZADOS_PRECIO_PUBLICO: &[&str] = &["precio_publico", "precio"];
+const ENCABEZADOS_SKU: &[&str] = &["sku", "codigo", "clave"];
+const ENCABEZADOS_CODIGO_BARRAS: &[&str] = &["codigo_barras", "ean", "upc"];
+
+const NOTA_AJUSTE: &str = "Importación de productos";
+
+// Índice de cada campo en las filas del archivo
+struct Columnas
+{
+    nombre: usize,
+    categoria: Option<usize>,
+    stock: Option<usize>,
+    precio_consultora: Option<usize>,
+    precio_publico: Option<usize>,
+    sku: Option<usize>,
+    codigo_barras: Option<usize>,
+}
+
+// Valores de una fila ya convertidos; None si la celda está vacía
+struct FilaProducto<'t>
+{
+    nombre: &'t str,
+    categoria: Option<&'t str>,
+    stock: Option<i64>,
+    precio_consultora: Option<f64>,
+    precio_publico: Option<f64>,
+    sku: Option<&'t str>,
+    codigo_barras: Option<&'t str>,
+}
+
+pub struct ImportacionService<'a>
+{
+    pub conn: &'a Connection,
+}
+
+impl<'a> ImportacionService<'a>
+{
+    pub fn new(conn: &'a Connection) -> Self
+    {
+        Self { conn }
+    }
+
+    // Valida y aplica cada fila con las reglas de ProductoService. Debe ejecutarse dentro de una
+    // transacción: la vista previa la descarta y la importación solo la confirma si no hubo errores.
+    // Cada fila corre en su propio savepoint para que una fila con error no deje cambios a medias
+    pub fn importar_productos(&self, tabla: &TablaImportacion, opciones: &OpcionesImportacion) -> Result<ResultadoImportacion>
+    {
+        let columnas = Self::resolver_columnas(&tabla.encabezados, opciones)?;
+        if tabla.filas.is_empty() {
+            return Err(business_error("El archivo no tiene productos debajo de los encabezados"));
+        }
+
+        let mut resultado = ResultadoImportacion {
+            filas: Vec::new(),
+            creados: 0,
+            actualizados: 0,
+            omitidos: 0,
+            con_errores: 0,
+            categorias_creadas: Vec::new(),
+            aplicado: false,
+        };
+
+        for (numero, celdas) in &tabla.filas {
+            let celda = |indice: Option<usize>| {
+                indice
+                    .and_then(|indice| celdas.get(indice))
+                    .map(|celda| celda.trim())
+                    .filter(|celda| !celda.is_empty())
+            };
+            let nombre_producto = celda(Some(columnas.nombre)).unwrap_or_default().to_string();
+
+            let mut categorias_fila = Vec::new();
+            self.conn.execute_batch("SAVEPOINT fila_importacion")?;
+            let nota = match tabla.archivo.as_deref() {
+                Some(archivo) => format!("{}: {}, fila {}", NOTA_AJUSTE, archivo, numero),
+                None => format!("{}, fila {}", NOTA_AJUSTE, numero),
+            };
+            let aplicada = Self::leer_fila(&columnas, &celda)
+                .and_then(|fila| self.importar_fila(&fila, &nota, opciones, &mut categorias_fila));
+
+            let fila = match aplicada {
+                Ok((accion, id_producto)) => {
+                    self.conn.execute_batch("RELEASE fila_importacion")?;
+                    match accion {
+                        AccionImportacion::Crear => resultado.creados += 1,
+                        AccionImportacion::Actualizar => resultado.actualizados += 1,
+                        _ => resultado.omitidos += 1,
+                    }
+                    resultado.categorias_creadas.append(&mut categorias_fila);
+                    FilaImportacion { fila: *numero as i64, nombre_producto, accion, id_producto, errores: Vec::new() }
+                }
+                Err(errores) => {
+                    self.conn.execute_batch("ROLLBACK TO fila_importacion; RELEASE fila_importacion")?;
+                    resultado.con_errores += 1;
+                    FilaImportacion {
+                        fila: *numero as i64,
+                        nombre_producto,
+                        accion: AccionImportacion::Error,
+                        id_producto: None,
+                        errores,
+                    }
+                }
+            };
+            resultado.filas.push(fila);
+        }
+
+        Ok(resultado)
+    }
+
+    fn resolver_columnas(encabezados: &[String], opciones: &OpcionesImportacion) -> Result<Columnas>
+    {
+        let normalizados: Vec<String> = encabezados.iter().map(|encabezado| Self::normalizar_encabezado(encabezado)).collect();
+        let buscar = |mapeo: &Option<String>, conocidos: &[&str]| -> Result<Option<usize>> {
+            match mapeo.as_deref().map(str::trim).filter(|mapeo| !mapeo.is_empty()) {
+                Some(mapeo) => {
+                    let buscado = Self::normalizar_encabezado(mapeo);
+                    match normalizados.iter().position(|encabezado| *encabezado == buscado) {
+                        Some(indice) => Ok(Some(indice)),
+                        None => Err(business_error(&format!("El archivo no tiene la columna '{}'", mapeo))),
+                    }
+                }
+                None => Ok(conocidos
+                    .iter()
+                    .find_map(|conocido| normalizados.iter().position(|encabezado| encabezado == conocido))),
+            }
+        };
+
+        let mapeo = &opciones.columnas;
+        let nombre = buscar(&mapeo.nombre, ENCABEZADOS_NOMBRE)?
+            .ok_or_else(|| business_error("Indica qué columna del archivo tiene el nombre del producto"))?;
+        Ok(Columnas {
+            nombre,
+            categoria: buscar(&mapeo.categoria, ENCABEZADOS_CATEGORIA)?,
+            stock: buscar(&mapeo.stock, ENCABEZADOS_STOCK)?,
+            precio_consultora: buscar(&mapeo.precio_consultora, ENCABEZADOS_PRECIO_CONSULTORA)?,
+            precio_publico: buscar(&mapeo.precio_publico, ENCABEZADOS_PRECIO_PUBLICO)?,
+            sku: buscar(&mapeo.sku, ENCABEZADOS_SKU)?,
+            codigo_barras: buscar(&mapeo.codigo_barras, ENCABEZADOS_CODIGO_BARRAS)?,
+        })
+    }
+
+    // "Precio Público" y "precio_publico" se tratan como el mismo encabezado
+    fn normalizar_encabezado(encabezado: &str) -> String
+    {
+        encabezado
+            .trim()
+            .to_lowercase()
+            .chars()
+            .map(|c| match c {
+                'á' | 'à' | 'ä' => 'a',
+                'é' | 'è' | 'ë' => 'e',
+                'í' | 'ì' | 'ï' => 'i',
+                'ó' | 'ò' | 'ö' => 'o',
+                'ú' | 'ù' | 'ü' => 'u',
+                'ñ' => 'n',
+                ' ' | '-' | '.' => '_',
+                otro => otro,
+            })
+            .collect()
+    }
+
+    // Convierte las celdas y junta todos los errores de formato de la fila
+    fn leer_fila<'t>(
+        columnas: &Columnas,
+        celda: &dyn Fn(Option<usize>) -> Option<&'t str>,
+    ) -> std::result::Result<FilaProducto<'t>, Vec<String>>
+    {
+        let mut errores = Vec::new();
+        let nombre = celda(Some(columnas.nombre));
+        if nombre.is_none() {
+            errores.push("Falta el nombre del producto".to_string());
+        }
+
+        let stock = match celda(columnas.stock).map(|texto| (texto, Self::leer_numero(texto))) {
+            None => None,
+            Some((_, Some(stock))) if stock.fract() == 0.0 => Some(stock as i64),
+            Some((texto, _)) => {
+                errores.push(format!("El stock '{}' no es un número entero", texto));
+                None
+            }
+        };
+        let mut precio = |indice: Option<usize>, campo: &str| match celda(indice) {
+            None => None,
+            Some(texto) => {
+                let valor = Self::leer_numero(texto);
+                if valor.is_none() {
+                    errores.push(format!("El {} '{}' no es un número", campo, texto));
+                }
+                valor
+            }
+        };
+        let precio_consultora = precio(columnas.precio_consultora, "precio de consultora");
+        let precio_publico = precio(columnas.precio_publico, "precio público");
+
+        if !errores.is_empty() {
+            return Err(errores);
+        }
+        Ok(FilaProducto {
+            nombre: nombre.unwrap_or_default(),
+            categoria: celda(columnas.categoria),
+            stock,
+            precio_consultora,
+            precio_publico,
+            sku: celda(columnas.sku),
+            codigo_barras: celda(columnas.codigo_barras),
+        })
+    }
+
+    // Acepta "$1,250.50", "1250.5" y "1250,50" (coma decimal de los CSV separados por punto y coma)
+    fn leer_numero(texto: &str) -> Option<f64>
+    {
+        let limpio: String = texto.chars().filter(|c| !c.is_whitespace() && *c != '$').collect();
+        let coma_decimal = !limpio.contains('.')
+            && limpio
+                .rsplit_once(',')
+                .is_some_and(|(_, decimales)| (1..=2).contains(&decimales.len()));
+        let limpio = if coma_decimal {
+            limpio.replace(',', ".")
+        } else {
+            limpio.replace(',', "")
+        };
+        limpio.parse::<f64>().ok().filter(|valor| valor.is_finite())
+    }
+
+    fn importar_fila(
+        &self,
+        fila: &FilaProducto,
+        nota: &str,
+        opciones: &OpcionesImportacion,
+        categorias_creadas: &mut Vec<String>,
+    ) -> std::result::Result<(AccionImportacion, Option<i64>), Vec<String>>
+    {
+        let mensaje = |err: rusqlite::Error| vec![Self::mensaje_error(err)];
+
+        let existente = self.buscar_existente(fila).map_err(mensaje)?;
+        if let Some(existente) = &existente {
+            if !opciones.actualizar_existentes {
+                return Ok((AccionImportacion::Omitir, Some(existente.id_producto)));
+            }
+            // Actualizarlo lo dejaría oculto en los listados aunque el reporte diga "Actualizar"
+            if existente.activo != 1 {
+                return Err(vec![format!(
+                    "'{}' coincide con un producto descontinuado; reactívalo antes de importarlo",
+                    existente.nombre_producto
+                )]);
+            }
+        }
+
+        let id_categoria = match fila.categoria {
+            Some(ruta) => Some(self.obtener_o_crear_categoria(ruta, categorias_creadas).map_err(mensaje)?),
+            None => None,
+        };
+
+        match existente {
+            Some(existente) => self
+                .actualizar_producto(existente, fila, id_categoria, nota)
+                .map(|id| (AccionImportacion::Actualizar, Some(id)))
+                .map_err(mensaje),
+            None => self
+                .crear_producto(fila, id_categoria)
+                .map(|id| (AccionImportacion::Crear, Some(id)))
+                .map_err(mensaje),
+        }
+    }
+
+    // Coincidencia por SKU, luego por código de barras y al final por nombre
+    fn buscar_existente(&self, fila: &FilaProducto) -> Result<Option<Producto>>
+    {
+        let repo = ProductoRepo { conn: self.conn };
+        if let Some(sku) = fila.sku {
+            if let Some(producto) = repo.find_by_sku(sku, None)? {
+                return Ok(Some(producto));
+            }
+        }
+        if let Some(codigo) = fila.codigo_barras {
+            let codigo: String = codigo.chars().filter(|c| !c.is_whitespace() && *c != '-').collect();
+            if let Some(producto) = repo.find_by_codigo_barras(&codigo, None)? {
+                return Ok(Some(producto));
+            }
+        }
+        repo.find_by_nombre(fila.nombre)
+    }
+
+    // "Rostro > Ojos" busca cada nivel por nombre y crea los que falten colgando del anterior
+    fn obtener_o_crear_categoria(&self, ruta: &str, categorias_creadas: &mut Vec<String>) -> Result<i64>
+    {
+        let repo = CategoriaRepo { conn: self.conn };
+        let categorias = CategoriaService::new(self.conn);
+        let mut id_padre: Option<i64> = None;
+        for nombre in ruta.split('>').map(str::trim).filter(|nombre| !nombre.is_empty()) {
+            let id = match repo.find_by_nombre(nombre, None)? {
+                Some(categoria) => categoria.id_categoria,
+                None => {
+                    let id = match id_padre {
+                        Some(id_padre) => categorias.create_subcategoria(nombre, id_padre)?,
+                        None => categorias.create_categoria(nombre)?,
+                    };
+                    categorias_creadas.push(nombre.to_string());
+                    id
+                }
+            };
+            id_padre = Some(id);
+        }
+        id_padre.ok_or_else(|| business_error(&format!("La categoría '{}' no es válida", ruta)))
+    }
+
+    fn crear_producto(&self, fila: &FilaProducto, id_categoria: Option<i64>) -> Result<i64>
+    {
+        // Con un solo precio se usa para ambos, igual que al editar un producto
+        let (precio_consultora, precio_publico) = match (fila.precio_consultora, fila.precio_publico) {
+            (Some(consultora), Some(publico)) => (consultora, publico),
+            (Some(consultora), None) => (consultora, consultora),
+            (None, Some(publico)) => (publico, publico),
+            (None, None) => return Err(business_error("Falta el precio del producto")),
+        };
+
+        let productos = ProductoService::new(self.conn);
+        let id = productos.create_producto_with_prices(
+            fila.nombre,
+            id_categoria,
+            None,
+            None,
+            fila.stock.unwrap_or(0),
+            precio_consultora,
+            precio_publico,
+        )?;
+        if fila.sku.is_some() || fila.codigo_barras.is_some() {
+            productos.set_codigos(id, fila.sku, fila.codigo_barras)?;
+        }
+        Ok(id)
+    }
+
+    // Solo cambia lo que trae la fila; el stock se corrige con un ajuste de importación anotado con archivo y fila
+    fn actualizar_producto(&self, existente: Producto, fila: &FilaProducto, id_categoria: Option<i64>, nota: &str) -> Result<i64>
+    {
+        let productos = ProductoService::new(self.conn);
+        let id_producto = existente.id_producto;
+        let stock_actual = existente.stock;
+
+        let mut producto = Producto {
+            nombre_producto: fila.nombre.to_string(),
+            id_categoria: id_categoria.or(existente.id_categoria),
+            sku: fila.sku.map(str::to_string).or_else(|| existente.sku.clone()),
+            codigo_barras: fila.codigo_barras.map(str::to_string).or_else(|| existente.codigo_barras.clone()),
+            ..existente
+        };
+        if let Some(precio_consultora) = fila.precio_consultora {
+            producto.precio_consultora = precio_consultora;
+        }
+        if let Some(precio_publico) = fila.precio_publico {
+            producto.precio_publico = precio_publico;
+            producto.precio = precio_publico;
+        }
+        productos.update_producto(&producto)?;
+
+        if let Some(stock) = fila.stock {
+            if stock < 0 {
+                return Err(business_error("stock no puede ser negativo"));
+            }
+            if stock != stock_actual {
+                productos.ajustar_stock(id_producto, stock - stock_actual, MotivoAjuste::Importacion, nota)?;
+            }
+        }
+        Ok(id_producto)
+    }
+
+    // Mensaje legible de los errores de negocio (`business_error`) y de las restricciones de SQLite
+    fn mensaje_error(err: rusqlite::Error) -> String
+    {
+        match err {
+            rusqlite::Error::SqliteFailure(_, Some(mensaje)) => mensaje,
+            otro => otro.to_string(),
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use rusqlite::{Connection, Error, Result};
+use crate::models::{KitComponente, KitComponenteInput, Producto, ReporteKits};
+use crate::repos::kit_repo::KitRepo;
+use crate::repos::producto_repo::ProductoRepo;
+use crate::services::business_error;
+
+pub struct KitService<'a>
+{
+    pub conn: &'a Connection,
+}
+
+impl<'a> KitService<'a>
+{
+    pub fn new(conn: &'a Connection) -> Self
+    {
+        Self { conn }
+    }
+
+    pub fn list_componentes(&self, id_kit: i64) -> Result<Vec<KitComponente>>
+    {
+        let repo = KitRepo { conn: self.conn };
+        repo.list_componentes(id_kit)
+    }
+
+    // Define (o reemplaza) los componentes del kit; debe ejecutarse dentro de una transacción.
+    // Un producto solo se convierte en kit si no tiene stock propio ni ventas registradas
+    pub fn set_componentes(&self, id_kit: i64, componentes: &[KitComponenteInput]) -> Result<()>
+    {
+        let kit = self.get_producto(id_kit, "El kit indicado no existe")?;
+        if kit.activo != 1 {
+            return Err(business_error("No se puede armar un kit con un producto descontinuado"));
+        }
+        if componentes.is_empty() {
+            return Err(business_error("El kit debe tener al menos un componente"));
+        }
+
+        let repo = KitRepo { conn: self.conn };
+        if !kit.es_kit && (kit.stock != 0 || repo.count_ventas(id_kit)? > 0) {
+            return Err(business_error(&format!(
+                "'{}' ya tiene stock o ventas propias; crea un producto nuevo para el kit",
+                kit.nombre_producto
+            )));
+        }
+
+        for (i, componente) in componentes.iter().enumerate() {
+            if componente.cantidad <= 0 {
+                return Err(business_error("La cantidad de cada componente debe ser mayor a 0"));
+            }
+            if componente.id_producto == id_kit {
+                return Err(business_error("Un kit no puede contenerse a sí mismo"));
+            }
+            if componentes[..i].iter().any(|c| c.id_producto == componente.id_producto) {
+                return Err(business_error("Un componente aparece más de una vez en el kit"));
+            }
+
+            let producto = self.get_producto(
+                componente.id_producto,
+                &format!("Producto con ID {} no encontrado", componente.id_producto),
+            )?;
+            if producto.es_kit {
+                return Err(business_error(&format!(
+                    "'{}' es un kit y no puede ser componente de otro kit",
+                    producto.nombre_producto
+                )));
+            }
+            if producto.activo != 1 {
+                return Err(business_error(&format!(
+                    "'{}' está descontinuado y no puede ser componente",
+                    producto.nombre_producto
+                )));
+            }
+        }
+
+        repo.set_es_kit(id_kit, true)?;
+        repo.replace_componentes(id_kit, componentes)?;
+        repo.recalcular_stock(id_kit)?;
+
+        // Referencia de costo; cada venta congela el costo de los componentes en ese momento
+        let costo = repo.costo_componentes(id_kit)?;
+        ProductoRepo { conn: self.conn }.update_costo_promedio(id_kit, costo)
+    }
+
+    // Regresa el kit a producto normal; solo si nunca se vendió como kit
+    pub fn deshacer_kit(&self, id_kit: i64) -> Result<()>
+    {
+        let kit = self.get_producto(id_kit, "El kit indicado no existe")?;
+        if !kit.es_kit {
+            return Err(business_error("El producto indicado no es un kit"));
+        }
+
+        let repo = KitRepo { conn: self.conn };
+        if repo.count_ventas(id_kit)? > 0 {
+            return Err(business_error("El kit ya tiene ventas; descontinúalo en lugar de deshacerlo"));
+        }
+
+        repo.replace_componentes(id_kit, &[])?;
+        repo.set_es_kit(id_kit, false)?;
+        repo.recalcular_stock(id_kit)
+    }
+
+    // Verifica que alcance el stock para los (id_producto, cantidad) de una venta,
+    // sumando lo que piden los kits a lo que se vende suelto de cada componente
+    pub fn validar_stock_venta(&self, items: &[(i64, i64)]) -> Result<()>
+    {
+        let repo = KitRepo { conn: self.conn };
+        let mut requeridos: Vec<(i64, i64)> = Vec::new();
+        let mut agregar = |id_producto: i64, cantidad: i64| {
+            match requeridos.iter_mut().find(|(id, _)| *id == id_producto) {
+                Some((_, total)) => *total += cantidad,
+                None => requeridos.push((id_producto, cantidad)),
+            }
+        };
+
+        for &(id_producto, cantidad) in items {
+            let producto = self.get_producto(id_producto, &format!("Producto con ID {} no encontrado", id_producto))?;
+            if producto.es_kit {
+                for componente in repo.list_componentes(id_producto)? {
+                    agregar(componente.id_componente, componente.cantidad * cantidad);
+                }
+            } else {
+                agregar(id_producto, cantidad);
+            }
+        }
+
+        let productos = ProductoRepo { conn: self.conn };
+        for (id_producto, cantidad) in requeridos {
+            let producto = productos.get(id_producto)?;
+            if producto.stock < cantidad {
+                return Err(business_error(&format!(
+                    "Stock insuficiente para '{}'. Disponible: {}, Solicitado: {}",
+                    producto.nombre_producto, producto.stock, cantidad
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    // Kits vendidos y lo que consumieron de cada componente en el periodo
+    pub fn get_reporte(&self, fecha_inicio: &str, fecha_fin: &str) -> Result<ReporteKits>
+    {
+        let fecha_inicio = fecha_inicio.trim();
+        let fecha_fin = fecha_fin.trim();
+        if fecha_inicio.is_empty() || fecha_fin.is_empty() {
+            return Err(business_error("Debe indicar la fecha de inicio y fin del periodo"));
+        }
+        if fecha_inicio > fecha_fin {
+            return Err(business_error("La fecha de inicio no puede ser posterior a la fecha fin"));
+        }
+
+        let repo = KitRepo { conn: self.conn };
+        Ok(ReporteKits {
+            fecha_inicio: fecha_inicio.to_string(),
+            fecha_fin: fecha_fin.to_string(),
+            kits: repo.kits_vendidos(fecha_inicio, fecha_fin)?,
+            consumos: repo.consumos(fecha_inicio, fecha_fin)?,
+        })
+    }
+
+    fn get_producto(&self, id_producto: i64, mensaje: &str) -> Result<Producto>
+    {
+        match (ProductoRepo { conn: self.conn }).get(id_producto) {
+            Ok(producto) => Ok(producto),
+            Err(Error::QueryReturnedNoRows) => Err(business_error(mensaje)),
+            Err(err) => Err(err),
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use chrono::NaiveDate;
+use rusqlite::{Connection, Result};
+use crate::models::{Lote, MotivoAjuste};
+use crate::repos::lote_repo::LoteRepo;
+use crate::repos::producto_repo::ProductoRepo;
+use crate::services::business_error;
+use crate::services::movimiento_inventario_service::MovimientoInventarioService;
+
+pub struct LoteService<'a>
+{
+    pub conn: &'a Connection,
+}
+
+impl<'a> LoteService<'a>
+{
+    pub fn new(conn: &'a Connection) -> Self
+    {
+        Self { conn }
+    }
+
+    pub fn listar_por_producto(&self, id_producto: i64) -> Result<Vec<Lote>>
+    {
+        let repo = LoteRepo { conn: self.conn };
+        repo.list_by_producto(id_producto)
+    }
+
+    pub fn listar_por_vencer(&self, dias: i64) -> Result<Vec<Lote>>
+    {
+        if dias < 0 {
+            return Err(business_error("Los días para vencer no pueden ser negativos"));
+        }
+
+        let repo = LoteRepo { conn: self.conn };
+        repo.list_por_vencer(dias)
+    }
+
+    // Suma existencia al lote (lo crea si no existe); el stock del producto lo actualiza quien llama
+    pub fn registrar_entrada(
+        &self,
+        id_producto: i64,
+        cantidad: i64,
+        codigo_lote: Option<&str>,
+        fecha_caducidad: Option<&str>,
+    ) -> Result<i64>
+    {
+        let codigo_lote = codigo_lote.map(str::trim).unwrap_or("");
+        let fecha_caducidad = match fecha_caducidad.map(str::trim) {
+            Some(fecha) if !fecha.is_empty() => {
+                if NaiveDate::parse_from_str(fecha, "%Y-%m-%d").is_err() {
+                    return Err(business_error("La fecha de caducidad debe tener formato AAAA-MM-DD"));
+                }
+                Some(fecha)
+            }
+            _ => None,
+        };
+
+        let repo = LoteRepo { conn: self.conn };
+        let id_lote = match repo.find_id(id_producto, codigo_lote, fecha_caducidad)? {
+            Some(id_lote) => id_lote,
+            None => repo.create(id_producto, codigo_lote, fecha_caducidad, 0)?,
+        };
+        repo.add_cantidad(id_lote, cantidad)?;
+        Ok(id_lote)
+    }
+
+    // Descuenta de los lotes primero el que caduca antes (FEFO)
+    pub fn consumir_fefo(&self, id_producto: i64, cantidad: i64) -> Result<()>
+    {
+        let repo = LoteRepo { conn: self.conn };
+        let mut pendiente = cantidad;
+        for lote in repo.list_disponibles(id_producto)? {
+            if pendiente == 0 {
+                break;
+            }
+            let tomado = pendiente.min(lote.cantidad);
+            repo.add_cantidad(lote.id_lote, -tomado)?;
+            pendiente -= tomado;
+        }
+
+        if pendiente > 0 {
+            return Err(business_error("Los lotes del producto no alcanzan para la salida solicitada"));
+        }
+        Ok(())
+    }
+
+    // Da de baja todo lote vencido con existencia; debe ejecutarse dentro de una transacción
+    pub fn dar_de_baja_vencidos(&self) -> Result<Vec<Lote>>
+    {
+        let repo = LoteRepo { conn: self.conn };
+        let producto_repo = ProductoRepo { conn: self.conn };
+        let movimientos = MovimientoInventarioService::new(self.conn);
+
+        let vencidos = repo.list_vencidos()?;
+        for lote in &vencidos {
+            if producto_repo.adjust_stock(lote.id_producto, -lote.cantidad)? == 0 {
+                return Err(business_error(&format!(
+                    "El stock de '{}' no coincide con sus lotes",
+                    lote.nombre_producto
+                )));
+            }
+            repo.add_cantidad(lote.id_lote, -lote.cantidad)?;
+
+            let nota = format!(
+                "Lote {} vencido el {}",
+                if lote.codigo_lote.is_empty() { "sin código" } else { &lote.codigo_lote },
+                lote.fecha_caducidad.as_deref().unwrap_or("")
+            );
+            movimientos.registrar_ajuste(lote.id_producto, -lote.cantidad, MotivoAjuste::Caducado, &nota)?;
+        }
+        Ok(vencidos)
+    }
+}
@@ -3,4 +3,49 @@ pub mod categoria_service;
 pub mod abono_venta_service;
 pub mod venta_service;
 pub mod producto_vendido_service;
-pub mod perfil_service;
\ No newline at end of file
+pub mod perfil_service;
+pub mod clienta_service;
+pub mod estado_cuenta_service;
+pub mod movimiento_inventario_service;
+pub mod proveedor_service;
+pub mod compra_service;
+pub mod ganancia_service;
+pub mod historial_precio_service;
+pub mod lote_service;
+pub mod conteo_service;
+pub mod variante_service;
+pub mod etiqueta_service;
+pub mod kit_service;
+pub mod campana_service;
+pub mod promocion_service;
+pub mod imagen_service;
+pub mod importacion_service;
+
+// Error de regla de negocio con un mensaje legible para la interfaz; todas las validaciones de servicios lo usan
+pub(crate) fn business_error(message: &str) -> rusqlite::Error
+{
+    rusqlite::Error::SqliteFailure(
+        rusqlite::ffi::Error {
+            code: rusqlite::ErrorCode::ConstraintViolation,
+            extended_code: 0,
+        },
+        Some(message.to_string()),
+    )
+}
+
+// Valida los rangos de un listado paginado; el campo de orden lo valida `consulta::orden_sql`
+pub(crate) fn validar_consulta(consulta: &crate::models::ConsultaLista) -> rusqlite::Result<()>
+{
+    let filtros = &consulta.filtros;
+    if let (Some(desde), Some(hasta)) = (filtros.stock_desde, filtros.stock_hasta) {
+        if desde > hasta {
+            return Err(business_error("El stock inicial del filtro es mayor que el final"));
+        }
+    }
+    if let (Some(desde), Some(hasta)) = (filtros.fecha_desde.as_deref(), filtros.fecha_hasta.as_deref()) {
+        if desde > hasta {
+            return Err(business_error("La fecha inicial del filtro es posterior a la final"));
+        }
+    }
+    Ok(())
+}
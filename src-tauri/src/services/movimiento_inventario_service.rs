@@ -0,0 +1,65 @@
+use rusqlite::{Connection, Result};
+use crate::models::{MotivoAjuste, MotivoMovimiento, MovimientoInventario};
+use crate::repos::movimiento_inventario_repo::MovimientoInventarioRepo;
+use crate::repos::producto_repo::ProductoRepo;
+use crate::services::business_error;
+
+pub struct MovimientoInventarioService<'a>
+{
+    pub conn: &'a Connection,
+}
+
+impl<'a> MovimientoInventarioService<'a>
+{
+    pub fn new(conn: &'a Connection) -> Self
+    {
+        Self { conn }
+    }
+
+    // Debe llamarse después de aplicar el cambio de stock en productos
+    pub fn registrar(&self, id_producto: i64, delta: i64, motivo: MotivoMovimiento, id_referencia: Option<i64>, nota: &str) -> Result<()>
+    {
+        if delta == 0 {
+            return Ok(());
+        }
+
+        let repo = MovimientoInventarioRepo { conn: self.conn };
+        repo.create(id_producto, delta, motivo, None, id_referencia, nota.trim())?;
+        Ok(())
+    }
+
+    pub fn registrar_ajuste(&self, id_producto: i64, delta: i64, motivo_ajuste: MotivoAjuste, nota: &str) -> Result<()>
+    {
+        let repo = MovimientoInventarioRepo { conn: self.conn };
+        repo.create(
+            id_producto,
+            delta,
+            motivo_ajuste.motivo_movimiento(),
+            Some(motivo_ajuste),
+            None,
+            nota.trim(),
+        )?;
+        Ok(())
+    }
+
+    // Un kit no tiene kardex propio: su stock se deriva de los componentes
+    pub fn listar_por_producto(&self, id_producto: i64) -> Result<Vec<MovimientoInventario>>
+    {
+        if let Ok(producto) = (ProductoRepo { conn: self.conn }).get(id_producto) {
+            if producto.es_kit {
+                return Err(business_error(&format!(
+                    "El stock de '{}' se calcula de sus componentes; consulta los movimientos de los componentes",
+                    producto.nombre_producto
+                )));
+            }
+        }
+        let repo = MovimientoInventarioRepo { conn: self.conn };
+        repo.list_by_producto(id_producto)
+    }
+
+    pub fn listar_por_fechas(&self, desde: &str, hasta: &str) -> Result<Vec<MovimientoInventario>>
+    {
+        let repo = MovimientoInventarioRepo { conn: self.conn };
+        repo.list_between_dates(desde, hasta)
+    }
+}
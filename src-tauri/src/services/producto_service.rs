@@ -1,6 +1,16 @@
 use rusqlite::{Connection, Result};
 use crate::repos::producto_repo::ProductoRepo;
-use crate::models::Producto;
+use crate::models::{AjusteStockOutput, ConsultaLista, FiltrosBusqueda, ImpactoEliminacionProducto, MotivoAjuste, MotivoMovimiento, OrigenCambioPrecio, Pagina, Producto, ProductoBajoStock};
+use crate::services::{business_error, validar_consulta};
+use crate::services::categoria_service::CategoriaService;
+use crate::services::historial_precio_service::HistorialPrecioService;
+use crate::services::lote_service::LoteService;
+use crate::services::movimiento_inventario_service::MovimientoInventarioService;
+use crate::services::variante_service::VarianteService;
+
+// Resultados de `buscar_productos` cuando no se indica límite, y el máximo permitido
+const LIMITE_BUSQUEDA: i64 = 50;
+const LIMITE_MAXIMO_BUSQUEDA: i64 = 200;
 
 pub struct ProductoService<'a> {
     pub conn: &'a Connection,
@@ -19,6 +29,21 @@ impl <'a> ProductoService<'a>
     repo.list()
    }
 
+   // Página de productos filtrada y ordenada en SQL; las miniaturas solo si se piden
+   pub fn list_productos_paginados(&self, consulta: &ConsultaLista) -> Result<Pagina<Producto>>
+   {
+    validar_consulta(consulta)?;
+    let repo = ProductoRepo {conn: self.conn};
+    let (productos, total) = repo.list_paginado(consulta)?;
+    Ok(Pagina::new(productos, total, consulta))
+   }
+
+   pub fn list_productos_por_categoria(&self, id_categoria: i64, incluir_subcategorias: bool) -> Result<Vec<Producto>>
+   {
+    let repo = ProductoRepo {conn: self.conn};
+    repo.list_por_categoria(id_categoria, incluir_subcategorias)
+   }
+
    pub fn get_producto(&self, id: i64) -> Result<Producto> 
    {
     let repo = ProductoRepo {conn: self.conn};
@@ -29,7 +54,10 @@ impl <'a> ProductoService<'a>
    {
     self.validate_prices(stock, precio, precio)?;
     let repo = ProductoRepo {conn: self.conn};
-    repo.create(nombre_producto, id_categoria, ruta_imagen, miniatura_base64, stock, precio)
+    let id = repo.create(nombre_producto, id_categoria, ruta_imagen, miniatura_base64, stock, precio)?;
+    self.registrar_stock_inicial(id, stock)?;
+    HistorialPrecioService::new(self.conn).registrar_alta(&repo.get(id)?)?;
+    Ok(id)
    }
 
    pub fn create_producto_with_prices(
@@ -45,7 +73,7 @@ impl <'a> ProductoService<'a>
    {
     self.validate_prices(stock, precio_consultora, precio_publico)?;
     let repo = ProductoRepo {conn: self.conn};
-    repo.create_with_prices(
+    let id = repo.create_with_prices(
         nombre_producto,
         id_categoria,
         ruta_imagen,
@@ -54,7 +82,10 @@ impl <'a> ProductoService<'a>
         precio_publico,
         precio_consultora,
         precio_publico,
-    )
+    )?;
+    self.registrar_stock_inicial(id, stock)?;
+    HistorialPrecioService::new(self.conn).registrar_alta(&repo.get(id)?)?;
+    Ok(id)
    }
 
    pub fn update_producto(&self, producto: &Producto) -> Result<()> 
@@ -62,17 +93,334 @@ impl <'a> ProductoService<'a>
     let mut normalized = producto.clone();
     self.normalize_product_prices(&mut normalized);
     self.validate_prices(normalized.stock, normalized.precio_consultora, normalized.precio_publico)?;
+    normalized.sku = Self::normalizar_sku(normalized.sku.as_deref());
+    normalized.codigo_barras = Self::normalizar_codigo_barras(normalized.codigo_barras.as_deref());
+    self.validate_codigos(normalized.id_producto, normalized.sku.as_deref(), normalized.codigo_barras.as_deref())?;
+
+    let repo = ProductoRepo {conn: self.conn};
+    let anterior = match repo.get(normalized.id_producto) {
+        Ok(producto) => producto,
+        Err(rusqlite::Error::QueryReturnedNoRows) => return repo.update(&normalized),
+        Err(err) => return Err(err),
+    };
+    repo.update(&normalized)?;
+
+    // Historial con los precios tal como quedaron guardados
+    let actual = repo.get(normalized.id_producto)?;
+    HistorialPrecioService::new(self.conn).registrar_cambios(&anterior, &actual, OrigenCambioPrecio::Edicion)?;
+
+    // Editar el precio de una variante la deja con precio propio; editar un padre actualiza sus variantes
+    if actual.id_producto_padre.is_some() {
+        let cambio_precio = anterior.precio_consultora != actual.precio_consultora
+            || anterior.precio_publico != actual.precio_publico;
+        if cambio_precio {
+            repo.update_precio_propio(actual.id_producto, true)?;
+        }
+        Ok(())
+    } else {
+        VarianteService::new(self.conn).sincronizar_variantes(&actual)
+    }
+   }
+
+   // Cambio relativo de stock con motivo; debe ejecutarse dentro de una transacción
+   pub fn ajustar_stock(&self, id_producto: i64, delta: i64, motivo: MotivoAjuste, nota: &str) -> Result<AjusteStockOutput>
+   {
+    if delta == 0 {
+        return Err(business_error("El ajuste de stock no puede ser 0"));
+    }
+
+    let repo = ProductoRepo {conn: self.conn};
+    let producto = match repo.get(id_producto) {
+        Ok(producto) => producto,
+        Err(rusqlite::Error::QueryReturnedNoRows) => {
+            return Err(business_error("El producto indicado no existe"));
+        }
+        Err(err) => return Err(err),
+    };
+    if producto.es_kit {
+        return Err(business_error("El stock de un kit se calcula de sus componentes; ajusta los componentes"));
+    }
+
+    if repo.adjust_stock(id_producto, delta)? == 0 {
+        return Err(business_error(&format!(
+            "El ajuste dejaría el stock de '{}' en negativo. Disponible: {}, Ajuste: {}",
+            producto.nombre_producto, producto.stock, delta
+        )));
+    }
+
+    // Las entradas van al lote general; las salidas se toman del lote que caduca primero
+    let lotes = LoteService::new(self.conn);
+    if delta > 0 {
+        lotes.registrar_entrada(id_producto, delta, None, None)?;
+    } else {
+        lotes.consumir_fefo(id_producto, -delta)?;
+    }
+
+    let movimientos = MovimientoInventarioService::new(self.conn);
+    movimientos.registrar_ajuste(id_producto, delta, motivo, nota)?;
+
+    Ok(AjusteStockOutput {
+        id_producto,
+        stock_anterior: producto.stock,
+        stock_actual: repo.get(id_producto)?.stock,
+    })
+   }
+
+   pub fn set_stock_minimo(&self, id_producto: i64, stock_minimo: Option<i64>) -> Result<()>
+   {
+    if stock_minimo.is_some_and(|minimo| minimo < 0) {
+        return Err(business_error("El stock mínimo no puede ser negativo"));
+    }
+
+    let repo = ProductoRepo {conn: self.conn};
+    if repo.update_stock_minimo(id_producto, stock_minimo)? == 0 {
+        return Err(business_error("El producto indicado no existe"));
+    }
+    Ok(())
+   }
+
+   // Productos en o bajo su stock mínimo con la venta promedio diaria de los últimos `dias`;
+   // con `id_categoria` solo los de esa categoría y sus subcategorías
+   pub fn list_productos_bajo_stock(&self, dias: i64, id_categoria: Option<i64>) -> Result<Vec<ProductoBajoStock>>
+   {
+    if dias <= 0 {
+        return Err(business_error("El periodo de ventas debe ser de al menos 1 día"));
+    }
 
     let repo = ProductoRepo {conn: self.conn};
-    repo.update(&normalized)
+    let mut productos = repo.list_bajo_stock(dias)?;
+    if let Some(id_categoria) = id_categoria {
+        let categorias = CategoriaService::new(self.conn).ids_con_subcategorias(id_categoria)?;
+        productos.retain(|producto| producto.id_categoria.is_some_and(|id| categorias.contains(&id)));
+    }
+    for producto in &mut productos {
+        producto.venta_promedio_diaria = producto.unidades_vendidas as f64 / dias as f64;
+        producto.dias_cobertura = if producto.venta_promedio_diaria > 0.0 {
+            Some(producto.stock as f64 / producto.venta_promedio_diaria)
+        } else {
+            None
+        };
+    }
+    Ok(productos)
+   }
+
+   // Asigna sku y código de barras (None o vacío los quita)
+   pub fn set_codigos(&self, id_producto: i64, sku: Option<&str>, codigo_barras: Option<&str>) -> Result<()>
+   {
+    let sku = Self::normalizar_sku(sku);
+    let codigo_barras = Self::normalizar_codigo_barras(codigo_barras);
+    self.validate_codigos(id_producto, sku.as_deref(), codigo_barras.as_deref())?;
+
+    let repo = ProductoRepo {conn: self.conn};
+    if repo.update_codigos(id_producto, sku.as_deref(), codigo_barras.as_deref())? == 0 {
+        return Err(business_error("El producto indicado no existe"));
+    }
+    Ok(())
    }
 
+   // Notas libres del producto (None o vacío las quita)
+   pub fn set_notas(&self, id_producto: i64, notas: Option<&str>) -> Result<()>
+   {
+    let notas = notas.map(str::trim).filter(|notas| !notas.is_empty());
+    let repo = ProductoRepo {conn: self.conn};
+    if repo.update_notas(id_producto, notas)? == 0 {
+        return Err(business_error("El producto indicado no existe"));
+    }
+    Ok(())
+   }
+
+   // Búsqueda por nombre, atributos, categoría, códigos y notas, ordenada por relevancia.
+   // Cada palabra se busca como prefijo ("lab ro" encuentra "Labial rojo") y sin acentos
+   pub fn buscar_productos(&self, texto: &str, filtros: &FiltrosBusqueda, limite: Option<i64>) -> Result<Vec<Producto>>
+   {
+    let consulta = match Self::consulta_fts(texto) {
+        Some(consulta) => consulta,
+        None => return Ok(Vec::new()),
+    };
+    let limite = limite.unwrap_or(LIMITE_BUSQUEDA).clamp(1, LIMITE_MAXIMO_BUSQUEDA);
+    let repo = ProductoRepo {conn: self.conn};
+    repo.buscar(&consulta, filtros, limite)
+   }
+
+   // Cada palabra entre comillas (para que guiones o paréntesis no se lean como operadores) y con `*` de prefijo
+   fn consulta_fts(texto: &str) -> Option<String>
+   {
+    let terminos: Vec<String> = texto
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|termino| !termino.is_empty())
+        .map(|termino| format!("\"{}\"*", termino))
+        .collect();
+    if terminos.is_empty() {
+        None
+    } else {
+        Some(terminos.join(" "))
+    }
+   }
+
+   // Producto o variante activo para un código escaneado (código de barras o sku).
+   // Un UPC-A de 12 dígitos también se encuentra leído como EAN-13 con un 0 adelante y viceversa.
+   pub fn buscar_por_codigo(&self, codigo: &str) -> Result<Producto>
+   {
+    let codigo = codigo.trim();
+    if codigo.is_empty() {
+        return Err(business_error("El código escaneado está vacío"));
+    }
+
+    let mut candidatos = vec![codigo.to_string()];
+    if codigo.bytes().all(|b| b.is_ascii_digit()) {
+        if codigo.len() == 12 {
+            candidatos.push(format!("0{}", codigo));
+        } else if codigo.len() == 13 && codigo.starts_with('0') {
+            candidatos.push(codigo[1..].to_string());
+        }
+    }
+
+    let repo = ProductoRepo {conn: self.conn};
+    repo.find_activo_por_codigo(&candidatos)?
+        .ok_or_else(|| business_error(&format!("No hay un producto activo con el código '{}'", codigo)))
+   }
+
+   // Dígito verificador GS1 (EAN-13, UPC-A, EAN-8): pesos 3 y 1 alternados desde la derecha
+   pub fn codigo_barras_valido(codigo: &str) -> bool
+   {
+    if !matches!(codigo.len(), 8 | 12 | 13) || !codigo.bytes().all(|b| b.is_ascii_digit()) {
+        return false;
+    }
+
+    let digitos: Vec<u32> = codigo.bytes().map(|b| (b - b'0') as u32).collect();
+    let (cuerpo, verificador) = digitos.split_at(digitos.len() - 1);
+    let suma: u32 = cuerpo
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, d)| if i % 2 == 0 { d * 3 } else { *d })
+        .sum();
+    (10 - suma % 10) % 10 == verificador[0]
+   }
+
+   // Descontinuar un producto padre también descontinúa sus variantes
    pub fn delete_producto(&self, id: i64) -> Result<()> 
    {
     let repo = ProductoRepo {conn: self.conn};
+    for variante in repo.list_variantes(id)? {
+        repo.delete(variante.id_producto)?;
+    }
     repo.delete(id)
    }
 
+   pub fn list_productos_descontinuados(&self) -> Result<Vec<Producto>>
+   {
+    let repo = ProductoRepo {conn: self.conn};
+    repo.list_descontinuados()
+   }
+
+   // Una variante solo vuelve si su producto padre está activo
+   pub fn reactivar_producto(&self, id: i64) -> Result<()>
+   {
+    let repo = ProductoRepo {conn: self.conn};
+    let producto = repo.get(id)?;
+    if producto.activo == 1 {
+        return Err(business_error(&format!("'{}' ya está activo", producto.nombre_producto)));
+    }
+    if let Some(id_padre) = producto.id_producto_padre {
+        let padre = repo.get(id_padre)?;
+        if padre.activo != 1 {
+            return Err(business_error(&format!(
+                "Reactiva primero el producto '{}' del que '{}' es variante",
+                padre.nombre_producto, producto.nombre_producto
+            )));
+        }
+    }
+    repo.reactivar(id)?;
+    Ok(())
+   }
+
+   // Qué historial se perdería al eliminar el producto definitivamente, para confirmarlo antes;
+   // falla igual que `eliminar_producto_definitivo` si el producto no se puede borrar
+   pub fn impacto_eliminacion_definitiva(&self, id: i64) -> Result<ImpactoEliminacionProducto>
+   {
+    let repo = ProductoRepo {conn: self.conn};
+    let producto = self.validar_eliminacion_definitiva(id)?;
+    let (movimientos, historial_precios, lotes, precios_campana) = repo.count_historial(id)?;
+    Ok(ImpactoEliminacionProducto {
+        id_producto: producto.id_producto,
+        nombre_producto: producto.nombre_producto,
+        movimientos,
+        historial_precios,
+        lotes,
+        precios_campana,
+    })
+   }
+
+   // Borrado permanente de un producto descontinuado que nunca se vendió, compró, contó ni tuvo promociones.
+   // Destruye sus movimientos de inventario (kardex), su historial de precios, sus lotes y sus precios de
+   // campaña; regresa el producto borrado para que quien llama elimine su imagen
+   pub fn eliminar_producto_definitivo(&self, id: i64) -> Result<Producto>
+   {
+    let repo = ProductoRepo {conn: self.conn};
+    let producto = self.validar_eliminacion_definitiva(id)?;
+    repo.delete_definitivo(id)?;
+    Ok(producto)
+   }
+
+   fn validar_eliminacion_definitiva(&self, id: i64) -> Result<Producto>
+   {
+    let repo = ProductoRepo {conn: self.conn};
+    let producto = repo.get(id)?;
+    if producto.activo == 1 {
+        return Err(business_error(&format!(
+            "Descontinúa '{}' antes de eliminarlo definitivamente",
+            producto.nombre_producto
+        )));
+    }
+
+    let (ventas, compras, kits, variantes, conteos, promociones) = repo.count_referencias(id)?;
+    let motivo = if ventas > 0 {
+        Some("tiene ventas registradas")
+    } else if compras > 0 {
+        Some("tiene compras registradas")
+    } else if kits > 0 {
+        Some("es componente de un kit")
+    } else if variantes > 0 {
+        Some("tiene variantes; elimínalas primero")
+    } else if conteos > 0 {
+        Some("aparece en conteos de inventario")
+    } else if promociones > 0 {
+        Some("tiene promociones; elimínalas primero")
+    } else {
+        None
+    };
+    if let Some(motivo) = motivo {
+        return Err(business_error(&format!(
+            "No se puede eliminar definitivamente '{}': {}",
+            producto.nombre_producto, motivo
+        )));
+    }
+    Ok(producto)
+   }
+
+   // Todos los productos de la venta deben existir y estar activos
+   pub fn validar_activos_para_venta(&self, ids: &[i64]) -> Result<()>
+   {
+    let repo = ProductoRepo {conn: self.conn};
+    for &id in ids {
+        let producto = match repo.get(id) {
+            Ok(producto) => producto,
+            Err(rusqlite::Error::QueryReturnedNoRows) => {
+                return Err(business_error(&format!("El producto {} no existe", id)));
+            }
+            Err(err) => return Err(err),
+        };
+        if producto.activo != 1 {
+            return Err(business_error(&format!(
+                "'{}' está descontinuado; reactívalo para poder venderlo",
+                producto.nombre_producto
+            )));
+        }
+    }
+    Ok(())
+   }
+
    pub fn get_total_inventory_value(&self) -> Result<f64>
    {
     let products = self.list_productos()?;
@@ -89,6 +437,58 @@ impl <'a> ProductoService<'a>
     Ok(total)
    }
 
+   fn registrar_stock_inicial(&self, id_producto: i64, stock: i64) -> Result<()>
+   {
+    if stock > 0 {
+        LoteService::new(self.conn).registrar_entrada(id_producto, stock, None, None)?;
+    }
+    let movimientos = MovimientoInventarioService::new(self.conn);
+    movimientos.registrar(id_producto, stock, MotivoMovimiento::Inicial, None, "Alta de producto")
+   }
+
+   fn normalizar_sku(sku: Option<&str>) -> Option<String>
+   {
+    sku.map(str::trim).filter(|s| !s.is_empty()).map(str::to_string)
+   }
+
+   // Los lectores y las etiquetas a veces separan el código con espacios o guiones
+   fn normalizar_codigo_barras(codigo: Option<&str>) -> Option<String>
+   {
+    codigo
+        .map(|c| c.chars().filter(|ch| !ch.is_whitespace() && *ch != '-').collect::<String>())
+        .filter(|c| !c.is_empty())
+   }
+
+   fn validate_codigos(&self, id_producto: i64, sku: Option<&str>, codigo_barras: Option<&str>) -> Result<()>
+   {
+    let repo = ProductoRepo {conn: self.conn};
+
+    if let Some(sku) = sku {
+        if let Some(otro) = repo.find_by_sku(sku, Some(id_producto))? {
+            return Err(business_error(&format!(
+                "El SKU '{}' ya está asignado a '{}'{}",
+                sku, otro.nombre_producto, if otro.activo == 1 { "" } else { " (descontinuado)" }
+            )));
+        }
+    }
+
+    if let Some(codigo) = codigo_barras {
+        if !Self::codigo_barras_valido(codigo) {
+            return Err(business_error(&format!(
+                "El código de barras '{}' no es un EAN-13, UPC-A o EAN-8 válido (revisa el dígito verificador)",
+                codigo
+            )));
+        }
+        if let Some(otro) = repo.find_by_codigo_barras(codigo, Some(id_producto))? {
+            return Err(business_error(&format!(
+                "El código de barras '{}' ya está asignado a '{}'{}",
+                codigo, otro.nombre_producto, if otro.activo == 1 { "" } else { " (descontinuado)" }
+            )));
+        }
+    }
+    Ok(())
+   }
+
    fn normalize_product_prices(&self, producto: &mut Producto)
    {
     if producto.precio_consultora <= 0.0 {
@@ -114,27 +514,19 @@ impl <'a> ProductoService<'a>
    fn validate_prices(&self, stock: i64, precio_consultora: f64, precio_publico: f64) -> Result<()>
    {
     if stock < 0 {
-        return Err(rusqlite::Error::InvalidParameterName(
-            "stock no puede ser negativo".to_string(),
-        ));
+        return Err(business_error("stock no puede ser negativo"));
     }
 
     if precio_consultora < 0.0 {
-        return Err(rusqlite::Error::InvalidParameterName(
-            "precio_consultora no puede ser negativo".to_string(),
-        ));
+        return Err(business_error("precio_consultora no puede ser negativo"));
     }
 
     if precio_publico < 0.0 {
-        return Err(rusqlite::Error::InvalidParameterName(
-            "precio_publico no puede ser negativo".to_string(),
-        ));
+        return Err(business_error("precio_publico no puede ser negativo"));
     }
 
     if precio_publico < precio_consultora {
-        return Err(rusqlite::Error::InvalidParameterName(
-            "precio_publico debe ser mayor o igual a precio_consultora".to_string(),
-        ));
+        return Err(business_error("precio_publico debe ser mayor o igual a precio_consultora"));
     }
 
     Ok(())
@@ -1,6 +1,8 @@
 use rusqlite::{Connection, Result};
+use crate::repos::producto_repo::ProductoRepo;
 use crate::repos::producto_vendido_repo::ProductoVendidoRepo;
-use crate::models::ProductoVendido;
+use crate::models::{ConsultaLista, Pagina, ProductoVendido};
+use crate::services::{business_error, validar_consulta};
 
 pub struct ProductoVendidoService<'a> 
 {
@@ -21,6 +23,14 @@ impl<'a> ProductoVendidoService<'a>
         repo.list()
     }
 
+    pub fn list_productos_vendidos_paginados(&self, consulta: &ConsultaLista) -> Result<Pagina<ProductoVendido>>
+    {
+        validar_consulta(consulta)?;
+        let repo = ProductoVendidoRepo { conn: self.conn };
+        let (productos_vendidos, total) = repo.list_paginado(consulta)?;
+        Ok(Pagina::new(productos_vendidos, total, consulta))
+    }
+
     pub fn get_producto_vendido(&self, id: i64) -> Result<ProductoVendido> 
     {
         let repo = ProductoVendidoRepo { conn: self.conn };
@@ -42,6 +52,20 @@ impl<'a> ProductoVendidoService<'a>
     pub fn update_producto_vendido(&self, producto_vendido: &ProductoVendido) -> Result<()> 
     {
         let repo = ProductoVendidoRepo { conn: self.conn };
+
+        // Una línea de kit puede cambiar de cantidad, pero no pasar a (o desde) otro producto
+        let anterior = match repo.get(producto_vendido.id_producto_vendido) {
+            Ok(anterior) => anterior,
+            Err(rusqlite::Error::QueryReturnedNoRows) => return repo.update(producto_vendido),
+            Err(err) => return Err(err),
+        };
+        if anterior.id_producto != producto_vendido.id_producto {
+            let productos = ProductoRepo { conn: self.conn };
+            let es_kit = |id: i64| productos.get(id).map(|p| p.es_kit).unwrap_or(false);
+            if es_kit(anterior.id_producto) || es_kit(producto_vendido.id_producto) {
+                return Err(business_error("No se puede cambiar el producto de una línea de kit; elimínala y agrega la nueva"));
+            }
+        }
         repo.update(producto_vendido)
     }
 
@@ -0,0 +1,321 @@
+use chrono::NaiveDate;
+use rusqlite::{Connection, Error, Result};
+use crate::models::{
+    CalculoVenta,
+    DescuentoInput,
+    ItemVenta,
+    LineaCalculada,
+    Producto,
+    Promocion,
+    PromocionAplicada,
+    PromocionInput,
+    TipoDescuento,
+    TipoPromocion,
+};
+use crate::repos::categoria_repo::CategoriaRepo;
+use crate::repos::producto_repo::ProductoRepo;
+use crate::repos::promocion_repo::PromocionRepo;
+use crate::services::abono_venta_service::AbonoVentaService;
+use crate::services::business_error;
+
+pub struct PromocionService<'a>
+{
+    pub conn: &'a Connection,
+}
+
+impl<'a> PromocionService<'a>
+{
+    pub fn new(conn: &'a Connection) -> Self
+    {
+        Self { conn }
+    }
+
+    pub fn listar_promociones(&self) -> Result<Vec<Promocion>>
+    {
+        let repo = PromocionRepo { conn: self.conn };
+        repo.list()
+    }
+
+    pub fn get_promocion(&self, id_promocion: i64) -> Result<Promocion>
+    {
+        let repo = PromocionRepo { conn: self.conn };
+        match repo.get(id_promocion) {
+            Ok(promocion) => Ok(promocion),
+            Err(Error::QueryReturnedNoRows) => Err(business_error("La promoción indicada no existe")),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn create_promocion(&self, input: &PromocionInput) -> Result<i64>
+    {
+        let input = self.validate_promocion(input)?;
+        let repo = PromocionRepo { conn: self.conn };
+        repo.create(&input)
+    }
+
+    pub fn update_promocion(&self, id_promocion: i64, input: &PromocionInput) -> Result<()>
+    {
+        self.get_promocion(id_promocion)?;
+        let input = self.validate_promocion(input)?;
+        let repo = PromocionRepo { conn: self.conn };
+        repo.update(id_promocion, &input)?;
+        Ok(())
+    }
+
+    // Una promoción que ya se aplicó queda en el historial de ventas; solo se puede desactivar
+    pub fn delete_promocion(&self, id_promocion: i64) -> Result<()>
+    {
+        let promocion = self.get_promocion(id_promocion)?;
+        let repo = PromocionRepo { conn: self.conn };
+        let usos = repo.count_usos(id_promocion)?;
+        if usos > 0 {
+            return Err(business_error(&format!(
+                "La promoción '{}' ya se aplicó en {} línea(s) de venta; desactívala en lugar de eliminarla",
+                promocion.nombre, usos
+            )));
+        }
+        repo.delete(id_promocion)
+    }
+
+    // Desglose de la venta: a cada línea se le aplica la mejor promoción vigente en la fecha,
+    // luego su descuento manual y al final el descuento sobre el total de la venta
+    pub fn calcular_venta(
+        &self,
+        fecha: &str,
+        items: &[ItemVenta],
+        precios: &[f64],
+        descuento: Option<&DescuentoInput>,
+    ) -> Result<CalculoVenta>
+    {
+        let promociones = PromocionRepo { conn: self.conn }.list_vigentes(fecha)?;
+        let productos = ProductoRepo { conn: self.conn };
+
+        // Una promoción de categoría también cubre sus subcategorías
+        let categorias = CategoriaRepo { conn: self.conn };
+        let mut alcances = Vec::with_capacity(promociones.len());
+        for promocion in &promociones {
+            alcances.push(match promocion.id_categoria {
+                Some(id_categoria) => categorias.ids_descendientes(id_categoria)?,
+                None => Vec::new(),
+            });
+        }
+
+        let mut lineas = Vec::with_capacity(items.len());
+        let mut aplicadas = Vec::new();
+        for (item, &precio_unitario) in items.iter().zip(precios) {
+            if item.cantidad <= 0 {
+                return Err(business_error("La cantidad de cada producto debe ser mayor a 0"));
+            }
+            if precio_unitario < 0.0 {
+                return Err(business_error("El precio de venta no puede ser negativo"));
+            }
+            let producto = match productos.get(item.id_producto) {
+                Ok(producto) => producto,
+                Err(Error::QueryReturnedNoRows) => {
+                    return Err(business_error(&format!("Producto con ID {} no encontrado", item.id_producto)));
+                }
+                Err(err) => return Err(err),
+            };
+
+            let bruto = AbonoVentaService::normalize_money(item.cantidad as f64 * precio_unitario);
+            let mut mejor: Option<(&Promocion, f64)> = None;
+            let vigentes = promociones.iter().zip(&alcances).filter(|(p, alcance)| aplica_a(p, alcance, &producto));
+            for (promocion, _) in vigentes {
+                let monto = descuento_promocion(promocion, item.cantidad, precio_unitario).min(bruto);
+                if monto > 0.0 && mejor.map_or(true, |(_, actual)| monto > actual) {
+                    mejor = Some((promocion, monto));
+                }
+            }
+            let descuento_promocion = mejor.map_or(0.0, |(_, monto)| monto);
+            if let Some((promocion, monto)) = mejor {
+                aplicadas.push(PromocionAplicada {
+                    id_promocion: promocion.id_promocion,
+                    nombre: promocion.nombre.clone(),
+                    id_producto: item.id_producto,
+                    descuento: monto,
+                });
+            }
+
+            let restante = AbonoVentaService::normalize_money(bruto - descuento_promocion);
+            let descuento_manual = monto_descuento(
+                item.descuento.as_ref(),
+                restante,
+                &format!("El descuento de '{}'", producto.nombre_producto),
+            )?;
+            let descuento_linea = AbonoVentaService::normalize_money(descuento_promocion + descuento_manual);
+
+            lineas.push(LineaCalculada {
+                id_producto: item.id_producto,
+                cantidad: item.cantidad,
+                precio_unitario,
+                bruto,
+                id_promocion: mejor.map(|(promocion, _)| promocion.id_promocion),
+                descuento_promocion,
+                descuento_tipo: item.descuento.as_ref().map(|d| d.tipo),
+                descuento_valor: item.descuento.as_ref().map_or(0.0, |d| d.valor),
+                descuento: descuento_linea,
+                subtotal: AbonoVentaService::normalize_money(bruto - descuento_linea),
+            });
+        }
+
+        let subtotal_bruto = AbonoVentaService::normalize_money(lineas.iter().map(|l| l.bruto).sum());
+        let descuento_lineas = AbonoVentaService::normalize_money(lineas.iter().map(|l| l.descuento).sum());
+        let subtotal = AbonoVentaService::normalize_money(lineas.iter().map(|l| l.subtotal).sum());
+        let descuento_venta = monto_descuento(descuento, subtotal, "El descuento de la venta")?;
+
+        Ok(CalculoVenta {
+            lineas,
+            subtotal_bruto,
+            descuento_lineas,
+            descuento_tipo: descuento.map(|d| d.tipo),
+            descuento_valor: descuento.map_or(0.0, |d| d.valor),
+            descuento_venta,
+            total: AbonoVentaService::normalize_money(subtotal - descuento_venta),
+            promociones: aplicadas,
+        })
+    }
+
+    fn validate_promocion(&self, input: &PromocionInput) -> Result<PromocionInput>
+    {
+        let nombre = input.nombre.trim();
+        if nombre.is_empty() {
+            return Err(business_error("El nombre de la promoción es obligatorio"));
+        }
+
+        match (input.id_producto, input.id_categoria) {
+            (Some(id_producto), None) => {
+                if let Err(Error::QueryReturnedNoRows) = (ProductoRepo { conn: self.conn }).get(id_producto) {
+                    return Err(business_error("El producto indicado no existe"));
+                }
+            }
+            (None, Some(id_categoria)) => {
+                if let Err(Error::QueryReturnedNoRows) = (CategoriaRepo { conn: self.conn }).get(id_categoria) {
+                    return Err(business_error("La categoría indicada no existe"));
+                }
+            }
+            _ => return Err(business_error("La promoción debe aplicar a un producto o a una categoría")),
+        }
+
+        let mut normalizada = PromocionInput {
+            nombre: nombre.to_string(),
+            tipo: input.tipo,
+            id_producto: input.id_producto,
+            id_categoria: input.id_categoria,
+            cantidad_lleva: None,
+            cantidad_paga: None,
+            precio_paquete: None,
+            porcentaje: None,
+            fecha_inicio: input.fecha_inicio.as_deref().map(str::trim).filter(|f| !f.is_empty()).map(String::from),
+            fecha_fin: input.fecha_fin.as_deref().map(str::trim).filter(|f| !f.is_empty()).map(String::from),
+            activa: input.activa,
+        };
+
+        match input.tipo {
+            TipoPromocion::NxM => {
+                let lleva = input.cantidad_lleva.unwrap_or(0);
+                let paga = input.cantidad_paga.unwrap_or(0);
+                if lleva < 2 || paga < 1 || paga >= lleva {
+                    return Err(business_error("En una promoción NxM se lleva al menos 2 y se paga al menos 1 pieza menos"));
+                }
+                normalizada.cantidad_lleva = Some(lleva);
+                normalizada.cantidad_paga = Some(paga);
+            }
+            TipoPromocion::PrecioPaquete => {
+                let lleva = input.cantidad_lleva.unwrap_or(0);
+                let precio = AbonoVentaService::normalize_money(input.precio_paquete.unwrap_or(0.0));
+                if lleva < 2 {
+                    return Err(business_error("El paquete debe ser de al menos 2 piezas"));
+                }
+                if precio <= 0.0 {
+                    return Err(business_error("El precio del paquete debe ser mayor a 0"));
+                }
+                normalizada.cantidad_lleva = Some(lleva);
+                normalizada.precio_paquete = Some(precio);
+            }
+            TipoPromocion::Porcentaje => {
+                let porcentaje = input.porcentaje.unwrap_or(0.0);
+                if porcentaje <= 0.0 || porcentaje > 100.0 {
+                    return Err(business_error("El porcentaje de la promoción debe estar entre 0 y 100"));
+                }
+                normalizada.porcentaje = Some(porcentaje);
+            }
+        }
+
+        for fecha in [&normalizada.fecha_inicio, &normalizada.fecha_fin].into_iter().flatten() {
+            if NaiveDate::parse_from_str(fecha, "%Y-%m-%d").is_err() {
+                return Err(business_error("Las fechas de la promoción deben tener formato AAAA-MM-DD"));
+            }
+        }
+        if let (Some(inicio), Some(fin)) = (&normalizada.fecha_inicio, &normalizada.fecha_fin) {
+            if inicio > fin {
+                return Err(business_error("La fecha de inicio no puede ser posterior a la fecha fin"));
+            }
+        }
+
+        Ok(normalizada)
+    }
+}
+
+// `categorias`: la categoría de la promoción y sus subcategorías
+fn aplica_a(promocion: &Promocion, categorias: &[i64], producto: &Producto) -> bool
+{
+    match (promocion.id_producto, promocion.id_categoria) {
+        (Some(id_producto), _) => id_producto == producto.id_producto,
+        (None, Some(_)) => producto.id_categoria.is_some_and(|id| categorias.contains(&id)),
+        (None, None) => false,
+    }
+}
+
+// Descuento que da la promoción a `cantidad` piezas de la misma línea
+fn descuento_promocion(promocion: &Promocion, cantidad: i64, precio_unitario: f64) -> f64
+{
+    let monto = match promocion.tipo {
+        TipoPromocion::NxM => {
+            let lleva = promocion.cantidad_lleva.unwrap_or(0);
+            let paga = promocion.cantidad_paga.unwrap_or(0);
+            if lleva <= 0 {
+                return 0.0;
+            }
+            ((cantidad / lleva) * (lleva - paga)) as f64 * precio_unitario
+        }
+        TipoPromocion::PrecioPaquete => {
+            let lleva = promocion.cantidad_lleva.unwrap_or(0);
+            let precio_paquete = promocion.precio_paquete.unwrap_or(0.0);
+            if lleva <= 0 {
+                return 0.0;
+            }
+            ((cantidad / lleva) as f64 * (lleva as f64 * precio_unitario - precio_paquete)).max(0.0)
+        }
+        TipoPromocion::Porcentaje => {
+            cantidad as f64 * precio_unitario * promocion.porcentaje.unwrap_or(0.0) / 100.0
+        }
+    };
+    AbonoVentaService::normalize_money(monto)
+}
+
+// Monto de un descuento (porcentaje o fijo) sobre `base`; nunca deja el importe en negativo
+fn monto_descuento(descuento: Option<&DescuentoInput>, base: f64, sujeto: &str) -> Result<f64>
+{
+    let descuento = match descuento {
+        Some(descuento) => descuento,
+        None => return Ok(0.0),
+    };
+    if descuento.valor < 0.0 {
+        return Err(business_error(&format!("{} no puede ser negativo", sujeto)));
+    }
+    match descuento.tipo {
+        TipoDescuento::Porcentaje => {
+            if descuento.valor > 100.0 {
+                return Err(business_error(&format!("{} no puede ser mayor a 100%", sujeto)));
+            }
+            Ok(AbonoVentaService::normalize_money(base * descuento.valor / 100.0))
+        }
+        TipoDescuento::Monto => {
+            let monto = AbonoVentaService::normalize_money(descuento.valor);
+            if monto > base {
+                return Err(business_error(&format!("{} no puede ser mayor al importe (${:.2})", sujeto, base)));
+            }
+            Ok(monto)
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use rusqlite::{Connection, Error, Result};
+use crate::models::{Proveedor, ProveedorInput};
+use crate::repos::proveedor_repo::ProveedorRepo;
+use crate::services::business_error;
+
+pub struct ProveedorService<'a>
+{
+    pub conn: &'a Connection,
+}
+
+impl<'a> ProveedorService<'a>
+{
+    pub fn new(conn: &'a Connection) -> Self
+    {
+        Self { conn }
+    }
+
+    pub fn list_proveedores(&self) -> Result<Vec<Proveedor>>
+    {
+        let repo = ProveedorRepo { conn: self.conn };
+        repo.list()
+    }
+
+    pub fn get_proveedor(&self, id: i64) -> Result<Proveedor>
+    {
+        let repo = ProveedorRepo { conn: self.conn };
+        match repo.get(id) {
+            Err(Error::QueryReturnedNoRows) => Err(business_error("El proveedor indicado no existe")),
+            other => other,
+        }
+    }
+
+    pub fn create_proveedor(&self, input: &ProveedorInput) -> Result<i64>
+    {
+        let nombre = input.nombre.trim();
+        self.validate_nombre(nombre, None)?;
+
+        let repo = ProveedorRepo { conn: self.conn };
+        repo.create(
+            nombre,
+            input.contacto.as_deref().map(str::trim).unwrap_or(""),
+            input.telefono.as_deref().map(str::trim).unwrap_or(""),
+            input.notas.as_deref().map(str::trim).unwrap_or(""),
+        )
+    }
+
+    pub fn update_proveedor(&self, proveedor: &Proveedor) -> Result<()>
+    {
+        let nombre = proveedor.nombre.trim();
+        self.validate_nombre(nombre, Some(proveedor.id_proveedor))?;
+        self.get_proveedor(proveedor.id_proveedor)?;
+
+        let normalizado = Proveedor {
+            id_proveedor: proveedor.id_proveedor,
+            nombre: nombre.to_string(),
+            contacto: proveedor.contacto.trim().to_string(),
+            telefono: proveedor.telefono.trim().to_string(),
+            notas: proveedor.notas.trim().to_string(),
+            creado_at: proveedor.creado_at.clone(),
+        };
+
+        let repo = ProveedorRepo { conn: self.conn };
+        repo.update(&normalizado)
+    }
+
+    pub fn delete_proveedor(&self, id: i64) -> Result<()>
+    {
+        let repo = ProveedorRepo { conn: self.conn };
+        if repo.count_compras(id)? > 0 {
+            return Err(business_error("No se puede eliminar un proveedor con compras registradas"));
+        }
+        repo.delete(id)
+    }
+
+    fn validate_nombre(&self, nombre: &str, id_actual: Option<i64>) -> Result<()>
+    {
+        if nombre.is_empty() {
+            return Err(business_error("Debe ingresar el nombre del proveedor"));
+        }
+
+        let repo = ProveedorRepo { conn: self.conn };
+        match repo.find_id_by_nombre(nombre)? {
+            Some(id) if Some(id) != id_actual => Err(business_error("Ya existe un proveedor con ese nombre")),
+            _ => Ok(()),
+        }
+    }
+}
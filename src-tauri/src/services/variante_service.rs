@@ -0,0 +1,264 @@
+use rusqlite::{Connection, Error, Result};
+use crate::models::{OrigenCambioPrecio, Producto, ProductoAgrupado, VarianteInput};
+use crate::repos::producto_repo::ProductoRepo;
+use crate::services::business_error;
+use crate::services::historial_precio_service::HistorialPrecioService;
+use crate::services::producto_service::ProductoService;
+
+pub struct VarianteService<'a>
+{
+    pub conn: &'a Connection,
+}
+
+// Atributos normalizados de una variante (vacío => None)
+struct Atributos
+{
+    tono: Option<String>,
+    tamano: Option<String>,
+    fragancia: Option<String>,
+}
+
+impl Atributos
+{
+    fn from_input(input: &VarianteInput) -> Self
+    {
+        let limpiar = |valor: &Option<String>| {
+            valor
+                .as_deref()
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(str::to_string)
+        };
+        Self {
+            tono: limpiar(&input.tono),
+            tamano: limpiar(&input.tamano),
+            fragancia: limpiar(&input.fragancia),
+        }
+    }
+
+    fn from_producto(producto: &Producto) -> Self
+    {
+        Self {
+            tono: producto.tono.clone(),
+            tamano: producto.tamano.clone(),
+            fragancia: producto.fragancia.clone(),
+        }
+    }
+
+    fn is_empty(&self) -> bool
+    {
+        self.tono.is_none() && self.tamano.is_none() && self.fragancia.is_none()
+    }
+
+    fn same_as(&self, producto: &Producto) -> bool
+    {
+        let igual = |a: &Option<String>, b: &Option<String>| {
+            a.as_deref().map(str::to_lowercase) == b.as_deref().map(|v| v.trim().to_lowercase())
+        };
+        igual(&self.tono, &producto.tono)
+            && igual(&self.tamano, &producto.tamano)
+            && igual(&self.fragancia, &producto.fragancia)
+    }
+
+    // "Labial Matte - Rojo / 3.5 g"
+    fn nombre_con_padre(&self, nombre_padre: &str) -> String
+    {
+        let partes: Vec<&str> = [&self.tono, &self.tamano, &self.fragancia]
+            .iter()
+            .filter_map(|v| v.as_deref())
+            .collect();
+        format!("{} - {}", nombre_padre.trim(), partes.join(" / "))
+    }
+}
+
+impl<'a> VarianteService<'a>
+{
+    pub fn new(conn: &'a Connection) -> Self
+    {
+        Self { conn }
+    }
+
+    pub fn list_variantes(&self, id_producto_padre: i64) -> Result<Vec<Producto>>
+    {
+        let repo = ProductoRepo { conn: self.conn };
+        repo.list_variantes(id_producto_padre)
+    }
+
+    // Productos activos con sus variantes anidadas; una variante cuyo padre está descontinuado se muestra sola
+    pub fn list_productos_agrupados(&self) -> Result<Vec<ProductoAgrupado>>
+    {
+        let repo = ProductoRepo { conn: self.conn };
+        let productos = repo.list()?;
+
+        let mut grupos: Vec<ProductoAgrupado> = Vec::new();
+        let mut variantes: Vec<Producto> = Vec::new();
+        for producto in productos {
+            if producto.id_producto_padre.is_some() {
+                variantes.push(producto);
+            } else {
+                grupos.push(ProductoAgrupado {
+                    stock_total: producto.stock,
+                    producto,
+                    variantes: Vec::new(),
+                });
+            }
+        }
+
+        for variante in variantes {
+            match grupos
+                .iter_mut()
+                .find(|g| Some(g.producto.id_producto) == variante.id_producto_padre)
+            {
+                Some(grupo) => {
+                    grupo.stock_total += variante.stock;
+                    grupo.variantes.push(variante);
+                }
+                None => grupos.push(ProductoAgrupado {
+                    stock_total: variante.stock,
+                    producto: variante,
+                    variantes: Vec::new(),
+                }),
+            }
+        }
+
+        grupos.sort_by_key(|g| g.producto.nombre_producto.to_lowercase());
+        for grupo in &mut grupos {
+            grupo.variantes.sort_by_key(|v| v.nombre_producto.to_lowercase());
+        }
+        Ok(grupos)
+    }
+
+    // Crea la variante como un producto más (stock, lotes y ventas propios) ligado al padre
+    pub fn create_variante(&self, id_producto_padre: i64, input: &VarianteInput, stock: i64) -> Result<i64>
+    {
+        let padre = self.get_padre(id_producto_padre)?;
+        let atributos = Atributos::from_input(input);
+        self.validate_atributos(&padre, &atributos, None)?;
+
+        let precio_propio = input.precio_consultora.is_some() || input.precio_publico.is_some();
+        let precio_consultora = input.precio_consultora.unwrap_or(padre.precio_consultora);
+        let precio_publico = input.precio_publico.unwrap_or(padre.precio_publico);
+
+        let id_variante = ProductoService::new(self.conn).create_producto_with_prices(
+            &atributos.nombre_con_padre(&padre.nombre_producto),
+            padre.id_categoria,
+            None,
+            None,
+            stock,
+            precio_consultora,
+            precio_publico,
+        )?;
+
+        let productos = ProductoService::new(self.conn);
+        if input.sku.is_some() || input.codigo_barras.is_some() {
+            productos.set_codigos(id_variante, input.sku.as_deref(), input.codigo_barras.as_deref())?;
+        }
+
+        let repo = ProductoRepo { conn: self.conn };
+        repo.update_variante(
+            id_variante,
+            padre.id_producto,
+            atributos.tono.as_deref(),
+            atributos.tamano.as_deref(),
+            atributos.fragancia.as_deref(),
+            precio_propio,
+        )?;
+        Ok(id_variante)
+    }
+
+    // Cambia atributos, precios y códigos de la variante; sin precios vuelve a heredar los del padre
+    pub fn update_variante(&self, id_variante: i64, input: &VarianteInput) -> Result<()>
+    {
+        let repo = ProductoRepo { conn: self.conn };
+        let mut variante = match repo.get(id_variante) {
+            Ok(producto) => producto,
+            Err(Error::QueryReturnedNoRows) => return Err(business_error("La variante indicada no existe")),
+            Err(err) => return Err(err),
+        };
+        let id_producto_padre = variante
+            .id_producto_padre
+            .ok_or_else(|| business_error("El producto indicado no es una variante"))?;
+        let padre = repo.get(id_producto_padre)?;
+
+        let atributos = Atributos::from_input(input);
+        self.validate_atributos(&padre, &atributos, Some(id_variante))?;
+
+        let precio_propio = input.precio_consultora.is_some() || input.precio_publico.is_some();
+        variante.nombre_producto = atributos.nombre_con_padre(&padre.nombre_producto);
+        variante.precio_consultora = input.precio_consultora.unwrap_or(padre.precio_consultora);
+        variante.precio_publico = input.precio_publico.unwrap_or(padre.precio_publico);
+        variante.precio = variante.precio_publico;
+        variante.sku = input.sku.clone();
+        variante.codigo_barras = input.codigo_barras.clone();
+
+        ProductoService::new(self.conn).update_producto(&variante)?;
+        repo.update_variante(
+            id_variante,
+            id_producto_padre,
+            atributos.tono.as_deref(),
+            atributos.tamano.as_deref(),
+            atributos.fragancia.as_deref(),
+            precio_propio,
+        )
+    }
+
+    // Lleva nombre, categoría y (si no tienen precio propio) precios del padre a sus variantes
+    pub fn sincronizar_variantes(&self, padre: &Producto) -> Result<()>
+    {
+        let repo = ProductoRepo { conn: self.conn };
+        let historial = HistorialPrecioService::new(self.conn);
+
+        for anterior in repo.list_variantes(padre.id_producto)? {
+            let mut variante = anterior.clone();
+            variante.nombre_producto = Atributos::from_producto(&anterior).nombre_con_padre(&padre.nombre_producto);
+            variante.id_categoria = padre.id_categoria;
+            if !variante.precio_propio {
+                variante.precio_consultora = padre.precio_consultora;
+                variante.precio_publico = padre.precio_publico;
+                variante.precio = padre.precio_publico;
+            }
+
+            repo.update(&variante)?;
+            historial.registrar_cambios(&anterior, &repo.get(variante.id_producto)?, OrigenCambioPrecio::Edicion)?;
+        }
+        Ok(())
+    }
+
+    fn get_padre(&self, id_producto_padre: i64) -> Result<Producto>
+    {
+        let repo = ProductoRepo { conn: self.conn };
+        let padre = match repo.get(id_producto_padre) {
+            Ok(producto) => producto,
+            Err(Error::QueryReturnedNoRows) => return Err(business_error("El producto padre no existe")),
+            Err(err) => return Err(err),
+        };
+
+        if padre.activo != 1 {
+            return Err(business_error("No se pueden agregar variantes a un producto descontinuado"));
+        }
+        if padre.id_producto_padre.is_some() {
+            return Err(business_error("Una variante no puede tener variantes propias"));
+        }
+        Ok(padre)
+    }
+
+    fn validate_atributos(&self, padre: &Producto, atributos: &Atributos, id_variante: Option<i64>) -> Result<()>
+    {
+        if atributos.is_empty() {
+            return Err(business_error("La variante debe tener al menos tono, tamaño o fragancia"));
+        }
+
+        let repo = ProductoRepo { conn: self.conn };
+        let duplicada = repo
+            .list_variantes(padre.id_producto)?
+            .iter()
+            .any(|v| Some(v.id_producto) != id_variante && atributos.same_as(v));
+        if duplicada {
+            return Err(business_error(&format!(
+                "'{}' ya tiene una variante con esos atributos",
+                padre.nombre_producto
+            )));
+        }
+        Ok(())
+    }
+}
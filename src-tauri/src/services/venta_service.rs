@@ -1,7 +1,10 @@
 use rusqlite::{Connection, Result};
-use crate::models::{EstadoPago, RegistrarAbonoInput, TipoPago, Venta, VentaCobranzaView};
+use crate::models::{Clienta, ConsultaLista, EstadoPago, Pagina, RegistrarAbonoInput, TipoPago, Venta, VentaCobranzaView};
+use crate::repos::campana_repo::CampanaRepo;
 use crate::repos::venta_repo::VentaRepo;
 use crate::services::abono_venta_service::AbonoVentaService;
+use crate::services::clienta_service::ClientaService;
+use crate::services::validar_consulta;
 
 pub struct VentaService<'a> {
     pub conn: &'a Connection,
@@ -21,17 +24,66 @@ impl <'a> VentaService<'a> {
         repo.list()
     }
 
+    pub fn list_ventas_paginadas(&self, consulta: &ConsultaLista) -> Result<Pagina<Venta>>
+    {
+        validar_consulta(consulta)?;
+        let repo = VentaRepo { conn: self.conn};
+        let (ventas, total) = repo.list_paginado(consulta)?;
+        Ok(Pagina::new(ventas, total, consulta))
+    }
+
+    // Ventas con su cobranza; el estado de pago y el saldo se filtran y ordenan en SQL
+    pub fn list_ventas_con_cobranza_paginadas(&self, consulta: &ConsultaLista) -> Result<Pagina<VentaCobranzaView>>
+    {
+        validar_consulta(consulta)?;
+        let repo = VentaRepo { conn: self.conn};
+        let (ventas, total) = repo.list_with_cobranza_paginado(consulta)?;
+        Ok(Pagina::new(ventas, total, consulta))
+    }
+
     pub fn get_venta(&self, id: i64) -> Result<Venta> 
     {
         let repo = VentaRepo { conn: self.conn};
         repo.get(id)
     }
 
+    // Venta con nombre libre: la clienta se busca o se registra en el directorio
     pub fn create_venta(&self, fecha: &str, nombre_clienta: &str, apellido_clienta: &str, total_venta: f64, tipo_pago: &TipoPago) -> Result<i64> 
+    {
+        let id_clienta = if nombre_clienta.trim().is_empty() {
+            None
+        } else {
+            let clienta_service = ClientaService::new(self.conn);
+            Some(clienta_service.obtener_o_crear_por_nombre(nombre_clienta, apellido_clienta)?.id_clienta)
+        };
+
+        let total_venta = AbonoVentaService::normalize_money(total_venta);
+        let repo = VentaRepo { conn: self.conn};
+        repo.create(fecha, id_clienta, nombre_clienta, apellido_clienta, total_venta, tipo_pago)
+    }
+
+    pub fn create_venta_para_clienta(
+        &self,
+        fecha: &str,
+        clienta: &Clienta,
+        total_venta: f64,
+        tipo_pago: &TipoPago,
+        abono_inicial: Option<f64>,
+    ) -> Result<i64>
     {
         let total_venta = AbonoVentaService::normalize_money(total_venta);
         let repo = VentaRepo { conn: self.conn};
-        repo.create(fecha, nombre_clienta, apellido_clienta, total_venta, tipo_pago)
+        let id_venta = repo.create(
+            fecha,
+            Some(clienta.id_clienta),
+            &clienta.nombre,
+            &clienta.apellido,
+            total_venta,
+            tipo_pago,
+        )?;
+
+        self.registrar_abono_inicial(id_venta, tipo_pago, abono_inicial)?;
+        Ok(id_venta)
     }
 
     pub fn create_venta_with_initial_abono(
@@ -52,6 +104,12 @@ impl <'a> VentaService<'a> {
             tipo_pago,
         )?;
 
+        self.registrar_abono_inicial(id_venta, tipo_pago, abono_inicial)?;
+        Ok(id_venta)
+    }
+
+    fn registrar_abono_inicial(&self, id_venta: i64, tipo_pago: &TipoPago, abono_inicial: Option<f64>) -> Result<()>
+    {
         if matches!(tipo_pago, TipoPago::Abono) {
             if let Some(monto) = abono_inicial {
                 let monto = AbonoVentaService::normalize_money(monto);
@@ -69,21 +127,36 @@ impl <'a> VentaService<'a> {
             }
         }
 
-        Ok(id_venta)
+        Ok(())
     }
 
+    // Sin otra campaña explícita, la venta conserva la suya salvo que cambie la fecha;
+    // entonces pasa a la campaña vigente en la nueva fecha (o a ninguna)
     pub fn update_venta(&self, venta: &Venta) -> Result<()> 
     {
+        let repo = VentaRepo { conn: self.conn};
+        let actual = repo.get(venta.id_venta)?;
+        let id_campana = match venta.id_campana {
+            Some(id_campana) if Some(id_campana) != actual.id_campana => Some(id_campana),
+            _ if venta.fecha != actual.fecha => {
+                let campanas = CampanaRepo { conn: self.conn };
+                campanas.find_por_fecha(&venta.fecha)?.map(|campana| campana.id_campana)
+            }
+            _ => actual.id_campana,
+        };
+
         let venta_normalizada = Venta {
             id_venta: venta.id_venta,
+            id_clienta: venta.id_clienta,
             fecha: venta.fecha.clone(),
             nombre_clienta: venta.nombre_clienta.clone(),
             apellido_clienta: venta.apellido_clienta.clone(),
             total_venta: AbonoVentaService::normalize_money(venta.total_venta),
             tipo_pago: venta.tipo_pago.clone(),
+            id_campana,
+            descuento: venta.descuento,
         };
 
-        let repo = VentaRepo { conn: self.conn};
         repo.update(&venta_normalizada)
     }
 
@@ -105,6 +178,7 @@ impl <'a> VentaService<'a> {
 
         Ok(VentaCobranzaView {
             id_venta: venta.id_venta,
+            id_clienta: venta.id_clienta,
             fecha: venta.fecha,
             nombre_clienta: venta.nombre_clienta,
             apellido_clienta: venta.apellido_clienta,
@@ -113,6 +187,7 @@ impl <'a> VentaService<'a> {
             total_abonado,
             saldo_pendiente,
             estado_pago,
+            id_campana: venta.id_campana,
         })
     }
 
@@ -0,0 +1,200 @@
+use app_lib::database;
+use app_lib::models::{CampanaInput, PrecioCampanaInput, TipoPago, VentaCompletaInput};
+use app_lib::services::campana_service::CampanaService;
+use app_lib::services::ganancia_service::GananciaService;
+use app_lib::services::producto_service::ProductoService;
+use app_lib::services::producto_vendido_service::ProductoVendidoService;
+use app_lib::services::promocion_service::PromocionService;
+use app_lib::services::venta_service::VentaService;
+use rusqlite::Connection;
+use std::fs;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+struct TestDb {
+    path: PathBuf,
+    conn: Connection,
+}
+
+impl TestDb {
+    fn new() -> Self {
+        let path = std::env::temp_dir().join(format!("inventario-mk-test-{}.sqlite", Uuid::new_v4()));
+        let conn = database::init_db(&path).expect("debe inicializar db de prueba");
+        Self { path, conn }
+    }
+}
+
+impl Drop for TestDb {
+    fn drop(&mut self) {
+        let wal = self.path.with_extension("sqlite-wal");
+        let shm = self.path.with_extension("sqlite-shm");
+        let _ = fs::remove_file(&wal);
+        let _ = fs::remove_file(&shm);
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn campana(nombre: &str, fecha_inicio: &str, fecha_fin: &str) -> CampanaInput {
+    CampanaInput {
+        nombre: nombre.to_string(),
+        fecha_inicio: fecha_inicio.to_string(),
+        fecha_fin: fecha_fin.to_string(),
+    }
+}
+
+#[test]
+fn campanas_no_se_empalman_y_las_ventas_toman_la_campana_de_su_fecha() {
+    let db = TestDb::new();
+    let campanas = CampanaService::new(&db.conn);
+    let ventas = VentaService::new(&db.conn);
+
+    // Venta previa a la campaña: se asigna al crear la campaña que cubre su fecha
+    let id_previa = ventas
+        .create_venta("2026-03-05", "Ana", "Perez", 100.0, &TipoPago::Contado)
+        .expect("debe crear venta");
+
+    let id_c3 = campanas
+        .create_campana(&campana("Campaña 3", "2026-03-01", "2026-03-21"))
+        .expect("debe crear campaña");
+    let err = campanas
+        .create_campana(&campana("Campaña 4", "2026-03-20", "2026-04-10"))
+        .expect_err("no debe empalmarse");
+    assert!(err.to_string().contains("se empalma con la campaña 'Campaña 3'"));
+    let id_c4 = campanas
+        .create_campana(&campana("Campaña 4", "2026-03-22", "2026-04-11"))
+        .expect("debe crear campaña contigua");
+
+    let id_nueva = ventas
+        .create_venta("2026-03-25", "Luz", "Gomez", 80.0, &TipoPago::Contado)
+        .expect("debe crear venta");
+    let id_fuera = ventas
+        .create_venta("2026-05-01", "Eva", "Ruiz", 50.0, &TipoPago::Contado)
+        .expect("debe crear venta");
+
+    assert_eq!(ventas.get_venta(id_previa).unwrap().id_campana, Some(id_c3));
+    assert_eq!(ventas.get_venta(id_nueva).unwrap().id_campana, Some(id_c4));
+    assert_eq!(ventas.get_venta(id_fuera).unwrap().id_campana, None);
+
+    let vigente = campanas.get_campana_vigente(Some("2026-04-01")).unwrap().unwrap();
+    assert_eq!(vigente.id_campana, id_c4);
+    assert_eq!(vigente.ventas, 1);
+
+    let err = campanas.delete_campana(id_c3).expect_err("tiene ventas");
+    assert!(err.to_string().contains("no se puede eliminar"));
+
+    // Al mover la fecha la venta pasa a la campaña de la nueva fecha, aunque la interfaz reenvíe la anterior
+    let mut venta = ventas.get_venta(id_previa).unwrap();
+    venta.fecha = "2026-03-30".to_string();
+    ventas.update_venta(&venta).expect("debe editar venta");
+    assert_eq!(ventas.get_venta(id_previa).unwrap().id_campana, Some(id_c4));
+    venta.fecha = "2026-05-02".to_string();
+    venta.id_campana = None;
+    ventas.update_venta(&venta).expect("debe editar venta");
+    assert_eq!(ventas.get_venta(id_previa).unwrap().id_campana, None);
+
+    // Al acortar la campaña sus ventas fuera del periodo la dejan; las que entran se asignan
+    campanas
+        .update_campana(id_c4, &campana("Campaña 4", "2026-03-26", "2026-05-10"))
+        .expect("debe acortar el inicio y extender el fin");
+    assert_eq!(ventas.get_venta(id_nueva).unwrap().id_campana, None);
+    assert_eq!(ventas.get_venta(id_fuera).unwrap().id_campana, Some(id_c4));
+    assert_eq!(ventas.get_venta(id_previa).unwrap().id_campana, Some(id_c4));
+    assert_eq!(campanas.get_campana(id_c4).unwrap().ventas, 2);
+}
+
+#[test]
+fn precio_de_campana_reemplaza_al_del_producto_y_ganancias_por_campana() {
+    let db = TestDb::new();
+    let campanas = CampanaService::new(&db.conn);
+    let id_producto = ProductoService::new(&db.conn)
+        .create_producto_with_prices("Labial", None, None, None, 10, 50.0, 80.0)
+        .expect("debe crear producto");
+
+    let id_c1 = campanas
+        .create_campana(&campana("Campaña 1", "2026-01-01", "2026-01-21"))
+        .expect("debe crear campaña");
+    let id_c2 = campanas
+        .create_campana(&campana("Campaña 2", "2026-01-22", "2026-02-11"))
+        .expect("debe crear campaña");
+    campanas
+        .set_precio(id_c1, &PrecioCampanaInput { id_producto, precio_consultora: 55.0, precio_publico: 90.0 })
+        .expect("debe fijar precio");
+    assert!(campanas
+        .set_precio(id_c1, &PrecioCampanaInput { id_producto, precio_consultora: 95.0, precio_publico: 90.0 })
+        .is_err());
+
+    let precio = campanas.precio_vigente(id_producto, None, Some("2026-01-10")).unwrap();
+    assert_eq!(precio.id_campana, Some(id_c1));
+    assert_eq!(precio.precio_publico, 90.0);
+
+    // Sin precio en la campaña 2 se usa el precio del producto
+    let precio = campanas.precio_vigente(id_producto, None, Some("2026-01-30")).unwrap();
+    assert_eq!(precio.id_campana, None);
+    assert_eq!(precio.precio_publico, 80.0);
+
+    let ventas = VentaService::new(&db.conn);
+    let items = ProductoVendidoService::new(&db.conn);
+    let id_v1 = ventas
+        .create_venta("2026-01-10", "Ana", "Perez", 180.0, &TipoPago::Contado)
+        .expect("debe crear venta");
+    items.create_producto_vendido(id_v1, id_producto, "Labial", 2, 90.0, 180.0).unwrap();
+    let id_v2 = ventas
+        .create_venta("2026-01-30", "Luz", "Gomez", 80.0, &TipoPago::Contado)
+        .expect("debe crear venta");
+    items.create_producto_vendido(id_v2, id_producto, "Labial", 1, 80.0, 80.0).unwrap();
+
+    let ganancias = GananciaService::new(&db.conn);
+    let todo = ganancias.get_reporte("2026-01-01", "2026-02-28", None).unwrap();
+    assert_eq!(todo.por_venta.len(), 2);
+
+    let c1 = ganancias.get_reporte("2026-01-01", "2026-02-28", Some(id_c1)).unwrap();
+    assert_eq!(c1.id_campana, Some(id_c1));
+    assert_eq!(c1.por_venta.len(), 1);
+    assert_eq!(c1.ingreso, 180.0);
+
+    let c2 = ganancias.get_reporte("2026-01-01", "2026-02-28", Some(id_c2)).unwrap();
+    assert_eq!(c2.ingreso, 80.0);
+
+    campanas.quitar_precio(id_c1, id_producto).unwrap();
+    assert!(campanas.listar_precios(id_c1).unwrap().is_empty());
+}
+
+#[test]
+fn venta_desde_la_interfaz_usa_el_precio_de_la_campana_vigente() {
+    let db = TestDb::new();
+    let campanas = CampanaService::new(&db.conn);
+    let id_producto = ProductoService::new(&db.conn)
+        .create_producto_with_prices("Labial", None, None, None, 10, 50.0, 80.0)
+        .expect("debe crear producto");
+    let id_c1 = campanas
+        .create_campana(&campana("Campaña 1", "2026-01-01", "2026-01-21"))
+        .expect("debe crear campaña");
+    campanas
+        .set_precio(id_c1, &PrecioCampanaInput { id_producto, precio_consultora: 55.0, precio_publico: 90.0 })
+        .expect("debe fijar precio");
+
+    // Mismo payload que arma la página de ventas para `create_venta_completa`
+    let input: VentaCompletaInput = serde_json::from_value(serde_json::json!({
+        "fecha": "2026-01-10T00:00:00.000Z",
+        "nombre_clienta": "Ana",
+        "apellido_clienta": "Perez",
+        "tipo_pago": "Contado",
+        "productos": [
+            { "id_producto": id_producto, "nombre_producto": "Labial", "cantidad": 2, "precio_unitario": null }
+        ]
+    }))
+    .expect("debe leer el payload de la interfaz");
+
+    let (id_campana, precios) = campanas.precios_venta(&input).unwrap();
+    assert_eq!(id_campana, Some(id_c1));
+    assert_eq!(precios, [90.0]);
+    let calculo = PromocionService::new(&db.conn)
+        .calcular_venta(&input.fecha, &input.productos, &precios, input.descuento.as_ref())
+        .unwrap();
+    assert_eq!(calculo.total, 180.0);
+
+    // Un precio capturado a mano se respeta
+    let mut manual = input;
+    manual.productos[0].precio_unitario = Some(85.0);
+    assert_eq!(campanas.precios_venta(&manual).unwrap().1, [85.0]);
+}
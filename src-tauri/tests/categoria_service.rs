@@ -0,0 +1,153 @@
+use app_lib::database;
+use app_lib::models::{Categoria, PromocionInput, TipoPromocion};
+use app_lib::services::categoria_service::CategoriaService;
+use app_lib::services::promocion_service::PromocionService;
+use app_lib::services::producto_service::ProductoService;
+use rusqlite::Connection;
+use std::fs;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+struct TestDb {
+    path: PathBuf,
+    conn: Connection,
+}
+
+impl TestDb {
+    fn new() -> Self {
+        let path = std::env::temp_dir().join(format!("inventario-mk-test-{}.sqlite", Uuid::new_v4()));
+        let conn = database::init_db(&path).expect("debe inicializar db de prueba");
+        Self { path, conn }
+    }
+}
+
+impl Drop for TestDb {
+    fn drop(&mut self) {
+        let wal = self.path.with_extension("sqlite-wal");
+        let shm = self.path.with_extension("sqlite-shm");
+        let _ = fs::remove_file(&wal);
+        let _ = fs::remove_file(&shm);
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[test]
+fn subcategorias_forman_arbol_sin_ciclos_y_filtran_descendientes() {
+    let db = TestDb::new();
+    let categorias = CategoriaService::new(&db.conn);
+    let productos = ProductoService::new(&db.conn);
+
+    let id_maquillaje = categorias.create_categoria("Maquillaje").unwrap();
+    let id_labios = categorias.create_subcategoria("Labios", id_maquillaje).unwrap();
+    let id_labiales = categorias.create_subcategoria("Labiales", id_labios).unwrap();
+    let id_cuidado = categorias.create_categoria("Cuidado facial").unwrap();
+
+    let err = categorias
+        .mover_categoria(id_maquillaje, Some(id_labiales))
+        .expect_err("no puede quedar dentro de su propia subcategoría");
+    assert!(err.to_string().contains("una de sus subcategorías"));
+    assert!(categorias.mover_categoria(id_labios, Some(id_labios)).is_err());
+
+    productos
+        .create_producto_with_prices("Labial rojo", Some(id_labiales), None, None, 1, 50.0, 80.0)
+        .unwrap();
+    productos
+        .create_producto_with_prices("Bálsamo", Some(id_labios), None, None, 1, 30.0, 50.0)
+        .unwrap();
+    productos
+        .create_producto_with_prices("Crema", Some(id_cuidado), None, None, 1, 40.0, 70.0)
+        .unwrap();
+
+    let arbol = categorias.arbol_categorias().unwrap();
+    assert_eq!(arbol.len(), 2);
+    let maquillaje = arbol.iter().find(|nodo| nodo.id_categoria == id_maquillaje).unwrap();
+    let labiales = &maquillaje.hijos[0].hijos[0];
+    assert_eq!(labiales.ruta, "Maquillaje > Labios > Labiales");
+    assert_eq!(labiales.productos, 1);
+
+    assert_eq!(productos.list_productos_por_categoria(id_maquillaje, true).unwrap().len(), 2);
+    assert!(productos.list_productos_por_categoria(id_maquillaje, false).unwrap().is_empty());
+    assert_eq!(productos.list_productos_por_categoria(id_labios, false).unwrap().len(), 1);
+
+    // Al borrar una categoría intermedia sus subcategorías suben un nivel
+    categorias.delete_categoria(id_labios, None).unwrap();
+    assert_eq!(categorias.get_categoria(id_labiales).unwrap().id_categoria_padre, Some(id_maquillaje));
+
+    categorias.mover_categoria(id_labiales, None).unwrap();
+    assert_eq!(categorias.arbol_categorias().unwrap().len(), 3);
+}
+
+#[test]
+fn eliminar_y_fusionar_categorias_mueve_productos_y_reporta_afectados() {
+    let db = TestDb::new();
+    let categorias = CategoriaService::new(&db.conn);
+    let productos = ProductoService::new(&db.conn);
+
+    let id_labiales = categorias.create_categoria("Labiales").unwrap();
+    let id_labios = categorias.create_categoria("Labios").unwrap();
+    let id_ojos = categorias.create_categoria("Ojos").unwrap();
+
+    let err = categorias.create_categoria("  labiales ").expect_err("nombre repetido");
+    assert_eq!(err.to_string(), "Ya existe una categoría llamada 'Labiales'");
+    let err = categorias
+        .update_categoria(&Categoria {
+            id_categoria: id_ojos,
+            nombre: "LABIOS".to_string(),
+            stock_minimo_default: None,
+            id_categoria_padre: None,
+        })
+        .expect_err("renombrar a un nombre existente");
+    assert!(err.to_string().contains("Ya existe una categoría llamada 'Labios'"));
+
+    let id_rojo = productos
+        .create_producto_with_prices("Labial rojo", Some(id_labiales), None, None, 1, 50.0, 80.0)
+        .unwrap();
+    let id_viejo = productos
+        .create_producto_with_prices("Labial viejo", Some(id_labiales), None, None, 0, 50.0, 80.0)
+        .unwrap();
+    productos.delete_producto(id_viejo).unwrap();
+    let id_sombra = productos
+        .create_producto_with_prices("Sombra", Some(id_ojos), None, None, 1, 30.0, 60.0)
+        .unwrap();
+    let id_promocion = PromocionService::new(&db.conn)
+        .create_promocion(&PromocionInput {
+            nombre: "Labiales 10%".to_string(),
+            tipo: TipoPromocion::Porcentaje,
+            id_producto: None,
+            id_categoria: Some(id_labiales),
+            cantidad_lleva: None,
+            cantidad_paga: None,
+            precio_paquete: None,
+            porcentaje: Some(10.0),
+            fecha_inicio: None,
+            fecha_fin: None,
+            activa: true,
+        })
+        .unwrap();
+
+    let impacto = categorias.impacto_eliminacion(id_labiales).unwrap();
+    assert_eq!((impacto.productos_activos, impacto.productos_descontinuados, impacto.promociones), (1, 1, 1));
+
+    let err = categorias
+        .delete_categoria(id_labiales, None)
+        .expect_err("las promociones necesitan destino");
+    assert!(err.to_string().contains("indica a qué categoría moverlas"));
+
+    let impacto = categorias.delete_categoria(id_labiales, Some(id_labios)).unwrap();
+    assert_eq!(impacto.id_categoria_destino, Some(id_labios));
+    assert_eq!(productos.get_producto(id_rojo).unwrap().id_categoria, Some(id_labios));
+    assert_eq!(productos.get_producto(id_viejo).unwrap().id_categoria, Some(id_labios));
+    let promocion = PromocionService::new(&db.conn).get_promocion(id_promocion).unwrap();
+    assert_eq!(promocion.id_categoria, Some(id_labios));
+
+    let impacto = categorias.merge_categorias(id_ojos, id_labios).unwrap();
+    assert_eq!(impacto.productos_activos, 1);
+    assert_eq!(productos.get_producto(id_sombra).unwrap().id_categoria, Some(id_labios));
+    assert_eq!(categorias.list_categorias().unwrap().len(), 1);
+
+    // Sin destino los productos quedan sin categoría, y se reporta
+    PromocionService::new(&db.conn).delete_promocion(id_promocion).unwrap();
+    let impacto = categorias.delete_categoria(id_labios, None).unwrap();
+    assert_eq!(impacto.productos_activos, 2);
+    assert_eq!(productos.get_producto(id_sombra).unwrap().id_categoria, None);
+}
@@ -0,0 +1,166 @@
+use app_lib::database;
+use app_lib::models::{ClientaInput, TipoPago, VentaCompletaInput};
+use app_lib::services::clienta_service::ClientaService;
+use app_lib::services::venta_service::VentaService;
+use rusqlite::Connection;
+use std::fs;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+struct TestDb {
+    path: PathBuf,
+    conn: Connection,
+}
+
+impl TestDb {
+    fn new() -> Self {
+        let path = std::env::temp_dir().join(format!("inventario-mk-test-{}.sqlite", Uuid::new_v4()));
+        let conn = database::init_db(&path).expect("debe inicializar db de prueba");
+        Self { path, conn }
+    }
+}
+
+impl Drop for TestDb {
+    fn drop(&mut self) {
+        let wal = self.path.with_extension("sqlite-wal");
+        let shm = self.path.with_extension("sqlite-shm");
+        let _ = fs::remove_file(&wal);
+        let _ = fs::remove_file(&shm);
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn venta_input(id_clienta: Option<i64>, clienta_nueva: Option<ClientaInput>, nombre: &str, apellido: &str) -> VentaCompletaInput {
+    VentaCompletaInput {
+        fecha: "2026-04-01".to_string(),
+        id_clienta,
+        clienta_nueva,
+        nombre_clienta: nombre.to_string(),
+        apellido_clienta: apellido.to_string(),
+        tipo_pago: TipoPago::Contado,
+        productos: Vec::new(),
+        id_campana: None,
+        descuento: None,
+    }
+}
+
+#[test]
+fn migracion_unifica_nombres_repetidos_en_una_clienta() {
+    let path = std::env::temp_dir().join(format!("inventario-mk-test-{}.sqlite", Uuid::new_v4()));
+    {
+        // Base de datos anterior al directorio de clientas
+        let legacy = Connection::open(&path).expect("debe crear db legacy");
+        legacy
+            .execute_batch(
+                "CREATE TABLE categorias (id_categoria INTEGER PRIMARY KEY AUTOINCREMENT, nombre TEXT NOT NULL UNIQUE);
+                 CREATE TABLE productos (id_producto INTEGER PRIMARY KEY AUTOINCREMENT, nombre_producto TEXT NOT NULL, id_categoria INTEGER, ruta_imagen TEXT, stock INTEGER NOT NULL DEFAULT 0, precio REAL NOT NULL DEFAULT 0.0);
+                 CREATE TABLE ventas (id_venta INTEGER PRIMARY KEY AUTOINCREMENT, fecha TEXT, nombre_clienta TEXT NOT NULL, apellido_clienta TEXT NOT NULL DEFAULT '', total_venta REAL NOT NULL, tipo_pago TEXT NOT NULL DEFAULT 'De Contado');
+                 CREATE TABLE productos_vendidos (id_producto_vendido INTEGER PRIMARY KEY AUTOINCREMENT, id_venta INTEGER NOT NULL, id_producto INTEGER NOT NULL, cantidad INTEGER NOT NULL, precio_unitario REAL NOT NULL, subtotal REAL NOT NULL);
+                 INSERT INTO ventas (fecha, nombre_clienta, apellido_clienta, total_venta) VALUES ('2026-01-01', 'Maria', 'Lopez', 10.0);
+                 INSERT INTO ventas (fecha, nombre_clienta, apellido_clienta, total_venta) VALUES ('2026-01-02', ' maria ', 'LOPEZ', 20.0);
+                 INSERT INTO ventas (fecha, nombre_clienta, apellido_clienta, total_venta) VALUES ('2026-01-03', 'Rosa', 'Diaz', 30.0);",
+            )
+            .expect("debe poblar db legacy");
+    }
+
+    let conn = database::init_db(&path).expect("debe migrar db legacy");
+
+    let clientas: i64 = conn
+        .query_row("SELECT COUNT(*) FROM clientas", [], |row| row.get(0))
+        .expect("debe contar clientas");
+    assert_eq!(clientas, 2);
+
+    let distintas: i64 = conn
+        .query_row(
+            "SELECT COUNT(DISTINCT id_clienta) FROM ventas WHERE nombre_clienta LIKE '%maria%'",
+            [],
+            |row| row.get(0),
+        )
+        .expect("debe agrupar ventas de la misma clienta");
+    assert_eq!(distintas, 1);
+
+    let sin_clienta: i64 = conn
+        .query_row("SELECT COUNT(*) FROM ventas WHERE id_clienta IS NULL", [], |row| row.get(0))
+        .expect("debe contar ventas sin clienta");
+    assert_eq!(sin_clienta, 0);
+
+    drop(conn);
+    let _ = fs::remove_file(path.with_extension("sqlite-wal"));
+    let _ = fs::remove_file(path.with_extension("sqlite-shm"));
+    let _ = fs::remove_file(&path);
+}
+
+#[test]
+fn resolver_clienta_reutiliza_nombre_existente_sin_distinguir_mayusculas() {
+    let db = TestDb::new();
+    let service = ClientaService::new(&db.conn);
+
+    let primera = service
+        .resolver_clienta_venta(&venta_input(None, None, "Ana", "Perez"))
+        .expect("debe registrar clienta por nombre");
+    let segunda = service
+        .resolver_clienta_venta(&venta_input(None, None, "  ana ", "PEREZ"))
+        .expect("debe reutilizar clienta existente");
+
+    assert_eq!(primera.id_clienta, segunda.id_clienta);
+    assert_eq!(service.list_clientas().expect("debe listar").len(), 1);
+}
+
+#[test]
+fn resolver_clienta_prioriza_id_y_acepta_clienta_nueva() {
+    let db = TestDb::new();
+    let service = ClientaService::new(&db.conn);
+
+    let id_existente = service
+        .create_clienta(&ClientaInput {
+            nombre: "Bea".to_string(),
+            apellido: "Lopez".to_string(),
+            telefono: Some("5551234".to_string()),
+            direccion: None,
+            notas: None,
+        })
+        .expect("debe crear clienta");
+
+    let por_id = service
+        .resolver_clienta_venta(&venta_input(Some(id_existente), None, "Otra", "Persona"))
+        .expect("debe usar la clienta indicada");
+    assert_eq!(por_id.id_clienta, id_existente);
+
+    let nueva = service
+        .resolver_clienta_venta(&venta_input(
+            None,
+            Some(ClientaInput {
+                nombre: "Caro".to_string(),
+                apellido: "Diaz".to_string(),
+                telefono: Some(" 5559876 ".to_string()),
+                direccion: Some("Centro".to_string()),
+                notas: None,
+            }),
+            "",
+            "",
+        ))
+        .expect("debe registrar clienta nueva en linea");
+    assert_eq!(nueva.telefono, "5559876");
+
+    let encontradas = service.buscar_clientas("9876").expect("debe buscar por telefono");
+    assert_eq!(encontradas.len(), 1);
+    assert_eq!(encontradas[0].id_clienta, nueva.id_clienta);
+}
+
+#[test]
+fn eliminar_clienta_con_ventas_es_rechazado() {
+    let db = TestDb::new();
+    let venta_service = VentaService::new(&db.conn);
+    let clienta_service = ClientaService::new(&db.conn);
+
+    let id_venta = venta_service
+        .create_venta("2026-04-02", "Dani", "Ruiz", 50.0, &TipoPago::Contado)
+        .expect("debe crear venta");
+    let venta = venta_service.get_venta(id_venta).expect("debe obtener venta");
+    let id_clienta = venta.id_clienta.expect("la venta debe quedar ligada a una clienta");
+
+    let err = clienta_service
+        .delete_clienta(id_clienta)
+        .expect_err("no debe eliminar clienta con ventas");
+    assert!(err.to_string().contains("ventas registradas"));
+}
@@ -0,0 +1,141 @@
+use app_lib::database;
+use app_lib::models::{
+    CompraInput,
+    EstadoCompra,
+    LineaCompraInput,
+    MotivoMovimiento,
+    ProveedorInput,
+    RecepcionLineaInput,
+    RegistrarPagoCompraInput,
+};
+use app_lib::services::compra_service::CompraService;
+use app_lib::services::movimiento_inventario_service::MovimientoInventarioService;
+use app_lib::services::producto_service::ProductoService;
+use app_lib::services::proveedor_service::ProveedorService;
+use rusqlite::Connection;
+use std::fs;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+struct TestDb {
+    path: PathBuf,
+    conn: Connection,
+}
+
+impl TestDb {
+    fn new() -> Self {
+        let path = std::env::temp_dir().join(format!("inventario-mk-test-{}.sqlite", Uuid::new_v4()));
+        let conn = database::init_db(&path).expect("debe inicializar db de prueba");
+        Self { path, conn }
+    }
+}
+
+impl Drop for TestDb {
+    fn drop(&mut self) {
+        let wal = self.path.with_extension("sqlite-wal");
+        let shm = self.path.with_extension("sqlite-shm");
+        let _ = fs::remove_file(&wal);
+        let _ = fs::remove_file(&shm);
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn crear_orden(db: &TestDb, id_producto: i64) -> i64 {
+    let id_proveedor = ProveedorService::new(&db.conn)
+        .create_proveedor(&ProveedorInput {
+            nombre: "Distribuidora Norte".to_string(),
+            contacto: None,
+            telefono: None,
+            notas: None,
+        })
+        .expect("debe crear proveedor");
+
+    CompraService::new(&db.conn)
+        .create_compra(&CompraInput {
+            id_proveedor: Some(id_proveedor),
+            fecha: Some("2026-05-01 10:00:00".to_string()),
+            notas: None,
+            lineas: vec![LineaCompraInput { id_producto, cantidad: 10, costo_unitario: 25.0 }],
+        })
+        .expect("debe crear compra")
+}
+
+#[test]
+fn recepcion_parcial_incrementa_stock_y_registra_movimiento() {
+    let db = TestDb::new();
+    let id_producto = ProductoService::new(&db.conn)
+        .create_producto_with_prices("Rimel", None, None, None, 2, 25.0, 60.0)
+        .expect("debe crear producto");
+    let id_compra = crear_orden(&db, id_producto);
+    let service = CompraService::new(&db.conn);
+
+    let compra = service.get_compra(id_compra).expect("debe obtener compra");
+    assert_eq!(compra.compra.estado, EstadoCompra::Pendiente);
+    assert_eq!(compra.compra.total_compra, 250.0);
+
+    let id_detalle = compra.lineas[0].id_detalle;
+    let recibida = service
+        .recibir_compra(id_compra, &[RecepcionLineaInput { id_detalle, cantidad: 4, codigo_lote: None, fecha_caducidad: None }])
+        .expect("debe recibir parcialmente");
+    assert_eq!(recibida.estado, EstadoCompra::Parcial);
+
+    let stock: i64 = db
+        .conn
+        .query_row("SELECT stock FROM productos WHERE id_producto = ?1", [id_producto], |row| row.get(0))
+        .expect("debe leer stock");
+    assert_eq!(stock, 6);
+
+    let kardex = MovimientoInventarioService::new(&db.conn)
+        .listar_por_producto(id_producto)
+        .expect("debe listar movimientos");
+    assert_eq!(kardex[0].motivo, MotivoMovimiento::Compra);
+    assert_eq!(kardex[0].delta, 4);
+    assert_eq!(kardex[0].id_referencia, Some(id_compra));
+
+    let err = service
+        .recibir_compra(id_compra, &[RecepcionLineaInput { id_detalle, cantidad: 7, codigo_lote: None, fecha_caducidad: None }])
+        .expect_err("no debe recibir más de lo pendiente");
+    assert!(err.to_string().contains("excede lo pendiente"));
+
+    let completa = service
+        .recibir_compra(id_compra, &[RecepcionLineaInput { id_detalle, cantidad: 6, codigo_lote: None, fecha_caducidad: None }])
+        .expect("debe completar recepción");
+    assert_eq!(completa.estado, EstadoCompra::Recibida);
+
+    let err = service.delete_compra(id_compra).expect_err("no debe eliminar compra recibida");
+    assert!(err.to_string().contains("mercancía recibida"));
+}
+
+#[test]
+fn pagos_de_compra_no_exceden_el_saldo() {
+    let db = TestDb::new();
+    let id_producto = ProductoService::new(&db.conn)
+        .create_producto_with_prices("Base", None, None, None, 0, 25.0, 60.0)
+        .expect("debe crear producto");
+    let id_compra = crear_orden(&db, id_producto);
+    let service = CompraService::new(&db.conn);
+
+    service
+        .registrar_pago(&RegistrarPagoCompraInput {
+            id_compra,
+            monto_pago: 100.0,
+            fecha_pago: None,
+            observacion: None,
+        })
+        .expect("debe registrar pago");
+
+    let compra = service.get_compra(id_compra).expect("debe obtener compra");
+    assert_eq!(compra.compra.total_pagado, 100.0);
+    assert_eq!(compra.compra.saldo_pendiente, 150.0);
+    assert_eq!(compra.pagos.len(), 1);
+
+    let err = service
+        .registrar_pago(&RegistrarPagoCompraInput {
+            id_compra,
+            monto_pago: 200.0,
+            fecha_pago: None,
+            observacion: None,
+        })
+        .expect_err("no debe exceder saldo");
+    assert!(err.to_string().contains("excede el saldo"));
+}
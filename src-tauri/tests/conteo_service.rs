@@ -0,0 +1,86 @@
+use app_lib::database;
+use app_lib::models::{EstadoConteo, MotivoAjuste};
+use app_lib::services::conteo_service::ConteoService;
+use app_lib::services::movimiento_inventario_service::MovimientoInventarioService;
+use app_lib::services::producto_service::ProductoService;
+use rusqlite::Connection;
+use std::fs;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+struct TestDb {
+    path: PathBuf,
+    conn: Connection,
+}
+
+impl TestDb {
+    fn new() -> Self {
+        let path = std::env::temp_dir().join(format!("inventario-mk-test-{}.sqlite", Uuid::new_v4()));
+        let conn = database::init_db(&path).expect("debe inicializar db de prueba");
+        Self { path, conn }
+    }
+}
+
+impl Drop for TestDb {
+    fn drop(&mut self) {
+        let wal = self.path.with_extension("sqlite-wal");
+        let shm = self.path.with_extension("sqlite-shm");
+        let _ = fs::remove_file(&wal);
+        let _ = fs::remove_file(&shm);
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[test]
+fn conteo_parcial_reporta_varianza_y_aplica_ajustes() {
+    let db = TestDb::new();
+    let productos = ProductoService::new(&db.conn);
+    let conteos = ConteoService::new(&db.conn);
+
+    let id_labial = productos
+        .create_producto_with_prices("Labial", None, None, None, 10, 50.0, 80.0)
+        .expect("debe crear producto");
+    let id_rimel = productos
+        .create_producto_with_prices("Rimel", None, None, None, 4, 30.0, 60.0)
+        .expect("debe crear producto");
+    let id_crema = productos
+        .create_producto_with_prices("Crema", None, None, None, 7, 20.0, 40.0)
+        .expect("debe crear producto");
+
+    let id_conteo = conteos.abrir_conteo("Campaña 5").expect("debe abrir conteo");
+    assert!(conteos.abrir_conteo("Otro").is_err(), "solo un conteo abierto a la vez");
+
+    conteos.registrar_cantidad(id_conteo, id_labial, 9).unwrap();
+    conteos.registrar_cantidad(id_conteo, id_rimel, 5).unwrap();
+    conteos.registrar_cantidad(id_conteo, id_rimel, 6).unwrap();
+
+    let reporte = conteos.get_reporte(id_conteo).expect("debe generar reporte");
+    assert_eq!(reporte.lineas.len(), 2);
+    assert_eq!(reporte.valor_faltante, 50.0);
+    assert_eq!(reporte.valor_sobrante, 60.0);
+    assert_eq!(reporte.valor_neto, 10.0);
+
+    let aplicado = conteos.aplicar_conteo(id_conteo).expect("debe aplicar conteo");
+    assert_eq!(aplicado.conteo.estado, EstadoConteo::Cerrado);
+    assert_eq!(productos.get_producto(id_labial).unwrap().stock, 9);
+    assert_eq!(productos.get_producto(id_rimel).unwrap().stock, 6);
+    assert_eq!(productos.get_producto(id_crema).unwrap().stock, 7, "producto no contado no cambia");
+
+    let kardex = MovimientoInventarioService::new(&db.conn)
+        .listar_por_producto(id_labial)
+        .unwrap();
+    assert_eq!(kardex[0].motivo_ajuste, Some(MotivoAjuste::Reconteo));
+    assert_eq!(kardex[0].delta, -1);
+
+    // El conteo cerrado conserva el stock y precio del cierre
+    let mut labial = productos.get_producto(id_labial).unwrap();
+    labial.precio_consultora = 70.0;
+    labial.precio_publico = 90.0;
+    productos.update_producto(&labial).unwrap();
+
+    let historico = conteos.get_reporte(id_conteo).expect("debe leer conteo cerrado");
+    let linea = historico.lineas.iter().find(|l| l.id_producto == id_labial).unwrap();
+    assert_eq!(linea.stock_sistema, 10);
+    assert_eq!(linea.precio_consultora, 50.0);
+    assert!(conteos.registrar_cantidad(id_conteo, id_crema, 1).is_err());
+}
@@ -0,0 +1,91 @@
+use app_lib::database;
+use app_lib::models::{RegistrarAbonoInput, TipoPago};
+use app_lib::services::abono_venta_service::AbonoVentaService;
+use app_lib::services::estado_cuenta_service::EstadoCuentaService;
+use app_lib::services::venta_service::VentaService;
+use rusqlite::Connection;
+use std::fs;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+struct TestDb {
+    path: PathBuf,
+    conn: Connection,
+}
+
+impl TestDb {
+    fn new() -> Self {
+        let path = std::env::temp_dir().join(format!("inventario-mk-test-{}.sqlite", Uuid::new_v4()));
+        let conn = database::init_db(&path).expect("debe inicializar db de prueba");
+        Self { path, conn }
+    }
+}
+
+impl Drop for TestDb {
+    fn drop(&mut self) {
+        let wal = self.path.with_extension("sqlite-wal");
+        let shm = self.path.with_extension("sqlite-shm");
+        let _ = fs::remove_file(&wal);
+        let _ = fs::remove_file(&shm);
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[test]
+fn estado_cuenta_suma_saldo_de_todas_las_ventas_abiertas() {
+    let db = TestDb::new();
+    let venta_service = VentaService::new(&db.conn);
+    let abono_service = AbonoVentaService::new(&db.conn);
+
+    let id_venta_1 = venta_service
+        .create_venta_with_initial_abono("2026-03-01", "Maria", "Lopez", 100.0, &TipoPago::Abono, Some(20.0))
+        .expect("debe crear primera venta a abonos");
+    venta_service
+        .create_venta("2026-03-05", "maria", "lopez", 50.0, &TipoPago::Contado)
+        .expect("debe crear venta de contado");
+    let id_venta_3 = venta_service
+        .create_venta("2026-03-10", "Maria", "Lopez", 80.0, &TipoPago::Abono)
+        .expect("debe crear segunda venta a abonos");
+
+    // Otra clienta no debe aparecer en el estado de cuenta
+    venta_service
+        .create_venta("2026-03-11", "Rosa", "Diaz", 500.0, &TipoPago::Abono)
+        .expect("debe crear venta de otra clienta");
+
+    abono_service
+        .registrar_abono(&RegistrarAbonoInput {
+            id_venta: id_venta_1,
+            monto_abono: 30.0,
+            fecha_abono: Some("2026-03-12 10:00:00".to_string()),
+            metodo_registro: Some("manual".to_string()),
+            observacion: None,
+        })
+        .expect("debe registrar abono");
+
+    let id_clienta = venta_service
+        .get_venta(id_venta_3)
+        .expect("debe obtener venta")
+        .id_clienta
+        .expect("la venta debe tener clienta");
+
+    let estado = EstadoCuentaService::new(&db.conn)
+        .get_estado_cuenta(id_clienta)
+        .expect("debe generar estado de cuenta");
+
+    assert_eq!(estado.ventas.len(), 3);
+    assert_eq!(estado.ventas_abiertas, 2);
+    // (100 - 20 - 30) + 80 = 130
+    assert!((estado.saldo_total - 130.0).abs() < 0.0001);
+    assert!((estado.total_comprado - 230.0).abs() < 0.0001);
+
+    let ultimo = estado.movimientos.last().expect("debe tener movimientos");
+    assert_eq!(ultimo.concepto, "Abono");
+    assert!((ultimo.saldo - 130.0).abs() < 0.0001);
+
+    // La venta de contado se paga al momento y no altera el saldo acumulado
+    let saldos: Vec<f64> = estado.movimientos.iter().map(|m| m.saldo).collect();
+    assert_eq!(saldos.len(), 5);
+    assert!((saldos[0] - 100.0).abs() < 0.0001);
+    assert!((saldos[1] - 100.0).abs() < 0.0001);
+    assert!((saldos[2] - 180.0).abs() < 0.0001);
+}
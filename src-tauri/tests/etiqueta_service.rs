@@ -0,0 +1,93 @@
+use app_lib::database;
+use app_lib::models::{EtiquetaInput, FormatoEtiquetas};
+use app_lib::pdf;
+use app_lib::services::etiqueta_service::EtiquetaService;
+use app_lib::services::producto_service::ProductoService;
+use rusqlite::Connection;
+use std::fs;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+struct TestDb {
+    path: PathBuf,
+    conn: Connection,
+}
+
+impl TestDb {
+    fn new() -> Self {
+        let path = std::env::temp_dir().join(format!("inventario-mk-test-{}.sqlite", Uuid::new_v4()));
+        let conn = database::init_db(&path).expect("debe inicializar db de prueba");
+        Self { path, conn }
+    }
+}
+
+impl Drop for TestDb {
+    fn drop(&mut self) {
+        let wal = self.path.with_extension("sqlite-wal");
+        let shm = self.path.with_extension("sqlite-shm");
+        let _ = fs::remove_file(&wal);
+        let _ = fs::remove_file(&shm);
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn paginas(pdf: &[u8]) -> usize {
+    String::from_utf8_lossy(pdf).matches("/Type /Page /Parent").count()
+}
+
+#[test]
+fn code128_usa_juego_c_para_numeros_pares_y_calcula_verificador() {
+    // Inicio C, 12, 34, verificador (105 + 12 + 2*34) % 103 = 82, paro
+    assert_eq!(pdf::code128_simbolos("1234"), Some(vec![105, 12, 34, 82, 106]));
+    // Impar: juego B, '7' = 23, verificador (104 + 23) % 103 = 24
+    assert_eq!(pdf::code128_simbolos("7"), Some(vec![104, 23, 24, 106]));
+    assert_eq!(pdf::code128_simbolos(""), None);
+
+    let (barras, modulos) = pdf::code128_barras(&[105, 12, 34, 82, 106]);
+    assert_eq!(modulos, 11 * 4 + 13);
+    assert_eq!(barras.len(), 3 * 4 + 4);
+}
+
+#[test]
+fn genera_hojas_de_etiquetas_segun_formato() {
+    let db = TestDb::new();
+    let productos = ProductoService::new(&db.conn);
+    let id_labial = productos
+        .create_producto_with_prices("Labial (rojo) pasión", None, None, None, 5, 50.0, 80.0)
+        .expect("debe crear producto");
+    let id_crema = productos
+        .create_producto_with_prices("Crema", None, None, None, 5, 40.0, 70.0)
+        .expect("debe crear producto");
+
+    let etiquetas = EtiquetaService::new(&db.conn);
+    let items = vec![
+        EtiquetaInput { id_producto: id_labial, cantidad: 25 },
+        EtiquetaInput { id_producto: id_crema, cantidad: 6 },
+    ];
+
+    let carta = etiquetas.generar_pdf(&items, FormatoEtiquetas::Carta3x10).expect("debe generar pdf");
+    assert!(carta.starts_with(b"%PDF-1.4"));
+    assert!(carta.ends_with(b"%%EOF\n"));
+    assert_eq!(paginas(&carta), 2);
+    // Paréntesis escapados y acentos en WinAnsi
+    assert!(carta.windows(b"Labial \\(rojo\\) pasi\xF3n".len()).any(|w| w == b"Labial \\(rojo\\) pasi\xF3n"));
+    assert!(String::from_utf8_lossy(&carta).contains("($80.00) Tj"));
+
+    let a4 = etiquetas.generar_pdf(&items, FormatoEtiquetas::A4_3x7).expect("debe generar pdf");
+    assert_eq!(paginas(&a4), 2);
+
+    let err = etiquetas
+        .generar_pdf(&[EtiquetaInput { id_producto: 999, cantidad: 1 }], FormatoEtiquetas::Carta3x10)
+        .expect_err("producto inexistente");
+    assert!(err.to_string().contains("#999 no existe"));
+
+    let err = etiquetas
+        .generar_pdf(&[EtiquetaInput { id_producto: id_crema, cantidad: 0 }], FormatoEtiquetas::Carta3x10)
+        .expect_err("cantidad invalida");
+    assert!(err.to_string().contains("mayor a 0"));
+
+    let err = etiquetas
+        .generar_pdf(&[EtiquetaInput { id_producto: id_crema, cantidad: 1_000_000_000 }], FormatoEtiquetas::Carta3x10)
+        .expect_err("cantidad excesiva");
+    assert!(err.to_string().contains("No se pueden imprimir más de 9000 etiquetas"));
+}
@@ -0,0 +1,111 @@
+use app_lib::database;
+use app_lib::models::{CompraInput, LineaCompraInput, RecepcionLineaInput, TipoPago};
+use app_lib::services::compra_service::CompraService;
+use app_lib::services::ganancia_service::GananciaService;
+use app_lib::services::producto_service::ProductoService;
+use app_lib::services::producto_vendido_service::ProductoVendidoService;
+use app_lib::services::venta_service::VentaService;
+use rusqlite::Connection;
+use std::fs;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+struct TestDb {
+    path: PathBuf,
+    conn: Connection,
+}
+
+impl TestDb {
+    fn new() -> Self {
+        let path = std::env::temp_dir().join(format!("inventario-mk-test-{}.sqlite", Uuid::new_v4()));
+        let conn = database::init_db(&path).expect("debe inicializar db de prueba");
+        Self { path, conn }
+    }
+}
+
+impl Drop for TestDb {
+    fn drop(&mut self) {
+        let wal = self.path.with_extension("sqlite-wal");
+        let shm = self.path.with_extension("sqlite-shm");
+        let _ = fs::remove_file(&wal);
+        let _ = fs::remove_file(&shm);
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn recibir(db: &TestDb, id_producto: i64, cantidad: i64, costo_unitario: f64) {
+    let service = CompraService::new(&db.conn);
+    let id_compra = service
+        .create_compra(&CompraInput {
+            id_proveedor: None,
+            fecha: None,
+            notas: None,
+            lineas: vec![LineaCompraInput { id_producto, cantidad, costo_unitario }],
+        })
+        .expect("debe crear compra");
+    let id_detalle = service.get_compra(id_compra).expect("debe obtener compra").lineas[0].id_detalle;
+    service
+        .recibir_compra(id_compra, &[RecepcionLineaInput { id_detalle, cantidad, codigo_lote: None, fecha_caducidad: None }])
+        .expect("debe recibir compra");
+}
+
+#[test]
+fn recepcion_recalcula_costo_promedio_ponderado() {
+    let db = TestDb::new();
+    let producto_service = ProductoService::new(&db.conn);
+    let id_producto = producto_service
+        .create_producto_with_prices("Sombra", None, None, None, 0, 45.0, 100.0)
+        .expect("debe crear producto");
+    assert_eq!(producto_service.get_producto(id_producto).unwrap().costo_promedio, 45.0);
+
+    recibir(&db, id_producto, 10, 40.0);
+    assert_eq!(producto_service.get_producto(id_producto).unwrap().costo_promedio, 40.0);
+
+    recibir(&db, id_producto, 10, 60.0);
+    let producto = producto_service.get_producto(id_producto).unwrap();
+    assert_eq!(producto.stock, 20);
+    assert_eq!(producto.costo_promedio, 50.0);
+}
+
+#[test]
+fn ganancia_usa_el_costo_congelado_al_vender() {
+    let db = TestDb::new();
+    let producto_service = ProductoService::new(&db.conn);
+    let id_producto = producto_service
+        .create_producto_with_prices("Labial", None, None, None, 10, 50.0, 100.0)
+        .expect("debe crear producto");
+
+    let id_venta = VentaService::new(&db.conn)
+        .create_venta("2026-06-10", "Ana", "Perez", 200.0, &TipoPago::Contado)
+        .expect("debe crear venta");
+    let id_item = ProductoVendidoService::new(&db.conn)
+        .create_producto_vendido(id_venta, id_producto, "Labial", 2, 100.0, 200.0)
+        .expect("debe registrar item vendido");
+
+    // Un cambio de precio posterior no altera la ganancia histórica
+    let mut producto = producto_service.get_producto(id_producto).unwrap();
+    producto.precio_consultora = 80.0;
+    producto.precio_publico = 120.0;
+    producto_service.update_producto(&producto).expect("debe actualizar precios");
+    recibir(&db, id_producto, 8, 80.0);
+
+    let item = ProductoVendidoService::new(&db.conn).get_producto_vendido(id_item).unwrap();
+    assert_eq!(item.costo_unitario, 50.0);
+
+    let reporte = GananciaService::new(&db.conn)
+        .get_reporte("2026-06-01", "2026-06-30", None)
+        .expect("debe calcular reporte");
+    assert_eq!(reporte.ingreso, 200.0);
+    assert_eq!(reporte.costo, 100.0);
+    assert_eq!(reporte.ganancia, 100.0);
+    assert_eq!(reporte.margen_porcentaje, 50.0);
+    assert_eq!(reporte.por_venta.len(), 1);
+    assert_eq!(reporte.por_producto[0].unidades, 2);
+    assert_eq!(reporte.por_producto[0].ganancia, 100.0);
+
+    let vacio = GananciaService::new(&db.conn)
+        .get_reporte("2026-07-01", "2026-07-31", None)
+        .expect("debe calcular reporte vacío");
+    assert!(vacio.por_venta.is_empty());
+    assert_eq!(vacio.ganancia, 0.0);
+}
@@ -0,0 +1,75 @@
+use app_lib::database;
+use app_lib::models::{CampoPrecio, OrigenCambioPrecio};
+use app_lib::services::historial_precio_service::HistorialPrecioService;
+use app_lib::services::producto_service::ProductoService;
+use rusqlite::Connection;
+use std::fs;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+struct TestDb {
+    path: PathBuf,
+    conn: Connection,
+}
+
+impl TestDb {
+    fn new() -> Self {
+        let path = std::env::temp_dir().join(format!("inventario-mk-test-{}.sqlite", Uuid::new_v4()));
+        let conn = database::init_db(&path).expect("debe inicializar db de prueba");
+        Self { path, conn }
+    }
+}
+
+impl Drop for TestDb {
+    fn drop(&mut self) {
+        let wal = self.path.with_extension("sqlite-wal");
+        let shm = self.path.with_extension("sqlite-shm");
+        let _ = fs::remove_file(&wal);
+        let _ = fs::remove_file(&shm);
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[test]
+fn edicion_registra_solo_los_precios_que_cambian() {
+    let db = TestDb::new();
+    let producto_service = ProductoService::new(&db.conn);
+    let historial_service = HistorialPrecioService::new(&db.conn);
+
+    let id_producto = producto_service
+        .create_producto_with_prices("Crema", None, None, None, 5, 60.0, 90.0)
+        .expect("debe crear producto");
+
+    // Editar sin cambiar precios no agrega registros
+    let mut producto = producto_service.get_producto(id_producto).unwrap();
+    producto.nombre_producto = "Crema facial".to_string();
+    producto_service.update_producto(&producto).expect("debe editar nombre");
+    assert_eq!(historial_service.listar_por_producto(id_producto).unwrap().len(), 2);
+
+    let mut producto = producto_service.get_producto(id_producto).unwrap();
+    producto.precio_publico = 110.0;
+    producto.precio = 110.0;
+    producto_service.update_producto(&producto).expect("debe subir precio publico");
+
+    let historial = historial_service
+        .listar_por_producto(id_producto)
+        .expect("debe listar historial");
+    assert_eq!(historial.len(), 3);
+    assert_eq!(historial[0].origen, OrigenCambioPrecio::Alta);
+    assert_eq!(historial[0].precio_anterior, None);
+
+    let ultimo = &historial[2];
+    assert_eq!(ultimo.campo, CampoPrecio::PrecioPublico);
+    assert_eq!(ultimo.origen, OrigenCambioPrecio::Edicion);
+    assert_eq!(ultimo.precio_anterior, Some(90.0));
+    assert_eq!(ultimo.precio_nuevo, 110.0);
+}
+
+#[test]
+fn historial_de_producto_inexistente_es_error() {
+    let db = TestDb::new();
+    let err = HistorialPrecioService::new(&db.conn)
+        .listar_por_producto(999)
+        .expect_err("debe fallar");
+    assert!(err.to_string().contains("no existe"));
+}
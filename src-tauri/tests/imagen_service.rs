@@ -0,0 +1,193 @@
+use app_lib::database;
+use app_lib::imagen;
+use app_lib::repos::producto_repo::ProductoRepo;
+use app_lib::services::imagen_service::ImagenService;
+use app_lib::services::producto_service::ProductoService;
+use base64::Engine;
+use image::{DynamicImage, ImageFormat, Rgb, RgbImage, Rgba, RgbaImage};
+use rusqlite::Connection;
+use std::fs;
+use std::io::Cursor;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+struct TestDb {
+    path: PathBuf,
+    conn: Connection,
+}
+
+impl TestDb {
+    fn new() -> Self {
+        let path = std::env::temp_dir().join(format!("inventario-mk-test-{}.sqlite", Uuid::new_v4()));
+        let conn = database::init_db(&path).expect("debe inicializar db de prueba");
+        Self { path, conn }
+    }
+}
+
+impl Drop for TestDb {
+    fn drop(&mut self) {
+        let wal = self.path.with_extension("sqlite-wal");
+        let shm = self.path.with_extension("sqlite-shm");
+        let _ = fs::remove_file(&wal);
+        let _ = fs::remove_file(&shm);
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn codificar(imagen: DynamicImage, formato: ImageFormat) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    imagen.write_to(&mut Cursor::new(&mut bytes), formato).unwrap();
+    bytes
+}
+
+#[test]
+fn procesa_por_contenido_reduce_y_genera_miniatura() {
+    let foto = DynamicImage::ImageRgb8(RgbImage::from_pixel(1800, 900, Rgb([200, 30, 90])));
+    let procesada = imagen::procesar(&codificar(foto, ImageFormat::Png), Some("PNG")).unwrap();
+    assert_eq!(procesada.extension, "jpg");
+    assert_eq!((procesada.ancho, procesada.alto), (imagen::LADO_MAXIMO, imagen::LADO_MAXIMO / 2));
+    assert_eq!(image::guess_format(&procesada.bytes).unwrap(), ImageFormat::Jpeg);
+
+    let miniatura = base64::engine::general_purpose::STANDARD
+        .decode(&procesada.miniatura_base64)
+        .unwrap();
+    let miniatura = image::load_from_memory(&miniatura).unwrap();
+    assert_eq!(miniatura.width(), imagen::LADO_MINIATURA);
+
+    // Con transparencia se conserva el canal alfa en WebP
+    let logo = DynamicImage::ImageRgba8(RgbaImage::from_pixel(40, 40, Rgba([0, 0, 0, 0])));
+    let procesada = imagen::procesar(&codificar(logo, ImageFormat::Png), None).unwrap();
+    assert_eq!(procesada.extension, "webp");
+    assert_eq!(image::guess_format(&procesada.bytes).unwrap(), ImageFormat::WebP);
+
+    // La extensión no decide el formato: un texto renombrado a .jpg se rechaza
+    let err = imagen::procesar(b"<script>alert(1)</script>", Some("jpg")).err().unwrap();
+    assert!(err.contains("no es una imagen"));
+    let png = codificar(DynamicImage::ImageRgb8(RgbImage::new(4, 4)), ImageFormat::Png);
+    let err = imagen::procesar(&png, Some("svg")).err().unwrap();
+    assert!(err.contains("'.svg' no está permitida"));
+}
+
+#[test]
+fn regenera_miniaturas_y_reporta_archivos_faltantes() {
+    let db = TestDb::new();
+    let dir = imagen::carpeta_imagenes(&db.path);
+    let foto = codificar(DynamicImage::ImageRgb8(RgbImage::from_pixel(300, 200, Rgb([10, 120, 10]))), ImageFormat::Jpeg);
+    let procesada = imagen::procesar(&foto, Some("jpg")).unwrap();
+    let ruta = imagen::guardar(&dir, "producto", &procesada).unwrap();
+
+    let productos = ProductoService::new(&db.conn);
+    let id_con_foto = productos
+        .create_producto_with_prices("Labial", None, Some(&ruta.to_string_lossy()), None, 1, 50.0, 80.0)
+        .unwrap();
+    let id_sin_archivo = productos
+        .create_producto_with_prices("Crema", None, Some("/no/existe/crema.jpg"), None, 1, 40.0, 70.0)
+        .unwrap();
+
+    let reporte = ImagenService::new(&db.conn).regenerar_miniaturas().unwrap();
+    assert_eq!(reporte.actualizadas, 1);
+    assert_eq!(reporte.fallidas.len(), 1);
+    assert_eq!(reporte.fallidas[0].id_producto, Some(id_sin_archivo));
+
+    let repo = ProductoRepo { conn: &db.conn };
+    assert_eq!(repo.get(id_con_foto).unwrap().miniatura_base64, Some(procesada.miniatura_base64));
+    assert_eq!(repo.get(id_sin_archivo).unwrap().miniatura_base64, None);
+
+    let _ = fs::remove_file(&ruta);
+}
+
+#[test]
+fn rutas_de_imagen_se_guardan_relativas_y_se_resuelven_al_leer() {
+    assert_eq!(imagen::ruta_relativa("C:\\Users\\ana\\AppData\\images\\labial.jpg"), "labial.jpg");
+    assert_eq!(imagen::ruta_relativa("/home/ana/.local/share/app/images/perfil_1.webp"), "perfil_1.webp");
+    assert_eq!(imagen::ruta_relativa("/otra/carpeta/crema.jpg"), "crema.jpg");
+    assert_eq!(imagen::ruta_relativa("../../etc/passwd"), "etc/passwd");
+
+    // Base creada en otra computadora con rutas absolutas
+    let path = std::env::temp_dir().join(format!("inventario-mk-test-{}.sqlite", Uuid::new_v4()));
+    {
+        let legacy = database::init_db(&path).expect("debe crear db");
+        legacy
+            .execute_batch(
+                "INSERT INTO productos (nombre_producto, ruta_imagen, stock, precio) VALUES ('Labial', 'C:\\Users\\ana\\AppData\\Roaming\\app\\images\\producto_1.jpg', 0, 10.0);
+                 INSERT INTO perfil (id, nombre, cargo, ruta_foto) VALUES (1, 'Ana', 'Directora', '/home/ana/app/images/perfil_1.jpg');",
+            )
+            .expect("debe poblar rutas absolutas");
+    }
+
+    let conn = database::init_db(&path).expect("debe migrar rutas");
+    let guardada: String = conn
+        .query_row("SELECT ruta_imagen FROM productos", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(guardada, "producto_1.jpg");
+    let foto: String = conn.query_row("SELECT ruta_foto FROM perfil", [], |row| row.get(0)).unwrap();
+    assert_eq!(foto, "perfil_1.jpg");
+
+    let carpeta = imagen::carpeta_imagenes(&path);
+    let producto = ProductoService::new(&conn).list_productos().unwrap().remove(0);
+    assert_eq!(producto.ruta_imagen, Some(carpeta.join("producto_1.jpg").to_string_lossy().to_string()));
+
+    // Al guardar de vuelta la ruta completa no se vuelve a volver absoluta
+    ProductoRepo { conn: &conn }.update(&producto).unwrap();
+    let guardada: String = conn
+        .query_row("SELECT ruta_imagen FROM productos", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(guardada, "producto_1.jpg");
+
+    drop(conn);
+    let _ = fs::remove_file(path.with_extension("sqlite-wal"));
+    let _ = fs::remove_file(path.with_extension("sqlite-shm"));
+    let _ = fs::remove_file(&path);
+}
+
+#[test]
+fn reporta_y_limpia_imagenes_huerfanas_y_faltantes() {
+    let dir = std::env::temp_dir().join(format!("inventario-mk-img-{}", Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("inventario.sqlite");
+    let conn = database::init_db(&path).expect("debe inicializar db de prueba");
+    let carpeta = imagen::carpeta_imagenes(&path);
+
+    let foto = codificar(DynamicImage::ImageRgb8(RgbImage::from_pixel(60, 60, Rgb([90, 10, 10]))), ImageFormat::Jpeg);
+    let procesada = imagen::procesar(&foto, Some("jpg")).unwrap();
+    let en_uso = imagen::guardar(&carpeta, "producto", &procesada).unwrap();
+    let huerfana = imagen::guardar(&carpeta, "producto", &procesada).unwrap();
+    let perfil_viejo = imagen::guardar(&carpeta, "perfil", &procesada).unwrap();
+
+    let productos = ProductoService::new(&conn);
+    productos
+        .create_producto_with_prices("Labial", None, Some(&en_uso.to_string_lossy()), None, 1, 50.0, 80.0)
+        .unwrap();
+    let id_faltante = productos
+        .create_producto_with_prices("Crema", None, Some("borrada.jpg"), None, 1, 40.0, 70.0)
+        .unwrap();
+
+    let service = ImagenService::new(&conn);
+    let reporte = service.reporte_almacenamiento(&carpeta).unwrap();
+    assert_eq!(reporte.total_archivos, 3);
+    let mut nombres: Vec<&str> = reporte.huerfanas.iter().map(|a| a.nombre.as_str()).collect();
+    nombres.sort();
+    let mut esperadas = vec![
+        perfil_viejo.file_name().unwrap().to_str().unwrap(),
+        huerfana.file_name().unwrap().to_str().unwrap(),
+    ];
+    esperadas.sort();
+    assert_eq!(nombres, esperadas);
+    assert_eq!(reporte.bytes_huerfanas, 2 * procesada.bytes.len() as i64);
+    assert_eq!(reporte.faltantes.len(), 1);
+    assert_eq!(reporte.faltantes[0].id_producto, Some(id_faltante));
+
+    // Solo se borra lo confirmado y nunca un archivo en uso
+    let confirmadas = vec![
+        huerfana.to_string_lossy().to_string(),
+        en_uso.file_name().unwrap().to_string_lossy().to_string(),
+    ];
+    let limpieza = service.limpiar_huerfanas(&carpeta, &confirmadas).unwrap();
+    assert_eq!(limpieza.eliminadas, 1);
+    assert!(!huerfana.exists());
+    assert!(en_uso.exists());
+    assert!(perfil_viejo.exists());
+
+    drop(conn);
+    let _ = fs::remove_dir_all(&dir);
+}
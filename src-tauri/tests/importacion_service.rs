@@ -0,0 +1,155 @@
+use app_lib::database;
+use app_lib::importacion;
+use app_lib::models::{AccionImportacion, MapeoColumnas, MotivoAjuste, OpcionesImportacion};
+use app_lib::repos::categoria_repo::CategoriaRepo;
+use app_lib::services::importacion_service::ImportacionService;
+use app_lib::services::movimiento_inventario_service::MovimientoInventarioService;
+use app_lib::services::producto_service::ProductoService;
+use rusqlite::Connection;
+use std::fs;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+struct TestDb {
+    path: PathBuf,
+    conn: Connection,
+}
+
+impl TestDb {
+    fn new() -> Self {
+        let path = std::env::temp_dir().join(format!("inventario-mk-test-{}.sqlite", Uuid::new_v4()));
+        let conn = database::init_db(&path).expect("debe inicializar db de prueba");
+        Self { path, conn }
+    }
+}
+
+impl Drop for TestDb {
+    fn drop(&mut self) {
+        let wal = self.path.with_extension("sqlite-wal");
+        let shm = self.path.with_extension("sqlite-shm");
+        let _ = fs::remove_file(&wal);
+        let _ = fs::remove_file(&shm);
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[test]
+fn vista_previa_de_csv_reporta_errores_por_fila_sin_guardar() {
+    let db = TestDb::new();
+    let csv = "\u{feff}Nombre;Categoría;Stock;Precio Consultora;Precio Público;SKU\n\
+               Labial mate;Rostro > Labios;5;80,50;$150\n\
+               ;Rostro;1;10;20\n\
+               Crema;Cuidado;dos;40;30\n\
+               \n\
+               Rímel;Ojos;3;90;150;MK-01\n";
+    let tabla = importacion::leer_csv(csv.as_bytes()).unwrap();
+    assert_eq!(tabla.encabezados[1], "Categoría");
+
+    let tx = db.conn.unchecked_transaction().unwrap();
+    let resultado = ImportacionService::new(&tx)
+        .importar_productos(&tabla, &OpcionesImportacion::default())
+        .unwrap();
+    assert_eq!((resultado.creados, resultado.con_errores), (2, 2));
+    assert_eq!(resultado.filas[0].accion, AccionImportacion::Crear);
+    assert_eq!(resultado.filas[1].errores, ["Falta el nombre del producto"]);
+    assert_eq!(resultado.filas[2].fila, 4);
+    assert_eq!(resultado.filas[2].errores.len(), 1);
+    assert!(resultado.filas[2].errores[0].contains("'dos' no es un número entero"));
+    assert_eq!(resultado.filas[3].fila, 6);
+    // La categoría de una fila con error no queda creada
+    assert_eq!(resultado.categorias_creadas, ["Rostro", "Labios", "Ojos"]);
+
+    let productos = ProductoService::new(&tx);
+    let labial = productos.get_producto(resultado.filas[0].id_producto.unwrap()).unwrap();
+    assert_eq!((labial.stock, labial.precio_consultora, labial.precio_publico), (5, 80.5, 150.0));
+    assert_eq!(productos.buscar_por_codigo("mk-01").unwrap().nombre_producto, "Rímel");
+    assert!(CategoriaRepo { conn: &tx }.find_by_nombre("Cuidado", None).unwrap().is_none());
+
+    // La regla de precios de ProductoService también aplica a cada fila
+    let tabla = importacion::leer_csv(b"nombre,precio_consultora,precio_publico\nSombra,100,90\n").unwrap();
+    let resultado = ImportacionService::new(&tx)
+        .importar_productos(&tabla, &OpcionesImportacion::default())
+        .unwrap();
+    assert!(resultado.filas[0].errores[0].contains("precio_publico debe ser mayor o igual"));
+
+    tx.rollback().unwrap();
+    assert!(ProductoService::new(&db.conn).list_productos().unwrap().is_empty());
+}
+
+#[test]
+fn importa_xlsx_con_mapeo_y_actualiza_existentes() {
+    let db = TestDb::new();
+    let productos = ProductoService::new(&db.conn);
+    let id_labial = productos
+        .create_producto_with_prices("Labial", None, None, None, 2, 50.0, 80.0)
+        .unwrap();
+    productos.set_codigos(id_labial, Some("LAB-1"), None).unwrap();
+
+    let ruta = std::env::temp_dir().join(format!("inventario-mk-import-{}.xlsx", Uuid::new_v4()));
+    let mut libro = rust_xlsxwriter::Workbook::new();
+    let hoja = libro.add_worksheet();
+    let filas: [[&str; 4]; 3] = [
+        ["Artículo", "Clave", "Piezas", "PVP"],
+        ["Labial rojo", "lab-1", "7", "95.5"],
+        ["Delineador", "DEL-1", "4", "120"],
+    ];
+    for (r, fila) in filas.iter().enumerate() {
+        for (c, valor) in fila.iter().enumerate() {
+            match valor.parse::<f64>() {
+                Ok(numero) if r > 0 && c != 1 => hoja.write_number(r as u32 + 2, c as u16, numero).unwrap(),
+                _ => hoja.write_string(r as u32 + 2, c as u16, *valor).unwrap(),
+            };
+        }
+    }
+    libro.save(&ruta).unwrap();
+
+    let tabla = importacion::leer_archivo(&ruta, None).unwrap();
+    assert_eq!(tabla.filas[0].0, 4);
+    assert_eq!(tabla.filas[0].1[2], "7");
+
+    let mut opciones = OpcionesImportacion {
+        columnas: MapeoColumnas {
+            nombre: Some("articulo".to_string()),
+            stock: Some("Piezas".to_string()),
+            precio_publico: Some("PVP".to_string()),
+            ..MapeoColumnas::default()
+        },
+        ..OpcionesImportacion::default()
+    };
+
+    // Sin actualizar, el producto con el mismo SKU se omite en lugar de duplicarse
+    let mut conn = database::init_db(&db.path).unwrap();
+    let tx = conn.transaction().unwrap();
+    let resultado = ImportacionService::new(&tx).importar_productos(&tabla, &opciones).unwrap();
+    assert_eq!(resultado.filas[0].accion, AccionImportacion::Omitir);
+    assert_eq!(resultado.filas[0].id_producto, Some(id_labial));
+    assert_eq!(resultado.creados, 1);
+    tx.rollback().unwrap();
+
+    opciones.actualizar_existentes = true;
+    let tx = conn.transaction().unwrap();
+    let resultado = ImportacionService::new(&tx).importar_productos(&tabla, &opciones).unwrap();
+    assert_eq!((resultado.actualizados, resultado.creados, resultado.con_errores), (1, 1, 0));
+    tx.commit().unwrap();
+
+    let labial = productos.get_producto(id_labial).unwrap();
+    assert_eq!(labial.nombre_producto, "Labial rojo");
+    let kardex = MovimientoInventarioService::new(&db.conn).listar_por_producto(id_labial).unwrap();
+    assert_eq!(kardex[0].motivo_ajuste, Some(MotivoAjuste::Importacion));
+    assert!(kardex[0].nota.contains(".xlsx, fila 4"), "{}", kardex[0].nota);
+    assert_eq!((labial.stock, labial.precio_publico, labial.precio_consultora), (7, 95.5, 50.0));
+    assert_eq!(productos.list_productos().unwrap().len(), 2);
+
+    // Un producto descontinuado no se actualiza en silencio
+    productos.delete_producto(id_labial).unwrap();
+    let resultado = ImportacionService::new(&conn).importar_productos(&tabla, &opciones).unwrap();
+    assert_eq!(resultado.filas[0].accion, AccionImportacion::Error);
+    assert!(resultado.filas[0].errores[0].contains("producto descontinuado"));
+    assert_eq!(productos.get_producto(id_labial).unwrap().stock, 7);
+
+    opciones.columnas.categoria = Some("Marca".to_string());
+    let err = ImportacionService::new(&conn).importar_productos(&tabla, &opciones).unwrap_err();
+    assert!(err.to_string().contains("no tiene la columna 'Marca'"));
+
+    let _ = fs::remove_file(&ruta);
+}
@@ -0,0 +1,150 @@
+use app_lib::database;
+use app_lib::models::{KitComponenteInput, MotivoAjuste, TipoPago};
+use app_lib::repos::lote_repo::LoteRepo;
+use app_lib::services::kit_service::KitService;
+use app_lib::services::movimiento_inventario_service::MovimientoInventarioService;
+use app_lib::services::producto_service::ProductoService;
+use app_lib::services::producto_vendido_service::ProductoVendidoService;
+use app_lib::services::venta_service::VentaService;
+use rusqlite::Connection;
+use std::fs;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+struct TestDb {
+    path: PathBuf,
+    conn: Connection,
+}
+
+impl TestDb {
+    fn new() -> Self {
+        let path = std::env::temp_dir().join(format!("inventario-mk-test-{}.sqlite", Uuid::new_v4()));
+        let conn = database::init_db(&path).expect("debe inicializar db de prueba");
+        Self { path, conn }
+    }
+}
+
+impl Drop for TestDb {
+    fn drop(&mut self) {
+        let wal = self.path.with_extension("sqlite-wal");
+        let shm = self.path.with_extension("sqlite-shm");
+        let _ = fs::remove_file(&wal);
+        let _ = fs::remove_file(&shm);
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn stock(db: &TestDb, id_producto: i64) -> i64 {
+    ProductoService::new(&db.conn).get_producto(id_producto).unwrap().stock
+}
+
+fn assert_stock_igual_a_lotes(db: &TestDb, id_producto: i64) {
+    let en_lotes = LoteRepo { conn: &db.conn }.total_por_producto(id_producto).unwrap();
+    assert_eq!(stock(db, id_producto), en_lotes);
+}
+
+#[test]
+fn venta_de_kit_descuenta_componentes_y_deriva_stock_del_kit() {
+    let db = TestDb::new();
+    let productos = ProductoService::new(&db.conn);
+    let kits = KitService::new(&db.conn);
+
+    let id_labial = productos
+        .create_producto_with_prices("Labial", None, None, None, 10, 50.0, 80.0)
+        .expect("debe crear producto");
+    let id_crema = productos
+        .create_producto_with_prices("Crema", None, None, None, 4, 40.0, 70.0)
+        .expect("debe crear producto");
+    let id_kit = productos
+        .create_producto_with_prices("Set navideño", None, None, None, 0, 130.0, 200.0)
+        .expect("debe crear kit");
+
+    let err = kits
+        .set_componentes(id_labial, &[KitComponenteInput { id_producto: id_crema, cantidad: 1 }])
+        .expect_err("un producto con stock no se convierte en kit");
+    assert!(err.to_string().contains("ya tiene stock o ventas"));
+
+    kits.set_componentes(
+        id_kit,
+        &[
+            KitComponenteInput { id_producto: id_labial, cantidad: 2 },
+            KitComponenteInput { id_producto: id_crema, cantidad: 1 },
+        ],
+    )
+    .expect("debe definir componentes");
+    assert_eq!(stock(&db, id_kit), 4);
+
+    // Kit + una crema suelta: la crema no alcanza para ambos
+    let err = kits
+        .validar_stock_venta(&[(id_kit, 4), (id_crema, 1)])
+        .expect_err("debe sumar lo que piden kit y venta suelta");
+    assert!(err.to_string().contains("Stock insuficiente para 'Crema'"));
+    kits.validar_stock_venta(&[(id_kit, 3), (id_crema, 1)]).expect("alcanza el stock");
+
+    let hoy: String = db
+        .conn
+        .query_row("SELECT DATE('now','localtime')", [], |row| row.get(0))
+        .unwrap();
+    let id_venta = VentaService::new(&db.conn)
+        .create_venta(&hoy, "Ana", "Perez", 600.0, &TipoPago::Contado)
+        .expect("debe crear venta");
+    let items = ProductoVendidoService::new(&db.conn);
+    let id_linea = items
+        .create_producto_vendido(id_venta, id_kit, "Set navideño", 3, 200.0, 600.0)
+        .expect("debe vender kit");
+
+    assert_eq!(stock(&db, id_labial), 4);
+    assert_eq!(stock(&db, id_crema), 1);
+    assert_eq!(stock(&db, id_kit), 1);
+    assert_stock_igual_a_lotes(&db, id_labial);
+    assert_stock_igual_a_lotes(&db, id_crema);
+
+    // El costo congelado de la línea es el de sus componentes
+    let linea = items.get_producto_vendido(id_linea).unwrap();
+    assert_eq!(linea.costo_unitario, 2.0 * 50.0 + 40.0);
+
+    let reporte = kits.get_reporte(&hoy, &hoy).expect("debe generar reporte");
+    assert_eq!(reporte.kits.len(), 1);
+    assert_eq!(reporte.kits[0].unidades, 3);
+    assert_eq!(reporte.kits[0].ingreso, 600.0);
+    let consumo_labial = reporte.consumos.iter().find(|c| c.id_componente == id_labial).unwrap();
+    assert_eq!(consumo_labial.unidades, 6);
+    assert_eq!(consumo_labial.nombre_kit, "Set navideño");
+
+    // Editar la cantidad vuelve a repartir entre componentes
+    let mut editada = items.get_producto_vendido(id_linea).unwrap();
+    editada.cantidad = 1;
+    editada.subtotal = 200.0;
+    items.update_producto_vendido(&editada).expect("debe editar cantidad");
+    assert_eq!(stock(&db, id_labial), 8);
+    assert_eq!(stock(&db, id_crema), 3);
+    assert_eq!(stock(&db, id_kit), 3);
+
+    editada.id_producto = id_crema;
+    assert!(items.update_producto_vendido(&editada).is_err());
+
+    let err = productos
+        .ajustar_stock(id_kit, 1, MotivoAjuste::Reconteo, "")
+        .expect_err("el stock del kit es derivado");
+    assert!(err.to_string().contains("se calcula de sus componentes"));
+
+    // El kit no tiene kardex ni aparece en reposición; sus componentes sí
+    let movimientos = MovimientoInventarioService::new(&db.conn);
+    assert!(movimientos.listar_por_producto(id_kit).is_err());
+    assert!(movimientos
+        .listar_por_fechas("2000-01-01", "2999-12-31")
+        .unwrap()
+        .iter()
+        .all(|movimiento| movimiento.id_producto != id_kit));
+    productos.set_stock_minimo(id_kit, Some(100)).unwrap();
+    let bajo_stock = productos.list_productos_bajo_stock(30, None).unwrap();
+    assert!(bajo_stock.iter().all(|producto| producto.id_producto != id_kit));
+
+    // Borrar la venta regresa todo a los componentes
+    VentaService::new(&db.conn).delete_venta(id_venta).expect("debe borrar venta");
+    assert_eq!(stock(&db, id_labial), 10);
+    assert_eq!(stock(&db, id_crema), 4);
+    assert_eq!(stock(&db, id_kit), 4);
+    assert_stock_igual_a_lotes(&db, id_labial);
+    assert_stock_igual_a_lotes(&db, id_crema);
+}
@@ -0,0 +1,162 @@
+use app_lib::database;
+use app_lib::models::{ConsultaLista, DireccionOrden, EstadoPago, FiltrosLista, RegistrarAbonoInput, TipoPago};
+use app_lib::repos::producto_repo::ProductoRepo;
+use app_lib::services::abono_venta_service::AbonoVentaService;
+use app_lib::services::categoria_service::CategoriaService;
+use app_lib::services::producto_service::ProductoService;
+use app_lib::services::producto_vendido_service::ProductoVendidoService;
+use app_lib::services::venta_service::VentaService;
+use rusqlite::Connection;
+use std::fs;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+struct TestDb {
+    path: PathBuf,
+    conn: Connection,
+}
+
+impl TestDb {
+    fn new() -> Self {
+        let path = std::env::temp_dir().join(format!("inventario-mk-test-{}.sqlite", Uuid::new_v4()));
+        let conn = database::init_db(&path).expect("debe inicializar db de prueba");
+        Self { path, conn }
+    }
+}
+
+impl Drop for TestDb {
+    fn drop(&mut self) {
+        let wal = self.path.with_extension("sqlite-wal");
+        let shm = self.path.with_extension("sqlite-shm");
+        let _ = fs::remove_file(&wal);
+        let _ = fs::remove_file(&shm);
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[test]
+fn pagina_productos_con_filtros_orden_y_total() {
+    let db = TestDb::new();
+    let categorias = CategoriaService::new(&db.conn);
+    let productos = ProductoService::new(&db.conn);
+
+    let id_rostro = categorias.create_categoria("Rostro").unwrap();
+    let id_ojos = categorias.create_subcategoria("Ojos", id_rostro).unwrap();
+    for (i, nombre) in ["Base", "Corrector", "Delineador", "Rubor", "Sombra"].iter().enumerate() {
+        let categoria = if i % 2 == 0 { id_ojos } else { id_rostro };
+        productos
+            .create_producto_with_prices(nombre, Some(categoria), None, Some("bWluaWF0dXJh"), i as i64 * 3, 10.0, 20.0 + i as f64)
+            .unwrap();
+    }
+    productos.create_producto_with_prices("Perfume", None, None, None, 8, 100.0, 200.0).unwrap();
+
+    let consulta = ConsultaLista {
+        pagina: Some(2),
+        tamano_pagina: Some(2),
+        orden: Some("precio_publico".to_string()),
+        direccion: DireccionOrden::Desc,
+        ..ConsultaLista::default()
+    };
+    let pagina = productos.list_productos_paginados(&consulta).unwrap();
+    assert_eq!(pagina.total, 6);
+    assert_eq!(pagina.total_paginas, 3);
+    let nombres: Vec<&str> = pagina.elementos.iter().map(|p| p.nombre_producto.as_str()).collect();
+    assert_eq!(nombres, ["Rubor", "Delineador"]);
+    assert!(pagina.elementos.iter().all(|p| p.miniatura_base64.is_none()));
+
+    // La categoría incluye sus subcategorías y el stock se filtra por rango
+    let consulta = ConsultaLista {
+        filtros: FiltrosLista { id_categoria: Some(id_rostro), stock_desde: Some(3), ..FiltrosLista::default() },
+        incluir_miniaturas: true,
+        ..ConsultaLista::default()
+    };
+    let pagina = productos.list_productos_paginados(&consulta).unwrap();
+    assert_eq!(pagina.total, 4);
+    assert_eq!(pagina.elementos[0].nombre_producto, "Corrector");
+    assert_eq!(pagina.elementos[0].miniatura_base64.as_deref(), Some("bWluaWF0dXJh"));
+
+    let err = productos
+        .list_productos_paginados(&ConsultaLista { orden: Some("costo; DROP TABLE productos".to_string()), ..ConsultaLista::default() })
+        .unwrap_err();
+    assert!(err.to_string().contains("No se puede ordenar por"));
+    // El repositorio tampoco acepta un campo desconocido aunque se llame sin pasar por el servicio
+    let err = ProductoRepo { conn: &db.conn }
+        .list_paginado(&ConsultaLista { orden: Some("costo".to_string()), ..ConsultaLista::default() })
+        .unwrap_err();
+    assert!(err.to_string().contains("No se puede ordenar por 'costo'"));
+
+    let lejana = ConsultaLista { pagina: Some(i64::MAX), tamano_pagina: Some(500), ..ConsultaLista::default() };
+    assert_eq!(lejana.offset(), i64::MAX);
+    let pagina = productos.list_productos_paginados(&lejana).unwrap();
+    assert!(pagina.elementos.is_empty());
+
+    let err = productos
+        .list_productos_paginados(&ConsultaLista {
+            filtros: FiltrosLista { stock_desde: Some(5), stock_hasta: Some(1), ..FiltrosLista::default() },
+            ..ConsultaLista::default()
+        })
+        .unwrap_err();
+    assert!(err.to_string().contains("stock inicial"));
+}
+
+#[test]
+fn pagina_ventas_por_estado_de_pago_y_productos_vendidos() {
+    let db = TestDb::new();
+    let ventas = VentaService::new(&db.conn);
+    let abonos = AbonoVentaService::new(&db.conn);
+
+    let id_contado = ventas.create_venta("2026-03-01", "Ana", "Perez", 100.0, &TipoPago::Contado).unwrap();
+    let id_pendiente = ventas.create_venta("2026-03-05", "Bea", "Lopez", 300.0, &TipoPago::Abono).unwrap();
+    let id_parcial = ventas.create_venta("2026-03-10", "Caro", "Diaz", 200.0, &TipoPago::Abono).unwrap();
+    abonos
+        .registrar_abono(&RegistrarAbonoInput {
+            id_venta: id_parcial,
+            monto_abono: 50.0,
+            fecha_abono: Some("2026-03-11 10:00:00".to_string()),
+            metodo_registro: None,
+            observacion: None,
+        })
+        .unwrap();
+
+    let pagina = ventas.list_ventas_paginadas(&ConsultaLista::default()).unwrap();
+    let ids: Vec<i64> = pagina.elementos.iter().map(|v| v.id_venta).collect();
+    assert_eq!(ids, [id_parcial, id_pendiente, id_contado]);
+
+    let consulta = ConsultaLista {
+        filtros: FiltrosLista { estado_pago: Some(EstadoPago::Liquidada), ..FiltrosLista::default() },
+        ..ConsultaLista::default()
+    };
+    let pagina = ventas.list_ventas_con_cobranza_paginadas(&consulta).unwrap();
+    assert_eq!(pagina.total, 1);
+    assert_eq!(pagina.elementos[0].id_venta, id_contado);
+
+    let consulta = ConsultaLista { orden: Some("saldo".to_string()), direccion: DireccionOrden::Desc, ..ConsultaLista::default() };
+    let pagina = ventas.list_ventas_con_cobranza_paginadas(&consulta).unwrap();
+    let saldos: Vec<f64> = pagina.elementos.iter().map(|v| v.saldo_pendiente).collect();
+    assert_eq!(saldos, [300.0, 150.0, 0.0]);
+    assert_eq!(pagina.elementos[1].estado_pago, EstadoPago::Parcial);
+
+    let consulta = ConsultaLista {
+        filtros: FiltrosLista { fecha_desde: Some("2026-03-02".to_string()), fecha_hasta: Some("2026-03-05".to_string()), ..FiltrosLista::default() },
+        ..ConsultaLista::default()
+    };
+    assert_eq!(ventas.list_ventas_paginadas(&consulta).unwrap().elementos[0].id_venta, id_pendiente);
+
+    let productos = ProductoService::new(&db.conn);
+    let id_labial = productos.create_producto_with_prices("Labial", None, None, None, 10, 50.0, 80.0).unwrap();
+    let id_crema = productos.create_producto_with_prices("Crema", None, None, None, 10, 40.0, 70.0).unwrap();
+    let vendidos = ProductoVendidoService::new(&db.conn);
+    vendidos.create_producto_vendido(id_contado, id_labial, "Labial", 1, 80.0, 80.0).unwrap();
+    vendidos.create_producto_vendido(id_parcial, id_labial, "Labial", 2, 80.0, 160.0).unwrap();
+    vendidos.create_producto_vendido(id_parcial, id_crema, "Crema", 1, 40.0, 40.0).unwrap();
+
+    let consulta = ConsultaLista {
+        orden: Some("subtotal".to_string()),
+        filtros: FiltrosLista { id_producto: Some(id_labial), ..FiltrosLista::default() },
+        ..ConsultaLista::default()
+    };
+    let pagina = vendidos.list_productos_vendidos_paginados(&consulta).unwrap();
+    assert_eq!(pagina.total, 2);
+    let subtotales: Vec<f64> = pagina.elementos.iter().map(|p| p.subtotal).collect();
+    assert_eq!(subtotales, [80.0, 160.0]);
+}
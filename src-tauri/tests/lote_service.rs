@@ -0,0 +1,213 @@
+use app_lib::database;
+use app_lib::models::{CompraInput, LineaCompraInput, MotivoAjuste, MotivoMovimiento, RecepcionLineaInput, TipoPago};
+use app_lib::repos::lote_repo::LoteRepo;
+use app_lib::services::compra_service::CompraService;
+use app_lib::services::lote_service::LoteService;
+use app_lib::services::movimiento_inventario_service::MovimientoInventarioService;
+use app_lib::services::producto_service::ProductoService;
+use app_lib::services::producto_vendido_service::ProductoVendidoService;
+use app_lib::services::venta_service::VentaService;
+use rusqlite::Connection;
+use std::fs;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+struct TestDb {
+    path: PathBuf,
+    conn: Connection,
+}
+
+impl TestDb {
+    fn new() -> Self {
+        let path = std::env::temp_dir().join(format!("inventario-mk-test-{}.sqlite", Uuid::new_v4()));
+        let conn = database::init_db(&path).expect("debe inicializar db de prueba");
+        Self { path, conn }
+    }
+}
+
+impl Drop for TestDb {
+    fn drop(&mut self) {
+        let wal = self.path.with_extension("sqlite-wal");
+        let shm = self.path.with_extension("sqlite-shm");
+        let _ = fs::remove_file(&wal);
+        let _ = fs::remove_file(&shm);
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+// Recibe una compra con un lote por línea: (codigo, caducidad, cantidad)
+fn recibir_lotes(db: &TestDb, id_producto: i64, lotes: &[(&str, &str, i64)]) {
+    let service = CompraService::new(&db.conn);
+    let id_compra = service
+        .create_compra(&CompraInput {
+            id_proveedor: None,
+            fecha: None,
+            notas: None,
+            lineas: lotes
+                .iter()
+                .map(|(_, _, cantidad)| LineaCompraInput { id_producto, cantidad: *cantidad, costo_unitario: 30.0 })
+                .collect(),
+        })
+        .expect("debe crear compra");
+
+    let detalle = service.get_compra(id_compra).expect("debe obtener compra").lineas;
+    let recepciones: Vec<RecepcionLineaInput> = detalle
+        .iter()
+        .zip(lotes)
+        .map(|(linea, (codigo, caducidad, cantidad))| RecepcionLineaInput {
+            id_detalle: linea.id_detalle,
+            cantidad: *cantidad,
+            codigo_lote: Some(codigo.to_string()),
+            fecha_caducidad: Some(caducidad.to_string()),
+        })
+        .collect();
+    service.recibir_compra(id_compra, &recepciones).expect("debe recibir lotes");
+}
+
+fn cantidades(db: &TestDb, id_producto: i64) -> Vec<(String, i64)> {
+    LoteService::new(&db.conn)
+        .listar_por_producto(id_producto)
+        .expect("debe listar lotes")
+        .into_iter()
+        .map(|l| (l.codigo_lote, l.cantidad))
+        .collect()
+}
+
+fn assert_stock_igual_a_lotes(db: &TestDb, id_producto: i64) {
+    let stock = ProductoService::new(&db.conn).get_producto(id_producto).unwrap().stock;
+    let en_lotes = LoteRepo { conn: &db.conn }.total_por_producto(id_producto).unwrap();
+    assert_eq!(stock, en_lotes);
+}
+
+#[test]
+fn venta_consume_primero_el_lote_que_caduca_antes() {
+    let db = TestDb::new();
+    let id_producto = ProductoService::new(&db.conn)
+        .create_producto_with_prices("Protector solar", None, None, None, 2, 30.0, 60.0)
+        .expect("debe crear producto");
+    recibir_lotes(&db, id_producto, &[("L-2031", "2031-01-01", 5), ("L-2030", "2030-06-01", 5)]);
+    assert_stock_igual_a_lotes(&db, id_producto);
+
+    let id_venta = VentaService::new(&db.conn)
+        .create_venta("2026-04-01", "Ana", "Perez", 420.0, &TipoPago::Contado)
+        .expect("debe crear venta");
+    let vendidos = ProductoVendidoService::new(&db.conn);
+    let id_item = vendidos
+        .create_producto_vendido(id_venta, id_producto, "Protector solar", 7, 60.0, 420.0)
+        .expect("debe vender");
+
+    // Lote general (sin caducidad) al final del orden FEFO
+    assert_eq!(
+        cantidades(&db, id_producto),
+        vec![("L-2030".to_string(), 0), ("L-2031".to_string(), 3), (String::new(), 2)]
+    );
+    assert_stock_igual_a_lotes(&db, id_producto);
+
+    let mut item = vendidos.get_producto_vendido(id_item).unwrap();
+    item.cantidad = 4;
+    item.subtotal = 240.0;
+    vendidos.update_producto_vendido(&item).expect("debe editar cantidad");
+    assert_eq!(
+        cantidades(&db, id_producto),
+        vec![("L-2030".to_string(), 1), ("L-2031".to_string(), 5), (String::new(), 2)]
+    );
+
+    // Eliminar la venta devuelve todo a sus lotes de origen
+    VentaService::new(&db.conn).delete_venta(id_venta).expect("debe eliminar venta");
+    assert_eq!(
+        cantidades(&db, id_producto),
+        vec![("L-2030".to_string(), 5), ("L-2031".to_string(), 5), (String::new(), 2)]
+    );
+    assert_stock_igual_a_lotes(&db, id_producto);
+}
+
+#[test]
+fn baja_de_lotes_vencidos_descuenta_stock_y_registra_merma() {
+    let db = TestDb::new();
+    let producto_service = ProductoService::new(&db.conn);
+    let id_producto = producto_service
+        .create_producto_with_prices("Base liquida", None, None, None, 2, 30.0, 60.0)
+        .expect("debe crear producto");
+    recibir_lotes(&db, id_producto, &[("VIEJO", "2020-01-31", 3), ("NUEVO", "2099-12-31", 4)]);
+
+    let lotes = LoteService::new(&db.conn);
+    let por_vencer = lotes.listar_por_vencer(30).expect("debe listar por vencer");
+    assert_eq!(por_vencer.len(), 1);
+    assert_eq!(por_vencer[0].codigo_lote, "VIEJO");
+    assert!(por_vencer[0].dias_para_vencer.unwrap() < 0);
+
+    let bajas = lotes.dar_de_baja_vencidos().expect("debe dar de baja");
+    assert_eq!(bajas.len(), 1);
+    assert_eq!(producto_service.get_producto(id_producto).unwrap().stock, 6);
+    assert_stock_igual_a_lotes(&db, id_producto);
+
+    let kardex = MovimientoInventarioService::new(&db.conn)
+        .listar_por_producto(id_producto)
+        .unwrap();
+    assert_eq!(kardex[0].motivo, MotivoMovimiento::Merma);
+    assert_eq!(kardex[0].motivo_ajuste, Some(MotivoAjuste::Caducado));
+    assert_eq!(kardex[0].delta, -3);
+
+    // Un ajuste negativo también sale del lote que caduca primero
+    producto_service
+        .ajustar_stock(id_producto, -5, MotivoAjuste::Dano, "")
+        .expect("debe ajustar");
+    assert_eq!(
+        cantidades(&db, id_producto),
+        vec![("VIEJO".to_string(), 0), ("NUEVO".to_string(), 0), (String::new(), 1)]
+    );
+    assert_stock_igual_a_lotes(&db, id_producto);
+}
+
+#[test]
+fn venta_que_excede_los_lotes_se_rechaza_sin_tocar_el_stock() {
+    let db = TestDb::new();
+    let id_producto = ProductoService::new(&db.conn)
+        .create_producto_with_prices("Tónico", None, None, None, 3, 30.0, 60.0)
+        .expect("debe crear producto");
+    // Descuadre heredado: el stock dice 5 pero los lotes solo tienen 3
+    db.conn
+        .execute("UPDATE productos SET stock = 5 WHERE id_producto = ?1", [id_producto])
+        .unwrap();
+
+    let id_venta = VentaService::new(&db.conn)
+        .create_venta("2026-04-01", "Ana", "Perez", 240.0, &TipoPago::Contado)
+        .expect("debe crear venta");
+    let err = ProductoVendidoService::new(&db.conn)
+        .create_producto_vendido(id_venta, id_producto, "Tónico", 4, 60.0, 240.0)
+        .expect_err("los lotes no alcanzan");
+    assert!(err.to_string().contains("no alcanzan"));
+    assert_eq!(ProductoService::new(&db.conn).get_producto(id_producto).unwrap().stock, 5);
+    assert_eq!(cantidades(&db, id_producto), vec![(String::new(), 3)]);
+}
+
+#[test]
+fn abrir_una_base_anterior_recrea_los_triggers_de_inventario() {
+    let db = TestDb::new();
+    // Versión anterior del trigger: consumía los lotes sin validar que alcanzaran
+    db.conn
+        .execute_batch(
+            "DROP TRIGGER trg_prodvend_lotes_after_insert;
+            CREATE TRIGGER trg_prodvend_lotes_after_insert
+            AFTER INSERT ON productos_vendidos
+            BEGIN
+              SELECT 1;
+            END;",
+        )
+        .unwrap();
+
+    let conn = database::init_db(&db.path).expect("debe reabrir la base");
+    let id_producto = ProductoService::new(&conn)
+        .create_producto_with_prices("Tónico", None, None, None, 3, 30.0, 60.0)
+        .expect("debe crear producto");
+    conn.execute("UPDATE productos SET stock = 5 WHERE id_producto = ?1", [id_producto])
+        .unwrap();
+
+    let id_venta = VentaService::new(&conn)
+        .create_venta("2026-04-01", "Ana", "Perez", 240.0, &TipoPago::Contado)
+        .expect("debe crear venta");
+    let err = ProductoVendidoService::new(&conn)
+        .create_producto_vendido(id_venta, id_producto, "Tónico", 4, 60.0, 240.0)
+        .expect_err("el trigger actual valida los lotes");
+    assert!(err.to_string().contains("no alcanzan"));
+}
@@ -0,0 +1,161 @@
+use app_lib::database;
+use app_lib::models::{MotivoAjuste, MotivoMovimiento, TipoPago};
+use app_lib::services::movimiento_inventario_service::MovimientoInventarioService;
+use app_lib::services::producto_service::ProductoService;
+use app_lib::services::producto_vendido_service::ProductoVendidoService;
+use app_lib::services::venta_service::VentaService;
+use rusqlite::Connection;
+use std::fs;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+struct TestDb {
+    path: PathBuf,
+    conn: Connection,
+}
+
+impl TestDb {
+    fn new() -> Self {
+        let path = std::env::temp_dir().join(format!("inventario-mk-test-{}.sqlite", Uuid::new_v4()));
+        let conn = database::init_db(&path).expect("debe inicializar db de prueba");
+        Self { path, conn }
+    }
+}
+
+impl Drop for TestDb {
+    fn drop(&mut self) {
+        let wal = self.path.with_extension("sqlite-wal");
+        let shm = self.path.with_extension("sqlite-shm");
+        let _ = fs::remove_file(&wal);
+        let _ = fs::remove_file(&shm);
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[test]
+fn triggers_de_venta_registran_cada_cambio_de_stock() {
+    let db = TestDb::new();
+    let producto_service = ProductoService::new(&db.conn);
+    let venta_service = VentaService::new(&db.conn);
+    let vendido_service = ProductoVendidoService::new(&db.conn);
+    let movimientos = MovimientoInventarioService::new(&db.conn);
+
+    let id_producto = producto_service
+        .create_producto_with_prices("Labial", None, None, None, 10, 50.0, 80.0)
+        .expect("debe crear producto");
+    let id_venta = venta_service
+        .create_venta("2026-04-01", "Ana", "Perez", 240.0, &TipoPago::Contado)
+        .expect("debe crear venta");
+
+    let id_item = vendido_service
+        .create_producto_vendido(id_venta, id_producto, "Labial", 3, 80.0, 240.0)
+        .expect("debe registrar item vendido");
+
+    let mut item = vendido_service.get_producto_vendido(id_item).expect("debe obtener item");
+    item.cantidad = 5;
+    item.subtotal = 400.0;
+    vendido_service.update_producto_vendido(&item).expect("debe editar cantidad");
+
+    vendido_service.delete_producto_vendido(id_item).expect("debe eliminar item");
+
+    let kardex = movimientos
+        .listar_por_producto(id_producto)
+        .expect("debe listar movimientos");
+
+    // Más reciente primero: devolución, venta por edición, venta, alta
+    let resumen: Vec<(MotivoMovimiento, i64, i64)> = kardex
+        .iter()
+        .map(|m| (m.motivo, m.delta, m.stock_resultante))
+        .collect();
+    assert_eq!(
+        resumen,
+        vec![
+            (MotivoMovimiento::Devolucion, 5, 10),
+            (MotivoMovimiento::Venta, -2, 5),
+            (MotivoMovimiento::Venta, -3, 7),
+            (MotivoMovimiento::Inicial, 10, 10),
+        ]
+    );
+    assert_eq!(kardex[2].id_referencia, Some(id_venta));
+}
+
+#[test]
+fn cambio_de_producto_en_item_devuelve_y_descuenta_stock() {
+    let db = TestDb::new();
+    let producto_service = ProductoService::new(&db.conn);
+    let venta_service = VentaService::new(&db.conn);
+    let vendido_service = ProductoVendidoService::new(&db.conn);
+
+    let id_a = producto_service
+        .create_producto_with_prices("Rimel", None, None, None, 5, 30.0, 45.0)
+        .expect("debe crear producto A");
+    let id_b = producto_service
+        .create_producto_with_prices("Delineador", None, None, None, 5, 20.0, 35.0)
+        .expect("debe crear producto B");
+    let id_venta = venta_service
+        .create_venta("2026-04-02", "Bea", "Lopez", 90.0, &TipoPago::Contado)
+        .expect("debe crear venta");
+
+    let id_item = vendido_service
+        .create_producto_vendido(id_venta, id_a, "Rimel", 2, 45.0, 90.0)
+        .expect("debe registrar item");
+
+    let mut item = vendido_service.get_producto_vendido(id_item).expect("debe obtener item");
+    item.id_producto = id_b;
+    vendido_service.update_producto_vendido(&item).expect("debe cambiar producto");
+
+    assert_eq!(producto_service.get_producto(id_a).expect("producto A").stock, 5);
+    assert_eq!(producto_service.get_producto(id_b).expect("producto B").stock, 3);
+}
+
+#[test]
+fn edicion_de_producto_no_modifica_stock() {
+    let db = TestDb::new();
+    let producto_service = ProductoService::new(&db.conn);
+    let movimientos = MovimientoInventarioService::new(&db.conn);
+
+    let id = producto_service
+        .create_producto_with_prices("Base", None, None, None, 4, 100.0, 150.0)
+        .expect("debe crear producto");
+
+    let mut producto = producto_service.get_producto(id).expect("debe obtener producto");
+    producto.stock = 9;
+    producto.nombre_producto = "Base liquida".to_string();
+    producto_service.update_producto(&producto).expect("debe actualizar producto");
+
+    let actualizado = producto_service.get_producto(id).expect("debe obtener producto actualizado");
+    assert_eq!(actualizado.stock, 4);
+    assert_eq!(actualizado.nombre_producto, "Base liquida");
+
+    let kardex = movimientos.listar_por_producto(id).expect("debe listar movimientos");
+    assert_eq!(kardex.len(), 1);
+}
+
+#[test]
+fn ajustar_stock_registra_motivo_y_rechaza_stock_negativo() {
+    let db = TestDb::new();
+    let producto_service = ProductoService::new(&db.conn);
+    let movimientos = MovimientoInventarioService::new(&db.conn);
+
+    let id = producto_service
+        .create_producto_with_prices("Perfume", None, None, None, 3, 200.0, 320.0)
+        .expect("debe crear producto");
+
+    let salida = producto_service
+        .ajustar_stock(id, -1, MotivoAjuste::Dano, "frasco roto")
+        .expect("debe aplicar ajuste");
+    assert_eq!(salida.stock_anterior, 3);
+    assert_eq!(salida.stock_actual, 2);
+
+    let err = producto_service
+        .ajustar_stock(id, -5, MotivoAjuste::Reconteo, "")
+        .expect_err("no debe dejar stock negativo");
+    assert!(err.to_string().contains("negativo"));
+    assert_eq!(producto_service.get_producto(id).expect("producto").stock, 2);
+
+    let kardex = movimientos.listar_por_producto(id).expect("debe listar movimientos");
+    assert_eq!(kardex[0].motivo, MotivoMovimiento::Merma);
+    assert_eq!(kardex[0].motivo_ajuste, Some(MotivoAjuste::Dano));
+    assert_eq!(kardex[0].nota, "frasco roto");
+    assert_eq!(kardex[0].stock_resultante, 2);
+}
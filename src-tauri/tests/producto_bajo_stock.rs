@@ -0,0 +1,91 @@
+use app_lib::database;
+use app_lib::models::TipoPago;
+use app_lib::services::categoria_service::CategoriaService;
+use app_lib::services::producto_service::ProductoService;
+use app_lib::services::producto_vendido_service::ProductoVendidoService;
+use app_lib::services::venta_service::VentaService;
+use rusqlite::Connection;
+use std::fs;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+struct TestDb {
+    path: PathBuf,
+    conn: Connection,
+}
+
+impl TestDb {
+    fn new() -> Self {
+        let path = std::env::temp_dir().join(format!("inventario-mk-test-{}.sqlite", Uuid::new_v4()));
+        let conn = database::init_db(&path).expect("debe inicializar db de prueba");
+        Self { path, conn }
+    }
+}
+
+impl Drop for TestDb {
+    fn drop(&mut self) {
+        let wal = self.path.with_extension("sqlite-wal");
+        let shm = self.path.with_extension("sqlite-shm");
+        let _ = fs::remove_file(&wal);
+        let _ = fs::remove_file(&shm);
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[test]
+fn bajo_stock_usa_minimo_propio_o_de_categoria_y_velocidad_de_venta() {
+    let db = TestDb::new();
+    let categorias = CategoriaService::new(&db.conn);
+    let productos = ProductoService::new(&db.conn);
+
+    let id_categoria = categorias.create_categoria("Labiales").expect("debe crear categoria");
+    categorias
+        .set_stock_minimo_default(id_categoria, Some(5))
+        .expect("debe fijar minimo de categoria");
+
+    let id_rojo = productos
+        .create_producto_with_prices("Labial rojo", Some(id_categoria), None, None, 10, 50.0, 80.0)
+        .expect("debe crear producto");
+    let id_rosa = productos
+        .create_producto_with_prices("Labial rosa", Some(id_categoria), None, None, 8, 50.0, 80.0)
+        .expect("debe crear producto");
+    let id_crema = productos
+        .create_producto_with_prices("Crema", None, None, None, 3, 40.0, 70.0)
+        .expect("debe crear producto");
+
+    // Propio por encima del de la categoría
+    productos.set_stock_minimo(id_rosa, Some(10)).expect("debe fijar minimo propio");
+
+    // Vende 6 labiales rojos hoy: quedan 4, bajo el mínimo de la categoría
+    let hoy: String = db
+        .conn
+        .query_row("SELECT DATE('now','localtime')", [], |row| row.get(0))
+        .unwrap();
+    let id_venta = VentaService::new(&db.conn)
+        .create_venta(&hoy, "Ana", "Perez", 480.0, &TipoPago::Contado)
+        .expect("debe crear venta");
+    ProductoVendidoService::new(&db.conn)
+        .create_producto_vendido(id_venta, id_rojo, "Labial rojo", 6, 80.0, 480.0)
+        .expect("debe registrar item");
+
+    let bajo_stock = productos.list_productos_bajo_stock(30, None).expect("debe listar bajo stock");
+    let ids: Vec<i64> = bajo_stock.iter().map(|p| p.id_producto).collect();
+    assert_eq!(ids.len(), 2);
+    assert!(ids.contains(&id_rojo));
+    assert!(ids.contains(&id_rosa));
+    assert!(!ids.contains(&id_crema), "sin minimo configurado solo aparece al agotarse");
+
+    let rojo = bajo_stock.iter().find(|p| p.id_producto == id_rojo).unwrap();
+    assert_eq!(rojo.stock, 4);
+    assert_eq!(rojo.stock_minimo, 5);
+    assert_eq!(rojo.unidades_vendidas, 6);
+    assert_eq!(rojo.venta_promedio_diaria, 0.2);
+    assert_eq!(rojo.dias_cobertura, Some(20.0));
+
+    let rosa = bajo_stock.iter().find(|p| p.id_producto == id_rosa).unwrap();
+    assert_eq!(rosa.stock_minimo, 10);
+    assert_eq!(rosa.dias_cobertura, None);
+
+    let err = productos.set_stock_minimo(id_crema, Some(-1)).expect_err("no debe aceptar negativos");
+    assert!(err.to_string().contains("negativo"));
+}
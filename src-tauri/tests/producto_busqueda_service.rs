@@ -0,0 +1,88 @@
+use app_lib::database;
+use app_lib::models::FiltrosBusqueda;
+use app_lib::services::categoria_service::CategoriaService;
+use app_lib::services::producto_service::ProductoService;
+use rusqlite::Connection;
+use std::fs;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+struct TestDb {
+    path: PathBuf,
+    conn: Connection,
+}
+
+impl TestDb {
+    fn new() -> Self {
+        let path = std::env::temp_dir().join(format!("inventario-mk-test-{}.sqlite", Uuid::new_v4()));
+        let conn = database::init_db(&path).expect("debe inicializar db de prueba");
+        Self { path, conn }
+    }
+}
+
+impl Drop for TestDb {
+    fn drop(&mut self) {
+        let wal = self.path.with_extension("sqlite-wal");
+        let shm = self.path.with_extension("sqlite-shm");
+        let _ = fs::remove_file(&wal);
+        let _ = fs::remove_file(&shm);
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn nombres(productos: &[app_lib::models::Producto]) -> Vec<&str> {
+    productos.iter().map(|p| p.nombre_producto.as_str()).collect()
+}
+
+#[test]
+fn busca_sin_acentos_por_prefijo_y_con_ranking() {
+    let db = TestDb::new();
+    let categorias = CategoriaService::new(&db.conn);
+    let productos = ProductoService::new(&db.conn);
+    let sin_filtros = FiltrosBusqueda::default();
+
+    let id_ojos = categorias.create_categoria("Ojos").unwrap();
+    let id_rimel = productos
+        .create_producto_with_prices("Rímel a prueba de agua", Some(id_ojos), None, None, 4, 90.0, 150.0)
+        .unwrap();
+    let id_labial = productos
+        .create_producto_with_prices("Labial mate rojo", None, None, None, 0, 50.0, 80.0)
+        .unwrap();
+    let id_crema = productos
+        .create_producto_with_prices("Crema hidratante", None, None, None, 2, 40.0, 70.0)
+        .unwrap();
+    productos.set_codigos(id_labial, Some("LAB-001"), None).unwrap();
+    productos.set_notas(id_crema, Some("Ideal para labios resecos")).unwrap();
+
+    assert_eq!(nombres(&productos.buscar_productos("rimel", &sin_filtros, None).unwrap()), ["Rímel a prueba de agua"]);
+    assert_eq!(nombres(&productos.buscar_productos("lab ro", &sin_filtros, None).unwrap()), ["Labial mate rojo"]);
+    assert_eq!(productos.buscar_productos("lab-001", &sin_filtros, None).unwrap()[0].id_producto, id_labial);
+    assert!(productos.buscar_productos("  \"*( ", &sin_filtros, None).unwrap().is_empty());
+
+    // El nombre pesa más que las notas
+    let resultados = productos.buscar_productos("lab", &sin_filtros, None).unwrap();
+    assert_eq!(nombres(&resultados), ["Labial mate rojo", "Crema hidratante"]);
+    assert_eq!(productos.buscar_productos("lab", &sin_filtros, Some(1)).unwrap().len(), 1);
+
+    // Los triggers siguen ediciones, renombres de categoría y descontinuados
+    let mut rimel = productos.get_producto(id_rimel).unwrap();
+    rimel.nombre_producto = "Máscara de pestañas".to_string();
+    productos.update_producto(&rimel).unwrap();
+    assert!(productos.buscar_productos("rimel", &sin_filtros, None).unwrap().is_empty());
+    assert_eq!(productos.buscar_productos("pestanas", &sin_filtros, None).unwrap().len(), 1);
+
+    let mut ojos = categorias.get_categoria(id_ojos).unwrap();
+    ojos.nombre = "Mirada".to_string();
+    categorias.update_categoria(&ojos).unwrap();
+    assert_eq!(productos.buscar_productos("mirada", &sin_filtros, None).unwrap()[0].id_producto, id_rimel);
+
+    let filtros = FiltrosBusqueda { solo_con_stock: true, ..FiltrosBusqueda::default() };
+    assert_eq!(nombres(&productos.buscar_productos("lab", &filtros, None).unwrap()), ["Crema hidratante"]);
+    let filtros = FiltrosBusqueda { id_categoria: Some(id_ojos), ..FiltrosBusqueda::default() };
+    assert!(productos.buscar_productos("lab", &filtros, None).unwrap().is_empty());
+
+    productos.delete_producto(id_crema).unwrap();
+    assert_eq!(productos.buscar_productos("lab", &sin_filtros, None).unwrap().len(), 1);
+    let filtros = FiltrosBusqueda { incluir_descontinuados: true, ..FiltrosBusqueda::default() };
+    assert_eq!(productos.buscar_productos("lab", &filtros, None).unwrap().len(), 2);
+}
@@ -0,0 +1,93 @@
+use app_lib::database;
+use app_lib::services::producto_service::ProductoService;
+use rusqlite::Connection;
+use std::fs;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+struct TestDb {
+    path: PathBuf,
+    conn: Connection,
+}
+
+impl TestDb {
+    fn new() -> Self {
+        let path = std::env::temp_dir().join(format!("inventario-mk-test-{}.sqlite", Uuid::new_v4()));
+        let conn = database::init_db(&path).expect("debe inicializar db de prueba");
+        Self { path, conn }
+    }
+}
+
+impl Drop for TestDb {
+    fn drop(&mut self) {
+        let wal = self.path.with_extension("sqlite-wal");
+        let shm = self.path.with_extension("sqlite-shm");
+        let _ = fs::remove_file(&wal);
+        let _ = fs::remove_file(&shm);
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[test]
+fn valida_digito_verificador_de_codigos_de_barras() {
+    assert!(ProductoService::codigo_barras_valido("4006381333931"));
+    assert!(ProductoService::codigo_barras_valido("036000291452"));
+    assert!(ProductoService::codigo_barras_valido("96385074"));
+    assert!(!ProductoService::codigo_barras_valido("4006381333932"));
+    assert!(!ProductoService::codigo_barras_valido("40063813339"));
+    assert!(!ProductoService::codigo_barras_valido("40063813339A1"));
+}
+
+#[test]
+fn codigos_unicos_y_busqueda_por_codigo_escaneado() {
+    let db = TestDb::new();
+    let productos = ProductoService::new(&db.conn);
+
+    let id_labial = productos
+        .create_producto_with_prices("Labial rojo", None, None, None, 5, 50.0, 80.0)
+        .expect("debe crear producto");
+    let id_crema = productos
+        .create_producto_with_prices("Crema", None, None, None, 5, 40.0, 70.0)
+        .expect("debe crear producto");
+
+    productos
+        .set_codigos(id_labial, Some(" LAB-001 "), Some("036000-291452"))
+        .expect("debe asignar codigos");
+    let labial = productos.get_producto(id_labial).unwrap();
+    assert_eq!(labial.sku.as_deref(), Some("LAB-001"));
+    assert_eq!(labial.codigo_barras.as_deref(), Some("036000291452"));
+
+    let err = productos
+        .set_codigos(id_crema, Some("lab-001"), None)
+        .expect_err("no debe repetir sku");
+    assert!(err.to_string().contains("ya está asignado a 'Labial rojo'"));
+
+    let err = productos
+        .set_codigos(id_crema, None, Some("036000291452"))
+        .expect_err("no debe repetir codigo de barras");
+    assert!(err.to_string().contains("ya está asignado a 'Labial rojo'"));
+
+    let err = productos
+        .set_codigos(id_crema, None, Some("4006381333932"))
+        .expect_err("no debe aceptar digito verificador incorrecto");
+    assert!(err.to_string().contains("dígito verificador"));
+
+    // Editar el producto conserva sus propios códigos
+    let mut labial = productos.get_producto(id_labial).unwrap();
+    labial.nombre_producto = "Labial rojo mate".to_string();
+    productos.update_producto(&labial).expect("debe actualizar");
+
+    // El lector puede entregar el UPC-A como EAN-13 con 0 adelante
+    assert_eq!(productos.buscar_por_codigo("0036000291452").unwrap().id_producto, id_labial);
+    assert_eq!(productos.buscar_por_codigo("lab-001").unwrap().id_producto, id_labial);
+    assert!(productos.buscar_por_codigo("4006381333931").is_err());
+
+    // Descontinuado: ya no aparece al escanear pero su código sigue reservado
+    productos.delete_producto(id_labial).expect("debe descontinuar");
+    let err = productos.buscar_por_codigo("036000291452").expect_err("no debe encontrar inactivos");
+    assert!(err.to_string().contains("No hay un producto activo"));
+    let err = productos
+        .set_codigos(id_crema, Some("LAB-001"), None)
+        .expect_err("sku reservado por descontinuado");
+    assert!(err.to_string().contains("(descontinuado)"));
+}
@@ -0,0 +1,131 @@
+use app_lib::database;
+use app_lib::models::{MotivoAjuste, PromocionInput, TipoPago, TipoPromocion};
+use app_lib::services::producto_service::ProductoService;
+use app_lib::services::producto_vendido_service::ProductoVendidoService;
+use app_lib::services::promocion_service::PromocionService;
+use app_lib::services::venta_service::VentaService;
+use rusqlite::Connection;
+use std::fs;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+struct TestDb {
+    path: PathBuf,
+    conn: Connection,
+}
+
+impl TestDb {
+    fn new() -> Self {
+        let path = std::env::temp_dir().join(format!("inventario-mk-test-{}.sqlite", Uuid::new_v4()));
+        let conn = database::init_db(&path).expect("debe inicializar db de prueba");
+        Self { path, conn }
+    }
+}
+
+impl Drop for TestDb {
+    fn drop(&mut self) {
+        let wal = self.path.with_extension("sqlite-wal");
+        let shm = self.path.with_extension("sqlite-shm");
+        let _ = fs::remove_file(&wal);
+        let _ = fs::remove_file(&shm);
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[test]
+fn descontinuados_se_listan_reactivan_y_solo_se_borran_sin_historial() {
+    let db = TestDb::new();
+    let productos = ProductoService::new(&db.conn);
+
+    let id_vendido = productos
+        .create_producto_with_prices("Labial", None, None, None, 5, 50.0, 80.0)
+        .expect("debe crear producto");
+    let id_sin_ventas = productos
+        .create_producto_with_prices("Crema", None, None, None, 3, 40.0, 70.0)
+        .expect("debe crear producto");
+
+    let id_venta = VentaService::new(&db.conn)
+        .create_venta("2026-04-01", "Ana", "Perez", 80.0, &TipoPago::Contado)
+        .expect("debe crear venta");
+    ProductoVendidoService::new(&db.conn)
+        .create_producto_vendido(id_venta, id_vendido, "Labial", 1, 80.0, 80.0)
+        .expect("debe vender");
+
+    let err = productos
+        .eliminar_producto_definitivo(id_sin_ventas)
+        .expect_err("un producto activo no se borra");
+    assert!(err.to_string().contains("Descontinúa 'Crema'"));
+
+    productos.delete_producto(id_vendido).unwrap();
+    productos.delete_producto(id_sin_ventas).unwrap();
+    assert!(productos.list_productos().unwrap().is_empty());
+    assert_eq!(productos.list_productos_descontinuados().unwrap().len(), 2);
+
+    let err = productos
+        .validar_activos_para_venta(&[id_vendido])
+        .expect_err("no se venden productos descontinuados");
+    assert!(err.to_string().contains("'Labial' está descontinuado"));
+
+    let err = productos
+        .eliminar_producto_definitivo(id_vendido)
+        .expect_err("con ventas solo se descontinúa");
+    assert!(err.to_string().contains("tiene ventas registradas"));
+
+    let eliminado = productos.eliminar_producto_definitivo(id_sin_ventas).expect("debe borrar");
+    assert_eq!(eliminado.nombre_producto, "Crema");
+    assert!(matches!(productos.get_producto(id_sin_ventas), Err(rusqlite::Error::QueryReturnedNoRows)));
+
+    productos.reactivar_producto(id_vendido).expect("debe reactivar");
+    assert_eq!(productos.list_productos().unwrap().len(), 1);
+    assert!(productos.list_productos_descontinuados().unwrap().is_empty());
+    productos.validar_activos_para_venta(&[id_vendido]).expect("ya se puede vender");
+}
+
+#[test]
+fn borrado_definitivo_informa_el_historial_y_respeta_promociones() {
+    let db = TestDb::new();
+    let productos = ProductoService::new(&db.conn);
+    let id_producto = productos
+        .create_producto_with_prices("Rubor", None, None, None, 4, 30.0, 60.0)
+        .expect("debe crear producto");
+    productos
+        .ajustar_stock(id_producto, -1, MotivoAjuste::Dano, "")
+        .expect("debe ajustar");
+
+    let promociones = PromocionService::new(&db.conn);
+    let id_promocion = promociones
+        .create_promocion(&PromocionInput {
+            nombre: "10% en rubor".to_string(),
+            tipo: TipoPromocion::Porcentaje,
+            id_producto: Some(id_producto),
+            id_categoria: None,
+            cantidad_lleva: None,
+            cantidad_paga: None,
+            precio_paquete: None,
+            porcentaje: Some(10.0),
+            fecha_inicio: None,
+            fecha_fin: None,
+            activa: true,
+        })
+        .expect("debe crear promoción");
+    productos.delete_producto(id_producto).unwrap();
+
+    let err = productos
+        .impacto_eliminacion_definitiva(id_producto)
+        .expect_err("tiene promociones");
+    assert!(err.to_string().contains("tiene promociones"));
+    let err = productos
+        .eliminar_producto_definitivo(id_producto)
+        .expect_err("tiene promociones");
+    assert!(err.to_string().contains("tiene promociones"));
+
+    promociones.delete_promocion(id_promocion).expect("debe borrar promoción");
+    let impacto = productos.impacto_eliminacion_definitiva(id_producto).expect("debe calcular impacto");
+    assert_eq!(impacto.nombre_producto, "Rubor");
+    assert!(impacto.movimientos >= 1);
+    assert!(impacto.historial_precios >= 1);
+    assert!(impacto.lotes >= 1);
+    assert_eq!(impacto.precios_campana, 0);
+
+    productos.eliminar_producto_definitivo(id_producto).expect("debe borrar");
+}
@@ -0,0 +1,215 @@
+use app_lib::database;
+use app_lib::models::{DescuentoInput, ItemVenta, PromocionInput, TipoDescuento, TipoPago, TipoPromocion};
+use app_lib::repos::producto_vendido_repo::ProductoVendidoRepo;
+use app_lib::repos::venta_repo::VentaRepo;
+use app_lib::services::categoria_service::CategoriaService;
+use app_lib::services::ganancia_service::GananciaService;
+use app_lib::services::producto_service::ProductoService;
+use app_lib::services::producto_vendido_service::ProductoVendidoService;
+use app_lib::services::promocion_service::PromocionService;
+use app_lib::services::venta_service::VentaService;
+use rusqlite::Connection;
+use std::fs;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+struct TestDb {
+    path: PathBuf,
+    conn: Connection,
+}
+
+impl TestDb {
+    fn new() -> Self {
+        let path = std::env::temp_dir().join(format!("inventario-mk-test-{}.sqlite", Uuid::new_v4()));
+        let conn = database::init_db(&path).expect("debe inicializar db de prueba");
+        Self { path, conn }
+    }
+}
+
+impl Drop for TestDb {
+    fn drop(&mut self) {
+        let wal = self.path.with_extension("sqlite-wal");
+        let shm = self.path.with_extension("sqlite-shm");
+        let _ = fs::remove_file(&wal);
+        let _ = fs::remove_file(&shm);
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn promocion(nombre: &str, tipo: TipoPromocion) -> PromocionInput {
+    PromocionInput {
+        nombre: nombre.to_string(),
+        tipo,
+        id_producto: None,
+        id_categoria: None,
+        cantidad_lleva: None,
+        cantidad_paga: None,
+        precio_paquete: None,
+        porcentaje: None,
+        fecha_inicio: None,
+        fecha_fin: None,
+        activa: true,
+    }
+}
+
+fn item(id_producto: i64, cantidad: i64, descuento: Option<DescuentoInput>) -> ItemVenta {
+    ItemVenta {
+        id_producto,
+        nombre_producto: String::new(),
+        cantidad,
+        precio_unitario: None,
+        descuento,
+    }
+}
+
+#[test]
+fn calcula_promociones_y_descuentos_de_linea_y_venta() {
+    let db = TestDb::new();
+    let id_labiales = CategoriaService::new(&db.conn).create_categoria("Labiales").unwrap();
+    let productos = ProductoService::new(&db.conn);
+    let id_labial = productos
+        .create_producto_with_prices("Labial", Some(id_labiales), None, None, 20, 50.0, 100.0)
+        .unwrap();
+    let id_crema = productos
+        .create_producto_with_prices("Crema", None, None, None, 20, 40.0, 80.0)
+        .unwrap();
+    let id_perfume = productos
+        .create_producto_with_prices("Perfume", None, None, None, 20, 200.0, 300.0)
+        .unwrap();
+
+    let promociones = PromocionService::new(&db.conn);
+    let mut dos_por_uno = promocion("2x1 en crema", TipoPromocion::NxM);
+    dos_por_uno.id_producto = Some(id_crema);
+    dos_por_uno.cantidad_lleva = Some(2);
+    dos_por_uno.cantidad_paga = Some(2);
+    let err = promociones.create_promocion(&dos_por_uno).expect_err("debe pagar menos de lo que lleva");
+    assert!(err.to_string().contains("NxM"));
+    dos_por_uno.cantidad_paga = Some(1);
+    let id_2x1 = promociones.create_promocion(&dos_por_uno).unwrap();
+
+    let mut paquete = promocion("3 labiales por $250", TipoPromocion::PrecioPaquete);
+    paquete.id_categoria = Some(id_labiales);
+    paquete.cantidad_lleva = Some(3);
+    paquete.precio_paquete = Some(250.0);
+    promociones.create_promocion(&paquete).unwrap();
+
+    // En labiales el 20% (80 de 400) gana al paquete (50 por 3 piezas)
+    let mut porcentaje = promocion("20% en labiales", TipoPromocion::Porcentaje);
+    porcentaje.id_categoria = Some(id_labiales);
+    porcentaje.porcentaje = Some(20.0);
+    porcentaje.fecha_inicio = Some("2026-05-01".to_string());
+    porcentaje.fecha_fin = Some("2026-05-31".to_string());
+    let id_porcentaje = promociones.create_promocion(&porcentaje).unwrap();
+
+    let items = vec![
+        item(id_labial, 4, None),
+        item(id_crema, 5, Some(DescuentoInput { tipo: TipoDescuento::Monto, valor: 20.0 })),
+        item(id_perfume, 1, Some(DescuentoInput { tipo: TipoDescuento::Porcentaje, valor: 10.0 })),
+    ];
+    let precios = [100.0, 80.0, 300.0];
+    let descuento_venta = DescuentoInput { tipo: TipoDescuento::Porcentaje, valor: 5.0 };
+
+    let calculo = promociones
+        .calcular_venta("2026-05-10", &items, &precios, Some(&descuento_venta))
+        .unwrap();
+    assert_eq!(calculo.lineas[0].id_promocion, Some(id_porcentaje));
+    assert_eq!(calculo.lineas[0].descuento, 80.0);
+    assert_eq!(calculo.lineas[1].id_promocion, Some(id_2x1));
+    assert_eq!(calculo.lineas[1].descuento_promocion, 160.0);
+    assert_eq!(calculo.lineas[1].descuento, 180.0);
+    assert_eq!(calculo.lineas[1].subtotal, 220.0);
+    assert_eq!(calculo.lineas[2].descuento, 30.0);
+    assert_eq!(calculo.subtotal_bruto, 1100.0);
+    assert_eq!(calculo.descuento_lineas, 290.0);
+    assert_eq!(calculo.descuento_venta, 40.5);
+    assert_eq!(calculo.total, 769.5);
+    assert_eq!(calculo.promociones.len(), 2);
+
+    // Fuera del periodo del porcentaje aplica el paquete
+    let calculo = promociones.calcular_venta("2026-06-10", &items[..1], &precios[..1], None).unwrap();
+    assert_eq!(calculo.lineas[0].descuento, 50.0);
+    assert_eq!(calculo.total, 350.0);
+
+    let excesivo = [item(id_perfume, 1, Some(DescuentoInput { tipo: TipoDescuento::Monto, valor: 301.0 }))];
+    let err = promociones
+        .calcular_venta("2026-06-10", &excesivo, &[300.0], None)
+        .expect_err("el descuento no puede superar el importe");
+    assert!(err.to_string().contains("El descuento de 'Perfume'"));
+}
+
+#[test]
+fn reporte_de_ganancias_muestra_bruto_descuento_y_neto() {
+    let db = TestDb::new();
+    let id_crema = ProductoService::new(&db.conn)
+        .create_producto_with_prices("Crema", None, None, None, 20, 40.0, 80.0)
+        .unwrap();
+    let promociones = PromocionService::new(&db.conn);
+    let mut dos_por_uno = promocion("2x1 en crema", TipoPromocion::NxM);
+    dos_por_uno.id_producto = Some(id_crema);
+    dos_por_uno.cantidad_lleva = Some(2);
+    dos_por_uno.cantidad_paga = Some(1);
+    let id_2x1 = promociones.create_promocion(&dos_por_uno).unwrap();
+
+    let descuento_venta = DescuentoInput { tipo: TipoDescuento::Monto, valor: 10.0 };
+    let calculo = promociones
+        .calcular_venta("2026-05-10", &[item(id_crema, 4, None)], &[80.0], Some(&descuento_venta))
+        .unwrap();
+    assert_eq!(calculo.total, 150.0);
+
+    // Mismo registro que hace create_venta_completa
+    let id_venta = VentaService::new(&db.conn)
+        .create_venta("2026-05-10", "Ana", "Perez", calculo.total, &TipoPago::Contado)
+        .unwrap();
+    VentaRepo { conn: &db.conn }.set_descuento(id_venta, &calculo).unwrap();
+    let linea = &calculo.lineas[0];
+    let id_linea = ProductoVendidoService::new(&db.conn)
+        .create_producto_vendido(id_venta, id_crema, "Crema", 4, linea.precio_unitario, linea.subtotal)
+        .unwrap();
+    ProductoVendidoRepo { conn: &db.conn }.set_descuento(id_linea, linea).unwrap();
+
+    let guardada = ProductoVendidoService::new(&db.conn).get_producto_vendido(id_linea).unwrap();
+    assert_eq!(guardada.descuento, 160.0);
+    assert_eq!(guardada.id_promocion, Some(id_2x1));
+
+    let reporte = GananciaService::new(&db.conn)
+        .get_reporte("2026-05-01", "2026-05-31", None)
+        .unwrap();
+    assert_eq!(reporte.bruto, 320.0);
+    assert_eq!(reporte.descuento, 170.0);
+    assert_eq!(reporte.ingreso, 150.0);
+    assert_eq!(reporte.costo, 160.0);
+    assert_eq!(reporte.por_producto[0].descuento, 170.0);
+    assert_eq!(reporte.por_producto[0].ingreso, 150.0);
+
+    let err = promociones.delete_promocion(id_2x1).expect_err("ya se aplicó");
+    assert!(err.to_string().contains("desactívala"));
+}
+
+#[test]
+fn promocion_de_categoria_aplica_a_sus_subcategorias() {
+    let db = TestDb::new();
+    let categorias = CategoriaService::new(&db.conn);
+    let id_maquillaje = categorias.create_categoria("Maquillaje").unwrap();
+    let id_labios = categorias.create_subcategoria("Labios", id_maquillaje).unwrap();
+    let id_cuidado = categorias.create_categoria("Cuidado").unwrap();
+    let productos = ProductoService::new(&db.conn);
+    let id_labial = productos
+        .create_producto_with_prices("Labial", Some(id_labios), None, None, 10, 50.0, 100.0)
+        .unwrap();
+    let id_crema = productos
+        .create_producto_with_prices("Crema", Some(id_cuidado), None, None, 10, 40.0, 80.0)
+        .unwrap();
+
+    let promociones = PromocionService::new(&db.conn);
+    let mut descuento = promocion("10% en maquillaje", TipoPromocion::Porcentaje);
+    descuento.id_categoria = Some(id_maquillaje);
+    descuento.porcentaje = Some(10.0);
+    let id_promocion = promociones.create_promocion(&descuento).unwrap();
+
+    let items = [item(id_labial, 1, None), item(id_crema, 1, None)];
+    let calculo = promociones.calcular_venta("2026-05-10", &items, &[100.0, 80.0], None).unwrap();
+    assert_eq!(calculo.lineas[0].id_promocion, Some(id_promocion));
+    assert_eq!(calculo.lineas[0].descuento, 10.0);
+    assert_eq!(calculo.lineas[1].id_promocion, None);
+    assert_eq!(calculo.total, 170.0);
+}
@@ -0,0 +1,146 @@
+use app_lib::database;
+use app_lib::models::{TipoPago, VarianteInput};
+use app_lib::repos::producto_vendido_repo::ProductoVendidoRepo;
+use app_lib::services::producto_service::ProductoService;
+use app_lib::services::producto_vendido_service::ProductoVendidoService;
+use app_lib::services::variante_service::VarianteService;
+use app_lib::services::venta_service::VentaService;
+use rusqlite::Connection;
+use std::fs;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+struct TestDb {
+    path: PathBuf,
+    conn: Connection,
+}
+
+impl TestDb {
+    fn new() -> Self {
+        let path = std::env::temp_dir().join(format!("inventario-mk-test-{}.sqlite", Uuid::new_v4()));
+        let conn = database::init_db(&path).expect("debe inicializar db de prueba");
+        Self { path, conn }
+    }
+}
+
+impl Drop for TestDb {
+    fn drop(&mut self) {
+        let wal = self.path.with_extension("sqlite-wal");
+        let shm = self.path.with_extension("sqlite-shm");
+        let _ = fs::remove_file(&wal);
+        let _ = fs::remove_file(&shm);
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn hoy(conn: &Connection) -> String {
+    conn.query_row("SELECT DATE('now','localtime')", [], |row| row.get(0)).unwrap()
+}
+
+#[test]
+fn variantes_heredan_precios_del_padre_salvo_precio_propio() {
+    let db = TestDb::new();
+    let productos = ProductoService::new(&db.conn);
+    let variantes = VarianteService::new(&db.conn);
+
+    let id_padre = productos
+        .create_producto_with_prices("Labial Matte", None, None, None, 0, 50.0, 80.0)
+        .expect("debe crear padre");
+
+    let id_rojo = variantes
+        .create_variante(
+            id_padre,
+            &VarianteInput { tono: Some("Rojo".to_string()), ..Default::default() },
+            4,
+        )
+        .expect("debe crear variante");
+    let id_nude = variantes
+        .create_variante(
+            id_padre,
+            &VarianteInput {
+                tono: Some("Nude".to_string()),
+                precio_publico: Some(95.0),
+                ..Default::default()
+            },
+            6,
+        )
+        .expect("debe crear variante con precio propio");
+
+    let rojo = productos.get_producto(id_rojo).unwrap();
+    assert_eq!(rojo.nombre_producto, "Labial Matte - Rojo");
+    assert_eq!(rojo.id_producto_padre, Some(id_padre));
+    assert_eq!(rojo.precio_publico, 80.0);
+    assert!(!rojo.precio_propio);
+    assert!(productos.get_producto(id_nude).unwrap().precio_propio);
+
+    let err = variantes
+        .create_variante(
+            id_padre,
+            &VarianteInput { tono: Some(" rojo ".to_string()), ..Default::default() },
+            0,
+        )
+        .expect_err("no debe duplicar atributos");
+    assert!(err.to_string().contains("ya tiene una variante"));
+
+    // Subir el precio del padre solo alcanza a las variantes sin precio propio
+    let mut padre = productos.get_producto(id_padre).unwrap();
+    padre.precio_publico = 90.0;
+    padre.precio = 90.0;
+    productos.update_producto(&padre).expect("debe actualizar padre");
+
+    assert_eq!(productos.get_producto(id_rojo).unwrap().precio_publico, 90.0);
+    assert_eq!(productos.get_producto(id_nude).unwrap().precio_publico, 95.0);
+
+    let grupos = variantes.list_productos_agrupados().expect("debe agrupar");
+    assert_eq!(grupos.len(), 1);
+    assert_eq!(grupos[0].producto.id_producto, id_padre);
+    assert_eq!(grupos[0].variantes.len(), 2);
+    assert_eq!(grupos[0].stock_total, 10);
+}
+
+#[test]
+fn resumen_de_ventas_suma_variantes_en_el_padre() {
+    let db = TestDb::new();
+    let productos = ProductoService::new(&db.conn);
+    let variantes = VarianteService::new(&db.conn);
+
+    let id_padre = productos
+        .create_producto_with_prices("Perfume", None, None, None, 0, 100.0, 150.0)
+        .expect("debe crear padre");
+    let id_50 = variantes
+        .create_variante(id_padre, &VarianteInput { tamano: Some("50 ml".to_string()), ..Default::default() }, 5)
+        .expect("debe crear variante");
+    let id_100 = variantes
+        .create_variante(
+            id_padre,
+            &VarianteInput {
+                tamano: Some("100 ml".to_string()),
+                precio_consultora: Some(180.0),
+                precio_publico: Some(250.0),
+                ..Default::default()
+            },
+            5,
+        )
+        .expect("debe crear variante");
+
+    let id_venta = VentaService::new(&db.conn)
+        .create_venta(&hoy(&db.conn), "Ana", "Perez", 550.0, &TipoPago::Contado)
+        .expect("debe crear venta");
+    let items = ProductoVendidoService::new(&db.conn);
+    items
+        .create_producto_vendido(id_venta, id_50, "Perfume - 50 ml", 2, 150.0, 300.0)
+        .expect("debe registrar item");
+    items
+        .create_producto_vendido(id_venta, id_100, "Perfume - 100 ml", 1, 250.0, 250.0)
+        .expect("debe registrar item");
+
+    let repo = ProductoVendidoRepo { conn: &db.conn };
+    let por_variante = repo.resumen_por_producto(false, None).expect("debe resumir");
+    assert_eq!(por_variante.len(), 2);
+
+    let por_padre = repo.resumen_por_producto(true, None).expect("debe resumir");
+    assert_eq!(por_padre.len(), 1);
+    assert_eq!(por_padre[0].nombre, "Perfume");
+    assert_eq!(por_padre[0].unidades, 3);
+    assert_eq!(por_padre[0].ingreso, 550.0);
+}
@@ -1,3 +1,6 @@
+// Motivos de ajuste que acepta `ajustar_stock` (MotivoAjuste en el backend)
+const MOTIVOS_AJUSTE = ['reconteo', 'dano', 'regalo', 'uso_personal', 'correccion', 'caducado'];
+
 export default function EditProductModal({
   open,
   product,
@@ -57,6 +60,17 @@ export default function EditProductModal({
             <input name="stock" value={formData.stock} onChange={onFormChange} type="number" min="0" />
           </label>
 
+          {Number(formData.stock) !== Number(product.stock) && (
+            <label className="products-modal-field">
+              <span>{t('dashboard_edit_stock_reason')}</span>
+              <select name="motivo_ajuste" value={formData.motivo_ajuste} onChange={onFormChange}>
+                {MOTIVOS_AJUSTE.map((motivo) => (
+                  <option key={motivo} value={motivo}>{t(`stock_reason_${motivo}`)}</option>
+                ))}
+              </select>
+            </label>
+          )}
+
           <label className="products-modal-field">
             <span>{t('products_price_consultora_label')}</span>
             <input name="precio_consultora" value={formData.precio_consultora}
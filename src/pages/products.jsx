@@ -43,7 +43,7 @@ export default function Products() {
   const [selectedProduct, setSelectedProduct] = useState(null);
   const [isEditModalOpen, setIsEditModalOpen] = useState(false);
   const [editForm, setEditForm] = useState({
-    nombre_producto: '', id_categoria: '', stock: '', precio_consultora: '', precio_publico: ''
+    nombre_producto: '', id_categoria: '', stock: '', motivo_ajuste: 'reconteo', precio_consultora: '', precio_publico: ''
   });
   const [editImageFile, setEditImageFile] = useState(null);
   const [editImagePreview, setEditImagePreview] = useState(null);
@@ -281,6 +281,7 @@ export default function Products() {
       nombre_producto: product.nombre_producto ?? '',
       id_categoria: product.id_categoria ?? '',
       stock: product.stock ?? '',
+      motivo_ajuste: 'reconteo',
       precio_consultora: product.precio_consultora ?? product.precio ?? '',
       precio_publico: product.precio_publico ?? product.precio ?? ''
     });
@@ -352,13 +353,34 @@ export default function Products() {
         producto: updatedProduct, imageBytes, imageExt,
         miniaturaBase64: editMiniaturaBase64, precioConsultora: precio_consultora, precioPublico: precio_publico
       });
-      setProducts((prev) => prev.map((p) => p.id_producto === updatedProduct.id_producto ? updatedProduct : p));
-      closeEditModal();
-      toast.success(t('toast_product_updated'));
     } catch (error) {
       console.error('Error al actualizar producto:', error);
       toast.error(t('toast_product_update_error'));
+      return;
     }
+
+    // update_producto ya no modifica el stock; el cambio se registra como ajuste con el motivo elegido
+    const stockDelta = stock - Number(selectedProduct.stock);
+    if (stockDelta !== 0) {
+      try {
+        await invoke('ajustar_stock', {
+          idProducto: updatedProduct.id_producto, delta: stockDelta, motivo: editForm.motivo_ajuste, nota: 'Edición de producto'
+        });
+      } catch (error) {
+        // Lo demás ya quedó guardado; el modal sigue abierto para reintentar solo el ajuste
+        console.error('Error al ajustar stock:', error);
+        const guardado = await invoke('get_producto', { id: updatedProduct.id_producto })
+          .catch(() => ({ ...updatedProduct, stock: selectedProduct.stock }));
+        setProducts((prev) => prev.map((p) => p.id_producto === guardado.id_producto ? guardado : p));
+        setSelectedProduct(guardado);
+        setEditImageFile(null);
+        toast.error(`${t('toast_stock_adjust_error')}: ${error}`);
+        return;
+      }
+    }
+    setProducts((prev) => prev.map((p) => p.id_producto === updatedProduct.id_producto ? updatedProduct : p));
+    closeEditModal();
+    toast.success(t('toast_product_updated'));
   };
 
   // ══════════════════════════════════════════
@@ -48,6 +48,42 @@ export default function Sales() {
     loadProducts();
   }, []);
 
+  // El carrito muestra el precio de la campaña vigente en la fecha de la venta
+  const cartProductIds = cartItems.map((item) => item.id_producto).join(',');
+
+  useEffect(() => {
+    if (!isTauri() || !cartProductIds) {
+      return;
+    }
+
+    let cancelled = false;
+    const loadCampaignPrices = async () => {
+      const ids = cartProductIds.split(',').map(Number);
+      const prices = await Promise.all(
+        ids.map((idProducto) =>
+          invoke('get_precio_vigente', { idProducto, fecha: saleDate })
+            .then((precio) => [idProducto, Number(precio.precio_publico)])
+            .catch(() => [idProducto, null])
+        )
+      );
+      if (cancelled) return;
+
+      const byProduct = new Map(prices.filter(([, precio]) => Number.isFinite(precio)));
+      setCartItems((prev) =>
+        prev.map((item) => {
+          const precio = byProduct.get(item.id_producto);
+          if (precio === undefined || precio === item.precio_publico) return item;
+          return { ...item, precio_unitario: precio, precio_publico: precio };
+        })
+      );
+    };
+
+    loadCampaignPrices();
+    return () => {
+      cancelled = true;
+    };
+  }, [cartProductIds, saleDate]);
+
   const filteredProducts = useMemo(() => {
     return fuzzyFilterByName(products, searchTerm, (product) => product.nombre_producto);
   }, [products, searchTerm]);
@@ -176,7 +212,8 @@ export default function Sales() {
         id_producto: item.id_producto,
         nombre_producto: item.nombre_producto,
         cantidad: item.cantidad,
-        precio_unitario: item.precio_publico,
+        // Sin precio capturado el backend usa el de la campaña vigente en la fecha de la venta
+        precio_unitario: null,
       })),
     };
 
@@ -55,6 +55,13 @@ const translations = {
     dashboard_edit_category: 'Categoría',
     dashboard_edit_no_category: 'Sin categoría',
     dashboard_edit_stock: 'Stock',
+    dashboard_edit_stock_reason: 'Motivo del ajuste de stock',
+    stock_reason_reconteo: 'Reconteo',
+    stock_reason_dano: 'Daño',
+    stock_reason_regalo: 'Regalo',
+    stock_reason_uso_personal: 'Uso personal',
+    stock_reason_correccion: 'Corrección',
+    stock_reason_caducado: 'Caducado',
     dashboard_edit_price: 'Precio',
     dashboard_edit_cancel: 'Cancelar',
     dashboard_edit_save: 'Guardar cambios',
@@ -70,6 +77,7 @@ const translations = {
     toast_tauri_unavailable: 'Backend Tauri no disponible. Ejecuta tauri dev.',
     toast_product_updated: 'Producto actualizado.',
     toast_product_update_error: 'No se pudo actualizar el producto.',
+    toast_stock_adjust_error: 'El producto se guardó, pero no se pudo ajustar el stock',
     toast_product_deleted: 'Producto descontinuado exitosamente.',
     toast_product_delete_error: 'No se pudo descontinuar el producto.',
 
@@ -463,6 +471,13 @@ const translations = {
     dashboard_edit_category: 'Category',
     dashboard_edit_no_category: 'No category',
     dashboard_edit_stock: 'Stock',
+    dashboard_edit_stock_reason: 'Stock adjustment reason',
+    stock_reason_reconteo: 'Recount',
+    stock_reason_dano: 'Damaged',
+    stock_reason_regalo: 'Gift',
+    stock_reason_uso_personal: 'Personal use',
+    stock_reason_correccion: 'Correction',
+    stock_reason_caducado: 'Expired',
     dashboard_edit_price: 'Price',
     dashboard_edit_cancel: 'Cancel',
     dashboard_edit_save: 'Save changes',
@@ -478,6 +493,7 @@ const translations = {
     toast_tauri_unavailable: 'Tauri backend not available. Run tauri dev.',
     toast_product_updated: 'Product updated.',
     toast_product_update_error: 'Could not update the product.',
+    toast_stock_adjust_error: 'The product was saved, but the stock could not be adjusted',
     toast_product_deleted: 'Product discontinued successfully.',
     toast_product_delete_error: 'Could not discontinue the product.',
 